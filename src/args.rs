@@ -0,0 +1,314 @@
+use std::path::Path;
+
+use cerussite::diagnostic::Format;
+use cerussite::message::Language;
+use cerussite::warning;
+
+pub const USAGE: &str = "\
+usage: cerussite [options] <file>...
+
+  <file> is a C source file, or `-` for the standard input.  assembly (`.s`) files are passed
+  to the assembler, and the other files to the linker.
+
+options:
+  -o <file>                   write the output to <file>, or to stdout for `-`
+  --emit=<kind>               what to output: tokens, ast, ast-json, ir, asm or obj
+  -E                          only preprocess
+  -S                          compile to assembly (<file>.s)
+  -c                          compile and assemble (<file>.o)
+  -I<dir>, -D<name>[=<value>], -U<name>
+                              passed to the preprocessor
+  -l<lib>, -L<dir>, -Wl,<arg> passed to the linker
+  -std=<standard>             the C standard, passed to the preprocessor
+  -O<level>                   the optimization level (0-3), passed to llc
+  -W<warning>, -Wno-<warning> enable or disable a warning
+  -Werror                     make warnings errors
+  --diagnostics-format=<fmt>  text (default) or json
+  --lang=<lang>               the language of diagnostics: en or ja (default: from LANG)
+  --apply-fixits              rewrite <file> with the suggested fixes
+  --verify-ir                 check the generated IR (always done in debug builds)
+  --explain <code>            explain an error code such as E0401
+  --help                      print this message
+  --version                   print the version
+
+  without -E, -S, -c or --emit, a single C file is compiled to LLVM IR, which is written to the
+  standard output or to a `-o` file named `*.ll`.  otherwise, the files are linked into an
+  executable (`a.out` by default).
+
+environment variables:
+  CERUSSITE_CPP, CERUSSITE_LLC, CERUSSITE_AS, CERUSSITE_LD
+                              the preprocessor (default: cpp), llc (default: llc), the
+                              assembler (default: as) and the linker (default: cc)";
+
+/// what the compiler outputs (`--emit=`, `-E`, `-S`, `-c`)
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Emit {
+    /// the output of the preprocessor (`-E`)
+    Preprocessed,
+
+    Tokens,
+
+    /// the syntax tree as an S-expression
+    Ast,
+
+    /// the syntax tree as JSON, with the spans and the types of the expressions
+    AstJson,
+
+    Ir,
+    Asm,
+    Obj,
+
+    /// an executable linked from all the inputs
+    Exe,
+}
+
+impl Emit {
+    pub fn from_name(name: &str) -> Option<Emit> {
+        match name {
+            "tokens" => Some(Emit::Tokens),
+            "ast" => Some(Emit::Ast),
+            "ast-json" => Some(Emit::AstJson),
+            "ir" => Some(Emit::Ir),
+            "asm" => Some(Emit::Asm),
+            "obj" => Some(Emit::Obj),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Input {
+    Stdin,
+    File(String),
+}
+
+impl Input {
+    /// the name shown in diagnostics
+    pub fn name(&self) -> &str {
+        match self {
+            Input::Stdin => "<stdin>",
+            Input::File(name) => name,
+        }
+    }
+
+    /// the extension of the file, which decides how it is processed
+    pub fn extension(&self) -> Option<&str> {
+        match self {
+            Input::Stdin => Some("c"),
+            Input::File(name) => Path::new(name).extension().and_then(|ext| ext.to_str()),
+        }
+    }
+}
+
+/// the command line arguments
+#[derive(Debug, Default)]
+pub struct Args {
+    pub inputs: Vec<Input>,
+
+    /// `-o`
+    pub output: Option<String>,
+
+    /// `--emit=`, `-E`, `-S` or `-c`.  inferred by `emit()` if `None`.
+    pub emit: Option<Emit>,
+
+    /// whether the outputs are named after the inputs as cc does (`-S`, `-c`)
+    pub cc_outputs: bool,
+
+    pub opt_level: u32,
+    pub warnings: warning::Options,
+    pub format: Format,
+
+    /// `--lang=`.  decided by `LANG` if `None`.
+    pub language: Option<Language>,
+
+    /// `-I`, `-D`, `-U` and `-std=`, in the order given
+    pub cpp_args: Vec<String>,
+
+    /// `-l`, `-L` and `-Wl,`, in the order given
+    pub link_args: Vec<String>,
+
+    /// the options not understood, which are ignored with a warning
+    pub unknown: Vec<String>,
+
+    pub apply_fixits: bool,
+    pub verify_ir: bool,
+    pub explain: Option<String>,
+    pub help: bool,
+    pub version: bool,
+}
+
+impl Args {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if let Some(output) = arg.strip_prefix("-o") {
+                parsed.output = Some(value(&mut args, "-o", output)?);
+            } else if let Some(name) = arg.strip_prefix("--emit=") {
+                let emit = Emit::from_name(name).ok_or(format!("unknown emit kind `{}`", name))?;
+                parsed.emit = Some(emit);
+                parsed.cc_outputs = false;
+            } else if arg == "-E" || arg == "-S" || arg == "-c" {
+                parsed.emit = Some(match &*arg {
+                    "-E" => Emit::Preprocessed,
+                    "-S" => Emit::Asm,
+                    _ => Emit::Obj,
+                });
+                parsed.cc_outputs = true;
+            } else if arg.starts_with("-I") || arg.starts_with("-D") || arg.starts_with("-U") {
+                let (option, attached) = arg.split_at(2);
+                let value = value(&mut args, option, attached)?;
+                parsed.cpp_args.push(format!("{}{}", option, value));
+            } else if arg.starts_with("-std=") {
+                parsed.cpp_args.push(arg);
+            } else if arg.starts_with("-l") || arg.starts_with("-L") {
+                let (option, attached) = arg.split_at(2);
+                let value = value(&mut args, option, attached)?;
+                parsed.link_args.push(format!("{}{}", option, value));
+            } else if arg.starts_with("-Wl,") {
+                parsed.link_args.push(arg);
+            } else if let Some(level) = arg.strip_prefix("-O") {
+                parsed.opt_level = match level {
+                    "" | "g" => 1,
+                    "s" | "z" => 2,
+                    "fast" => 3,
+                    _ => match level.parse::<u32>() {
+                        Ok(level) => level.min(3),
+                        Err(_) => {
+                            parsed.unknown.push(arg);
+                            continue;
+                        }
+                    },
+                };
+            } else if arg.starts_with("-W") {
+                if !parsed.warnings.parse_flag(&arg) {
+                    parsed.unknown.push(arg);
+                }
+            } else if let Some(name) = arg.strip_prefix("--diagnostics-format=") {
+                let format = Format::from_name(name)
+                    .ok_or(format!("unknown diagnostics format `{}`", name))?;
+                parsed.format = format;
+            } else if let Some(name) = arg.strip_prefix("--lang=") {
+                let language =
+                    Language::from_name(name).ok_or(format!("unknown language `{}`", name))?;
+                parsed.language = Some(language);
+            } else if arg == "--apply-fixits" {
+                parsed.apply_fixits = true;
+            } else if arg == "--verify-ir" {
+                parsed.verify_ir = true;
+            } else if arg == "--explain" {
+                parsed.explain = Some(value(&mut args, "--explain", "")?);
+            } else if arg == "--help" {
+                parsed.help = true;
+            } else if arg == "--version" {
+                parsed.version = true;
+            } else if arg == "-" {
+                parsed.inputs.push(Input::Stdin);
+            } else if arg.starts_with('-') {
+                parsed.unknown.push(arg);
+            } else {
+                parsed.inputs.push(Input::File(arg));
+            }
+        }
+
+        if parsed.apply_fixits && parsed.inputs.contains(&Input::Stdin) {
+            return Err("cannot apply fix-its to the standard input".to_owned());
+        }
+        if parsed.output.is_some() && parsed.inputs.len() > 1 && parsed.cc_outputs {
+            match parsed.emit {
+                Some(Emit::Asm) | Some(Emit::Obj) => {
+                    return Err(
+                        "cannot specify `-o` with `-S` or `-c` and multiple files".to_owned()
+                    )
+                }
+                _ => {}
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// what to output.  without `--emit`, `-E`, `-S` or `-c`, a C file is compiled to LLVM IR,
+    /// unless the inputs or `-o` ask for linking as cc does.
+    pub fn emit(&self) -> Emit {
+        if let Some(emit) = self.emit {
+            return emit;
+        }
+        let single_source = match self.inputs[..] {
+            [ref input] => input.extension() == Some("c"),
+            _ => false,
+        };
+        let ir_output = self
+            .output
+            .as_ref()
+            .is_none_or(|output| output == "-" || output.ends_with(".ll"));
+        if single_source && ir_output {
+            Emit::Ir
+        } else {
+            Emit::Exe
+        }
+    }
+}
+
+/// the value of `option`, attached to it (`-Idir`) or in the next argument (`-I dir`)
+fn value<I: Iterator<Item = String>>(
+    args: &mut I,
+    option: &str,
+    attached: &str,
+) -> Result<String, String> {
+    match attached {
+        "" => args
+            .next()
+            .ok_or(format!("missing argument after `{}`", option)),
+        _ => Ok(attached.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_args() {
+        let args = parse(&["-o", "a.s", "--emit=asm", "-O2", "-Wno-overflow", "a.c"]).unwrap();
+        assert_eq!(args.inputs, [Input::File("a.c".to_owned())]);
+        assert_eq!(args.output, Some("a.s".to_owned()));
+        assert_eq!(args.emit(), Emit::Asm);
+        assert_eq!(args.opt_level, 2);
+
+        let args = parse(&["-", "-oa.ll"]).unwrap();
+        assert_eq!(args.inputs, [Input::Stdin]);
+        assert_eq!(args.output, Some("a.ll".to_owned()));
+        assert_eq!(args.emit(), Emit::Ir);
+
+        assert!(parse(&["--help"]).unwrap().help);
+        assert!(parse(&["--verify-ir", "a.c"]).unwrap().verify_ir);
+        assert!(parse(&["--emit=exe", "a.c"]).is_err());
+        assert!(parse(&["-o"]).is_err());
+        assert!(parse(&["--apply-fixits", "-"]).is_err());
+    }
+
+    #[test]
+    fn cc_args() {
+        let args = parse(&[
+            "-c", "-std=c11", "-I", "include", "-DN=1", "-g", "-Wall", "-fPIC", "a.c", "b.c",
+        ])
+        .unwrap();
+        assert_eq!(args.emit(), Emit::Obj);
+        assert!(args.cc_outputs);
+        assert_eq!(args.cpp_args, ["-std=c11", "-Iinclude", "-DN=1"]);
+        assert_eq!(args.unknown, ["-g", "-fPIC"]);
+        assert_eq!(args.inputs.len(), 2);
+
+        let args = parse(&["a.o", "b.c", "-lm", "-o", "prog"]).unwrap();
+        assert_eq!(args.emit(), Emit::Exe);
+        assert_eq!(args.link_args, ["-lm"]);
+        assert_eq!(parse(&["a.c", "-o", "prog"]).unwrap().emit(), Emit::Exe);
+        assert_eq!(parse(&["a.c", "-o", "-"]).unwrap().emit(), Emit::Ir);
+        assert_eq!(parse(&["-O4", "a.c"]).unwrap().opt_level, 3);
+        assert!(parse(&["-c", "a.c", "b.c", "-o", "a.o"]).is_err());
+    }
+}
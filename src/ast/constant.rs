@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+
+use token::Token;
+use types::{FloatType, IntType, Type};
+
+/// the value of a constant expression.  integers are kept as their bit pattern, truncated to
+/// the width of their type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constant {
+    Int(u64, IntType),
+    Float(f64, FloatType),
+}
+
+fn truncate(value: u64, ty: IntType) -> u64 {
+    value & (u64::MAX >> (64 - ty.bits()))
+}
+
+/// the value of `bits` (a bit pattern of type `ty`) as a signed integer
+fn sign_extend(bits: u64, ty: IntType) -> i64 {
+    let shift = 64 - ty.bits();
+    ((bits << shift) as i64) >> shift
+}
+
+impl Constant {
+    pub fn ty(&self) -> Type {
+        match *self {
+            Constant::Int(_, ty) => Type::Int(ty),
+            Constant::Float(_, ty) => Type::Float(ty),
+        }
+    }
+
+    /// whether this is a negative value of a signed or floating type
+    pub fn is_negative(self) -> bool {
+        match self {
+            Constant::Int(value, ty) => ty.signed && sign_extend(value, ty) < 0,
+            Constant::Float(value, _) => value < 0.0,
+        }
+    }
+
+    fn as_f64(self) -> f64 {
+        match self {
+            Constant::Int(value, ty) if ty.signed => sign_extend(value, ty) as f64,
+            Constant::Int(value, _) => value as f64,
+            Constant::Float(value, _) => value,
+        }
+    }
+
+    fn as_u64(self) -> u64 {
+        match self {
+            Constant::Int(value, ty) if ty.signed => sign_extend(value, ty) as u64,
+            Constant::Int(value, _) => value,
+            Constant::Float(value, _) if value < 0.0 => value as i64 as u64,
+            Constant::Float(value, _) => value as u64,
+        }
+    }
+
+    /// converts this constant as if by assignment to an object of type `to`.  returns None if
+    /// `to` is not an arithmetic type.
+    pub fn convert(self, to: &Type) -> Option<Constant> {
+        match *to {
+            Type::Int(ty) => Some(Constant::Int(truncate(self.as_u64(), ty), ty)),
+            Type::Float(FloatType::Float) => Some(Constant::Float(
+                f64::from(self.as_f64() as f32),
+                FloatType::Float,
+            )),
+            Type::Float(FloatType::Double) => {
+                Some(Constant::Float(self.as_f64(), FloatType::Double))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn negate(self) -> Constant {
+        match self {
+            Constant::Int(value, ty) => Constant::Int(truncate(value.wrapping_neg(), ty), ty),
+            Constant::Float(value, ty) => Constant::Float(-value, ty),
+        }
+    }
+
+    /// evaluates `lhs op rhs` for an arithmetic or comparison operator.  returns None if the
+    /// result is undefined (division by zero) or the operands are invalid.
+    pub fn binary(op: Token, lhs: Constant, rhs: Constant) -> Option<Constant> {
+        let common = lhs.ty().common_checked(&rhs.ty())?;
+        let (lhs, rhs) = (lhs.convert(&common)?, rhs.convert(&common)?);
+
+        let ordering = lhs.partial_cmp_value(rhs);
+        let compared = match op {
+            Token::OpEq => Some(ordering == Some(Ordering::Equal)),
+            Token::OpNe => Some(ordering != Some(Ordering::Equal)),
+            Token::OpLt => Some(ordering == Some(Ordering::Less)),
+            Token::OpGt => Some(ordering == Some(Ordering::Greater)),
+            Token::OpLe => Some(matches!(ordering, Some(Ordering::Less | Ordering::Equal))),
+            Token::OpGe => Some(matches!(
+                ordering,
+                Some(Ordering::Greater | Ordering::Equal)
+            )),
+            _ => None,
+        };
+        if let Some(result) = compared {
+            return Some(Constant::Int(result as u64, IntType::INT));
+        }
+
+        match (lhs, rhs) {
+            (Constant::Int(lhs, ty), Constant::Int(rhs, _)) => {
+                let value = match op {
+                    Token::OpAdd => lhs.wrapping_add(rhs),
+                    Token::OpSub => lhs.wrapping_sub(rhs),
+                    Token::OpMul => lhs.wrapping_mul(rhs),
+                    Token::OpDiv | Token::OpRem if rhs == 0 => return None,
+                    Token::OpDiv if ty.signed => {
+                        sign_extend(lhs, ty).wrapping_div(sign_extend(rhs, ty)) as u64
+                    }
+                    Token::OpRem if ty.signed => {
+                        sign_extend(lhs, ty).wrapping_rem(sign_extend(rhs, ty)) as u64
+                    }
+                    Token::OpDiv => lhs / rhs,
+                    Token::OpRem => lhs % rhs,
+                    _ => return None,
+                };
+                Some(Constant::Int(truncate(value, ty), ty))
+            }
+            (Constant::Float(lhs, ty), Constant::Float(rhs, _)) => {
+                let value = match op {
+                    Token::OpAdd => lhs + rhs,
+                    Token::OpSub => lhs - rhs,
+                    Token::OpMul => lhs * rhs,
+                    Token::OpDiv => lhs / rhs,
+                    _ => return None,
+                };
+                Constant::Float(value, ty).convert(&Type::Float(ty))
+            }
+            _ => None,
+        }
+    }
+
+    /// whether `lhs op rhs` overflows when the operands are converted to a signed integer type.
+    /// the result is undefined then, which `binary` wraps around.
+    pub fn overflows(op: Token, lhs: Constant, rhs: Constant) -> bool {
+        let ty = match lhs.ty().common_checked(&rhs.ty()) {
+            Some(Type::Int(ty)) if ty.signed => ty,
+            _ => return false,
+        };
+        let value = |constant: Constant| match constant.convert(&Type::Int(ty)) {
+            Some(Constant::Int(value, _)) => i128::from(sign_extend(value, ty)),
+            _ => unreachable!(),
+        };
+        let (lhs, rhs) = (value(lhs), value(rhs));
+        let exact = match op {
+            Token::OpAdd => lhs + rhs,
+            Token::OpSub => lhs - rhs,
+            Token::OpMul => lhs * rhs,
+            Token::OpDiv if rhs != 0 => lhs / rhs,
+            _ => return false,
+        };
+        let max = i128::from(ty.max_value());
+        exact > max || exact < -max - 1
+    }
+
+    /// integers compare by their (sign-extended) value, floats by theirs
+    fn partial_cmp_value(self, other: Constant) -> Option<Ordering> {
+        match (self, other) {
+            (Constant::Int(lhs, ty), Constant::Int(rhs, _)) if ty.signed => {
+                Some(sign_extend(lhs, ty).cmp(&sign_extend(rhs, ty)))
+            }
+            (Constant::Int(lhs, _), Constant::Int(rhs, _)) => Some(lhs.cmp(&rhs)),
+            (Constant::Float(lhs, _), Constant::Float(rhs, _)) => lhs.partial_cmp(&rhs),
+            _ => None,
+        }
+    }
+
+    /// formats this constant as an LLVM constant of its own type
+    pub fn llvm_constant(&self) -> String {
+        match *self {
+            Constant::Int(value, ty) => ty.llvm_constant(value),
+            Constant::Float(value, ty) => ty.llvm_constant(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(value: i64) -> Constant {
+        Constant::Int(truncate(value as u64, IntType::INT), IntType::INT)
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(
+            Constant::binary(Token::OpAdd, int(40), int(2)),
+            Some(int(42))
+        );
+        assert_eq!(
+            Constant::binary(Token::OpDiv, int(-7), int(2)),
+            Some(int(-3))
+        );
+        assert_eq!(
+            Constant::binary(Token::OpRem, int(-7), int(2)),
+            Some(int(-1))
+        );
+        assert_eq!(Constant::binary(Token::OpDiv, int(1), int(0)), None);
+        assert_eq!(
+            Constant::binary(Token::OpAdd, int(i32::MAX as i64), int(1)),
+            Some(int(i32::MIN as i64))
+        );
+        assert_eq!(
+            Constant::binary(
+                Token::OpMul,
+                int(3),
+                Constant::Float(0.5, FloatType::Double)
+            ),
+            Some(Constant::Float(1.5, FloatType::Double))
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let max = int(i32::MAX as i64);
+        assert!(Constant::overflows(Token::OpAdd, max, int(1)));
+        assert!(!Constant::overflows(Token::OpSub, max, int(1)));
+        assert!(Constant::overflows(
+            Token::OpDiv,
+            int(i32::MIN as i64),
+            int(-1)
+        ));
+        assert!(!Constant::overflows(
+            Token::OpAdd,
+            Constant::Int(u32::MAX as u64, IntType::UINT),
+            int(1)
+        ));
+    }
+
+    #[test]
+    fn comparison() {
+        let unsigned = Constant::Int(1, IntType::UINT);
+        assert_eq!(Constant::binary(Token::OpLt, int(-1), int(1)), Some(int(1)));
+        assert_eq!(
+            Constant::binary(Token::OpLt, int(-1), unsigned),
+            Some(int(0))
+        );
+        assert_eq!(Constant::binary(Token::OpEq, int(2), int(2)), Some(int(1)));
+    }
+
+    #[test]
+    fn conversion() {
+        assert_eq!(
+            int(-1).convert(&Type::Int(IntType::UINT)),
+            Some(Constant::Int(u32::MAX as u64, IntType::UINT))
+        );
+        assert_eq!(
+            int(-1).convert(&Type::Int(IntType::LONG)),
+            Some(Constant::Int(u64::MAX, IntType::LONG))
+        );
+        assert_eq!(
+            Constant::Float(-2.5, FloatType::Double).convert(&Type::INT),
+            Some(int(-2))
+        );
+    }
+}
@@ -0,0 +1,1577 @@
+use super::checked;
+use super::constant::Constant;
+use super::dump::Node;
+use super::env::{Env, Global, Types, Variable};
+use super::expr::Assignment;
+use super::init::{Init, Initializer};
+use super::stmt::Compound;
+use diagnostic::{CheckResult, Diagnostic, ParseError, ParseResult};
+use ir::{self, Value};
+use message::{DiagnosticKind, Term};
+use source::Span;
+use token::{Token, Tokens};
+use types::{FloatType, FunctionType, IntType, Member, Qualifiers, Type};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StorageClass {
+    Static,
+    Extern,
+}
+
+/// storage class, type specifiers and type qualifiers at the head of a declaration
+#[derive(Debug, Clone)]
+pub struct DeclSpecifiers {
+    pub storage: Option<StorageClass>,
+    pub specifier: TypeSpecifier,
+    pub qualifiers: Qualifiers,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum TypeSpecifier {
+    /// a combination of the keywords of basic types
+    Basic(Type),
+    Struct(StructSpecifier),
+}
+
+#[derive(Debug, Clone)]
+pub struct StructSpecifier {
+    tag: Option<String>,
+
+    /// None if it only refers to a structure by its tag
+    members: Option<Vec<StructDeclaration>>,
+
+    span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct StructDeclaration {
+    specifiers: DeclSpecifiers,
+    declarators: Vec<Declarator>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Declarator {
+    /// the qualifiers of each `*`, outermost first
+    pointers: Vec<Qualifiers>,
+
+    direct: DirectDeclarator,
+
+    /// array and function declarators after the name, in the order they appear
+    suffixes: Vec<Suffix>,
+
+    /// empty at the position of the name for an empty abstract declarator
+    span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum DirectDeclarator {
+    /// None for an abstract declarator (a parameter or a type name without a name)
+    Name(Option<String>),
+
+    /// a declarator in parentheses, as in `(*fp)(int)`
+    Nested(Box<Declarator>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Suffix {
+    /// None for an array of unknown size
+    Array(Option<Box<Assignment>>),
+    Function(ParamList),
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamList {
+    /// None for `()`, which declares a function without a prototype
+    params: Option<Vec<ParamDecl>>,
+    variadic: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamDecl {
+    specifiers: DeclSpecifiers,
+    declarator: Declarator,
+}
+
+/// the type in a cast
+#[derive(Debug, Clone)]
+pub struct TypeName {
+    specifiers: DeclSpecifiers,
+    declarator: Declarator,
+}
+
+#[derive(Debug)]
+pub struct Declaration {
+    specifiers: DeclSpecifiers,
+    declarators: Vec<InitDeclarator>,
+}
+
+#[derive(Debug)]
+pub struct InitDeclarator {
+    declarator: Declarator,
+    init: Option<Initializer>,
+}
+
+#[derive(Debug)]
+pub struct FunctionDefinition {
+    specifiers: DeclSpecifiers,
+    declarator: Declarator,
+    body: Compound,
+}
+
+#[derive(Debug)]
+pub enum ExternalDeclaration {
+    FunctionDefinition(Box<FunctionDefinition>),
+    Declaration(Box<Declaration>),
+}
+
+/// the keywords of qualifiers, such as `const volatile`
+fn qualifier_words(qualifiers: Qualifiers) -> Vec<&'static str> {
+    let mut words = Vec::new();
+    if qualifiers.is_const {
+        words.push("const");
+    }
+    if qualifiers.is_volatile {
+        words.push("volatile");
+    }
+    words
+}
+
+pub fn is_type_specifier(token: Token) -> bool {
+    matches!(
+        token,
+        Token::TyVoid
+            | Token::TyChar
+            | Token::TyShort
+            | Token::TyInt
+            | Token::TyLong
+            | Token::TyFloat
+            | Token::TyDouble
+            | Token::TySigned
+            | Token::TyUnsigned
+    )
+}
+
+/// whether `token` can start a declaration
+pub fn is_declaration_specifier(token: Token) -> bool {
+    is_type_specifier(token)
+        || matches!(
+            token,
+            Token::KwStatic
+                | Token::KwExtern
+                | Token::KwConst
+                | Token::KwVolatile
+                | Token::KwStruct
+        )
+}
+
+/// reads type qualifiers (after `*`)
+fn parse_qualifiers<'a>(tokens: &mut Tokens<'a>) -> Qualifiers {
+    let mut qualifiers = Qualifiers::default();
+    loop {
+        match tokens.peek() {
+            Some(Token::KwConst) => qualifiers.is_const = true,
+            Some(Token::KwVolatile) => qualifiers.is_volatile = true,
+            _ => return qualifiers,
+        }
+        tokens.next();
+    }
+}
+
+/// determines the type denoted by type specifiers, which may appear in any order (C11 6.7.2)
+fn type_of_specifiers(specifiers: &[Token], span: Span) -> ParseResult<Type> {
+    let count = |expect| specifiers.iter().filter(|&&token| token == expect).count();
+    let signed = count(Token::TySigned);
+    let unsigned = count(Token::TyUnsigned);
+    let int = count(Token::TyInt);
+    let others = (
+        count(Token::TyVoid),
+        count(Token::TyChar),
+        count(Token::TyShort),
+        count(Token::TyLong),
+        count(Token::TyFloat),
+        count(Token::TyDouble),
+    );
+
+    let invalid = || {
+        let spelling: Vec<String> = specifiers.iter().map(Token::to_string).collect();
+        Err(ParseError::new(
+            span,
+            DiagnosticKind::InvalidTypeSpecifiers(spelling.join(" ")),
+        ))
+    };
+    if specifiers.is_empty() {
+        return Err(ParseError::new(span, DiagnosticKind::MissingTypeSpecifier));
+    }
+    if signed + unsigned > 1 || int > 1 {
+        return invalid();
+    }
+
+    let sign_or_int = signed + unsigned + int;
+    let int_type = |signed_type: IntType| {
+        if unsigned > 0 {
+            signed_type.to_unsigned()
+        } else {
+            signed_type
+        }
+    };
+
+    let ty = match others {
+        (1, 0, 0, 0, 0, 0) if sign_or_int == 0 => Type::Void,
+        (0, 0, 0, 0, 1, 0) if sign_or_int == 0 => Type::Float(FloatType::Float),
+        (0, 0, 0, 0, 0, 1) if sign_or_int == 0 => Type::Float(FloatType::Double),
+        (0, 0, 0, 1, 0, 1) if sign_or_int == 0 => {
+            return Err(ParseError::new(span, DiagnosticKind::LongDouble))
+        }
+        (0, 1, 0, 0, 0, 0) if int == 0 => Type::Int(int_type(IntType::CHAR)),
+        (0, 0, 1, 0, 0, 0) => Type::Int(int_type(IntType::SHORT)),
+        (0, 0, 0, 0, 0, 0) => Type::Int(int_type(IntType::INT)),
+        (0, 0, 0, 1, 0, 0) => Type::Int(int_type(IntType::LONG)),
+        (0, 0, 0, 2, 0, 0) => Type::Int(int_type(IntType::LLONG)),
+        _ => return invalid(),
+    };
+    Ok(ty)
+}
+
+/// the length of an array declared with `size`
+fn array_len(size: &Assignment) -> CheckResult<usize> {
+    let kind = match size.constant() {
+        Some(constant @ Constant::Int(..)) if constant.is_negative() => {
+            DiagnosticKind::NegativeArraySize
+        }
+        Some(Constant::Int(0, _)) => DiagnosticKind::ZeroSizeArray,
+        Some(Constant::Int(value, _)) => return Ok(value as usize),
+        _ => DiagnosticKind::NonConstantArraySize,
+    };
+    Err(Diagnostic::new(size.span(), kind))
+}
+
+/// <decl-specifiers> ::= (<storage-class> | <type-specifier> | <type-qualifier>)+
+impl DeclSpecifiers {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<DeclSpecifiers> {
+        let start = tokens.span();
+        let mut storage = None;
+        let mut qualifiers = Qualifiers::default();
+        let mut specifiers = Vec::new();
+        let mut struct_specifier = None;
+        while let Some(token) = tokens.peek() {
+            let storage_class = match token {
+                Token::KwStatic => Some(StorageClass::Static),
+                Token::KwExtern => Some(StorageClass::Extern),
+                Token::KwConst => {
+                    qualifiers.is_const = true;
+                    None
+                }
+                Token::KwVolatile => {
+                    qualifiers.is_volatile = true;
+                    None
+                }
+                Token::KwStruct => {
+                    if struct_specifier.is_some() {
+                        return Err(ParseError::new(
+                            tokens.span(),
+                            DiagnosticKind::StructAfterStruct,
+                        ));
+                    }
+                    struct_specifier = Some(StructSpecifier::parse(tokens)?);
+                    continue;
+                }
+                token if is_type_specifier(token) => {
+                    specifiers.push(token);
+                    None
+                }
+                _ => break,
+            };
+            if storage_class.is_some() {
+                if storage.is_some() {
+                    return Err(ParseError::new(
+                        tokens.span(),
+                        DiagnosticKind::MultipleStorageClasses,
+                    ));
+                }
+                storage = storage_class;
+            }
+            tokens.eat(token)?;
+        }
+
+        if struct_specifier.is_none() && specifiers.is_empty() {
+            return Err(tokens.expected(Term::TypeSpecifier));
+        }
+        let span = start.to(tokens.prev_span());
+
+        let specifier = match struct_specifier {
+            Some(_) if !specifiers.is_empty() => {
+                return Err(ParseError::new(
+                    span,
+                    DiagnosticKind::StructWithSpecifier(specifiers[0].to_string()),
+                ))
+            }
+            Some(struct_specifier) => TypeSpecifier::Struct(struct_specifier),
+            None => TypeSpecifier::Basic(type_of_specifiers(&specifiers, span)?),
+        };
+        Ok(DeclSpecifiers {
+            storage,
+            specifier,
+            qualifiers,
+            span,
+        })
+    }
+
+    /// the type denoted by the type specifiers.  a structure definition in them is defined by
+    /// this, so this must be called once for each declaration.
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self.specifier {
+            TypeSpecifier::Basic(ref ty) => Ok(ty.clone()),
+            TypeSpecifier::Struct(ref specifier) => specifier.ty(env),
+        }
+    }
+
+    /// the node for the dump of the syntax tree
+    pub fn dump(&self, types: &Types) -> Node {
+        let mut words = Vec::new();
+        match self.storage {
+            Some(StorageClass::Static) => words.push("static".to_owned()),
+            Some(StorageClass::Extern) => words.push("extern".to_owned()),
+            None => {}
+        }
+        words.extend(
+            qualifier_words(self.qualifiers)
+                .into_iter()
+                .map(str::to_owned),
+        );
+        let mut node = Node::new("specifiers", Some(self.span));
+        match self.specifier {
+            TypeSpecifier::Basic(ref ty) => words.push(ty.to_string()),
+            TypeSpecifier::Struct(ref specifier) => node = node.child(specifier.dump(types)),
+        }
+        if words.is_empty() {
+            node
+        } else {
+            node.value(words.join(" "))
+        }
+    }
+}
+
+/// <struct-specifier> ::= KwStruct Ident
+///                      | KwStruct Ident? SyLBrace <struct-declaration>+ SyRBrace
+/// <struct-declaration> ::= <decl-specifiers> <declarator> (SyComma <declarator>)* SySemicolon
+impl StructSpecifier {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<StructSpecifier> {
+        let start = tokens.span();
+        tokens.eat(Token::KwStruct)?;
+        let tag = match tokens.peek() {
+            Some(Token::Ident(tag)) => {
+                tokens.next();
+                Some(tag.to_owned())
+            }
+            _ => None,
+        };
+
+        if tokens.peek() != Some(Token::SyLBrace) {
+            let span = start.to(tokens.prev_span());
+            if tag.is_none() {
+                return Err(ParseError::new(
+                    span,
+                    DiagnosticKind::AnonymousStructDeclaration,
+                ));
+            }
+            return Ok(StructSpecifier {
+                tag,
+                members: None,
+                span,
+            });
+        }
+
+        tokens.eat(Token::SyLBrace)?;
+        let mut members = Vec::new();
+        while tokens.peek() != Some(Token::SyRBrace) {
+            let specifiers = DeclSpecifiers::parse(tokens)?;
+            if specifiers.storage.is_some() {
+                return Err(ParseError::new(
+                    specifiers.span,
+                    DiagnosticKind::StorageClassInTypeName,
+                ));
+            }
+            let mut declarators = vec![Declarator::parse(tokens)?];
+            while tokens.peek() == Some(Token::SyComma) {
+                tokens.eat(Token::SyComma)?;
+                declarators.push(Declarator::parse(tokens)?);
+            }
+            tokens.eat_err(Token::SySemicolon, DiagnosticKind::MissingSemicolonInStruct)?;
+            members.push(StructDeclaration {
+                specifiers,
+                declarators,
+            });
+        }
+        tokens.eat(Token::SyRBrace)?;
+        let span = start.to(tokens.prev_span());
+        if members.is_empty() {
+            return Err(ParseError::new(span, DiagnosticKind::EmptyStruct));
+        }
+
+        Ok(StructSpecifier {
+            tag,
+            members: Some(members),
+            span,
+        })
+    }
+
+    fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        let declarations = match self.members {
+            Some(ref declarations) => declarations,
+            None => {
+                let tag = self
+                    .tag
+                    .as_ref()
+                    .expect("ICE: anonymous struct without members");
+                return Ok(Type::Struct(env.lookup_struct(tag)));
+            }
+        };
+
+        let name = env.define_struct(self.tag.as_deref(), self.span)?;
+        let mut members: Vec<Member> = Vec::new();
+        for declaration in declarations {
+            let base = declaration.specifiers.ty(env)?;
+            for declarator in &declaration.declarators {
+                let (ty, qualifiers) =
+                    declarator.resolve(&base, declaration.specifiers.qualifiers, env)?;
+                let member_name = declarator.name()?;
+                if !env.is_complete(&ty) {
+                    return Err(Diagnostic::new(
+                        declarator.span,
+                        DiagnosticKind::IncompleteField(member_name.to_owned(), ty),
+                    ));
+                }
+                if members.iter().any(|member| member.name == member_name) {
+                    return Err(Diagnostic::new(
+                        declarator.span,
+                        DiagnosticKind::DuplicateMember(member_name.to_owned()),
+                    ));
+                }
+                members.push(Member {
+                    name: member_name.to_owned(),
+                    ty,
+                    qualifiers,
+                });
+            }
+        }
+        env.complete_struct(&name, members, self.span)?;
+        Ok(Type::Struct(name))
+    }
+
+    fn dump(&self, types: &Types) -> Node {
+        let mut node = Node::new("struct", Some(self.span));
+        if let Some(ref tag) = self.tag {
+            node = node.value(tag.clone());
+        }
+        let members = self.members.iter().flatten().map(|member| {
+            let span = match member.declarators.last() {
+                Some(declarator) => member.specifiers.span.to(declarator.span),
+                None => member.specifiers.span,
+            };
+            Node::new("member-declaration", Some(span))
+                .child(member.specifiers.dump(types))
+                .children(
+                    member
+                        .declarators
+                        .iter()
+                        .map(|declarator| declarator.dump(types)),
+                )
+        });
+        node.children(members)
+    }
+}
+
+/// <declarator> ::= (OpMul <type-qualifier>*)* <direct-declarator> <suffix>*
+/// <direct-declarator> ::= Ident?
+///                       | SyLPar <declarator> SyRPar
+/// <suffix> ::= SyLBracket <assignment>? SyRBracket
+///            | SyLPar <param-list> SyRPar
+impl Declarator {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Declarator> {
+        let start = tokens.span();
+        let mut pointers = Vec::new();
+        while let Some(Token::OpMul) = tokens.peek() {
+            tokens.eat(Token::OpMul)?;
+            pointers.push(parse_qualifiers(tokens));
+        }
+
+        let direct = match tokens.peek() {
+            Some(Token::Ident(name)) => {
+                tokens.next();
+                DirectDeclarator::Name(Some(name.to_owned()))
+            }
+            Some(Token::SyLPar) if Declarator::starts_nested(*tokens) => {
+                tokens.eat(Token::SyLPar)?;
+                let declarator = Declarator::parse(tokens)?;
+                tokens.eat_err(
+                    Token::SyRPar,
+                    DiagnosticKind::Unclosed(Term::Parens, Term::Declarator),
+                )?;
+                DirectDeclarator::Nested(Box::new(declarator))
+            }
+            _ => DirectDeclarator::Name(None),
+        };
+
+        let mut suffixes = Vec::new();
+        loop {
+            match tokens.peek() {
+                Some(Token::SyLPar) => suffixes.push(Suffix::Function(ParamList::parse(tokens)?)),
+                Some(Token::SyLBracket) => {
+                    tokens.eat(Token::SyLBracket)?;
+                    let size = match tokens.peek() {
+                        Some(Token::SyRBracket) => None,
+                        _ => Some(Box::new(Assignment::parse(tokens)?)),
+                    };
+                    tokens.eat_err(
+                        Token::SyRBracket,
+                        DiagnosticKind::Unclosed(Term::Brackets, Term::ArraySize),
+                    )?;
+                    suffixes.push(Suffix::Array(size));
+                }
+                _ => break,
+            }
+        }
+
+        Ok(Declarator {
+            pointers,
+            direct,
+            suffixes,
+            span: start.to(tokens.prev_span()),
+        })
+    }
+
+    /// whether `(` at the head of `tokens` starts a nested declarator rather than a parameter
+    /// list of an abstract declarator
+    fn starts_nested(mut tokens: Tokens) -> bool {
+        tokens.next();
+        match tokens.peek() {
+            Some(Token::SyRPar) => false,
+            Some(token) => !is_declaration_specifier(token),
+            None => false,
+        }
+    }
+
+    /// whether this declarator derives a type from the declaration specifiers
+    fn is_derived(&self) -> bool {
+        !self.pointers.is_empty()
+            || !self.suffixes.is_empty()
+            || match self.direct {
+                DirectDeclarator::Nested(ref declarator) => declarator.is_derived(),
+                DirectDeclarator::Name(_) => false,
+            }
+    }
+
+    /// the nested declarator if it derives the type last, which is the case unless it is just
+    /// a name in parentheses
+    fn derived_nested(&self) -> Option<&Declarator> {
+        match self.direct {
+            DirectDeclarator::Nested(ref declarator) if declarator.is_derived() => Some(declarator),
+            _ => None,
+        }
+    }
+
+    pub fn is_function(&self) -> bool {
+        self.params().is_some()
+    }
+
+    /// the parameters if this declares a function
+    fn params(&self) -> Option<&ParamList> {
+        if let Some(declarator) = self.derived_nested() {
+            return declarator.params();
+        }
+        match self.suffixes.first() {
+            Some(Suffix::Function(params)) => Some(params),
+            _ => None,
+        }
+    }
+
+    /// the declared name, or None for an abstract declarator
+    fn ident(&self) -> Option<&str> {
+        match self.direct {
+            DirectDeclarator::Name(ref name) => name.as_ref().map(String::as_str),
+            DirectDeclarator::Nested(ref declarator) => declarator.ident(),
+        }
+    }
+
+    /// the declared name.  reports an error for an abstract declarator.
+    pub fn name(&self) -> CheckResult<&str> {
+        self.ident()
+            .ok_or_else(|| Diagnostic::new(self.span, DiagnosticKind::MissingIdentifier))
+    }
+
+    /// the qualifiers of the declared entity itself, the same as those `resolve` returns
+    fn qualifiers(&self, qualifiers: Qualifiers) -> Qualifiers {
+        if let Some(declarator) = self.derived_nested() {
+            return declarator.qualifiers(Qualifiers::default());
+        }
+        if !self.suffixes.is_empty() {
+            return Qualifiers::default();
+        }
+        self.pointers.last().copied().unwrap_or(qualifiers)
+    }
+
+    /// the type of the declared entity and the qualifiers of the entity itself, given the type
+    /// and the qualifiers in the declaration specifiers
+    pub fn resolve(
+        &self,
+        base: &Type,
+        qualifiers: Qualifiers,
+        env: &mut Env,
+    ) -> CheckResult<(Type, Qualifiers)> {
+        let mut ty = base.clone();
+        let mut qualifiers = qualifiers;
+        for pointer in &self.pointers {
+            ty = Type::Pointer(Box::new(ty), qualifiers);
+            qualifiers = *pointer;
+        }
+
+        // the suffix nearest to the name applies last
+        for suffix in self.suffixes.iter().rev() {
+            match suffix {
+                Suffix::Array(size) => {
+                    if let Type::Function(_) = ty {
+                        return Err(Diagnostic::new(self.span, DiagnosticKind::ArrayOfFunctions));
+                    }
+                    if !env.is_complete(&ty) {
+                        return Err(Diagnostic::new(
+                            self.span,
+                            DiagnosticKind::IncompleteElementType(ty),
+                        ));
+                    }
+                    let len = match size {
+                        Some(size) => Some(array_len(size)?),
+                        None => None,
+                    };
+                    // the qualifiers of an array apply to its elements
+                    ty = Type::Array(Box::new(ty), len);
+                }
+                Suffix::Function(params) => {
+                    let kind = match ty {
+                        Type::Array(..) => Some(DiagnosticKind::FunctionReturningArray),
+                        Type::Function(_) => Some(DiagnosticKind::FunctionReturningFunction),
+                        _ => None,
+                    };
+                    if let Some(kind) = kind {
+                        return Err(Diagnostic::new(self.span, kind));
+                    }
+                    let function = FunctionType {
+                        ret: ty,
+                        params: params.resolve(env)?,
+                        variadic: params.variadic,
+                    };
+                    ty = Type::Function(Box::new(function));
+                    qualifiers = Qualifiers::default();
+                }
+            }
+        }
+
+        // the type derived so far is the base of the nested declarator
+        let (ty, qualifiers) = match self.direct {
+            DirectDeclarator::Nested(ref declarator) => declarator.resolve(&ty, qualifiers, env)?,
+            DirectDeclarator::Name(_) => (ty, qualifiers),
+        };
+        env.record_type(self.span, &ty);
+        Ok((ty, qualifiers))
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        let mut node = Node::typed("declarator", self.span, types);
+        for qualifiers in &self.pointers {
+            let words = qualifier_words(*qualifiers);
+            let pointer = Node::new("pointer", None);
+            node = node.child(if words.is_empty() {
+                pointer
+            } else {
+                pointer.value(words.join(" "))
+            });
+        }
+        match self.direct {
+            DirectDeclarator::Name(Some(ref name)) => node = node.value(name.clone()),
+            DirectDeclarator::Name(None) => {}
+            DirectDeclarator::Nested(ref declarator) => node = node.child(declarator.dump(types)),
+        }
+        for suffix in &self.suffixes {
+            node = node.child(match suffix {
+                Suffix::Array(size) => {
+                    Node::new("array", None).children(size.iter().map(|size| size.dump(types)))
+                }
+                Suffix::Function(params) => params.dump(types),
+            });
+        }
+        node
+    }
+}
+
+/// <param-list> ::= SyLPar SyRPar
+///                | SyLPar TyVoid SyRPar
+///                | SyLPar <param-decl> (SyComma <param-decl>)* (SyComma SyEllipsis)? SyRPar
+/// <param-decl> ::= <decl-specifiers> <declarator>
+impl ParamList {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<ParamList> {
+        tokens.eat(Token::SyLPar)?;
+        if let Some(Token::SyRPar) = tokens.peek() {
+            tokens.eat(Token::SyRPar)?;
+            return Ok(ParamList {
+                params: None,
+                variadic: false,
+            });
+        }
+
+        let mut params = Vec::new();
+        let mut variadic = false;
+        loop {
+            if let Some(Token::SyEllipsis) = tokens.peek() {
+                if params.is_empty() {
+                    return Err(ParseError::new(
+                        tokens.span(),
+                        DiagnosticKind::UnnamedParameterBeforeEllipsis,
+                    ));
+                }
+                tokens.eat(Token::SyEllipsis)?;
+                variadic = true;
+                break;
+            }
+            params.push(ParamDecl::parse(tokens)?);
+            if tokens.peek() != Some(Token::SyComma) {
+                break;
+            }
+            tokens.eat(Token::SyComma)?;
+        }
+        tokens.eat_err(
+            Token::SyRPar,
+            DiagnosticKind::Unclosed(Term::Parens, Term::ParameterList),
+        )?;
+
+        // `(void)` declares a function without parameters
+        let is_void = |param: &ParamDecl| {
+            matches!(param.specifiers.specifier, TypeSpecifier::Basic(Type::Void))
+                && param.declarator.ident().is_none()
+                && !param.declarator.is_derived()
+        };
+        if params.len() == 1 && !variadic && is_void(&params[0]) {
+            params.clear();
+        }
+
+        Ok(ParamList {
+            params: Some(params),
+            variadic,
+        })
+    }
+
+    /// the types of the parameters, or None if the function has no prototype
+    fn resolve(&self, env: &mut Env) -> CheckResult<Option<Vec<Type>>> {
+        match self.params {
+            Some(ref params) => params
+                .iter()
+                .map(|param| Ok(param.resolve(env)?.0))
+                .collect::<CheckResult<_>>()
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn dump(&self, types: &Types) -> Node {
+        let node = Node::new("function", None);
+        let params = match self.params {
+            Some(ref params) => params,
+            None => return node.value("no-prototype"),
+        };
+        let node = node.children(params.iter().map(|param| {
+            Node::new("param", Some(param.span()))
+                .child(param.specifiers.dump(types))
+                .child(param.declarator.dump(types))
+        }));
+        if self.variadic {
+            node.value("...")
+        } else {
+            node
+        }
+    }
+}
+
+impl ParamDecl {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<ParamDecl> {
+        let specifiers = DeclSpecifiers::parse(tokens)?;
+        if specifiers.storage.is_some() {
+            return Err(ParseError::new(
+                specifiers.span,
+                DiagnosticKind::InvalidParameterStorageClass,
+            ));
+        }
+        let declarator = Declarator::parse(tokens)?;
+        Ok(ParamDecl {
+            specifiers,
+            declarator,
+        })
+    }
+
+    fn span(&self) -> Span {
+        self.specifiers.span.to(self.declarator.span)
+    }
+
+    /// the type of the parameter after adjustment, and its qualifiers.  a parameter of an array
+    /// type is adjusted to a pointer to its first element, and that of a function type to a
+    /// pointer to the function.
+    fn resolve(&self, env: &mut Env) -> CheckResult<(Type, Qualifiers)> {
+        let base = self.specifiers.ty(env)?;
+        let (ty, qualifiers) = self
+            .declarator
+            .resolve(&base, self.specifiers.qualifiers, env)?;
+        match ty {
+            Type::Void => Err(Diagnostic::new(self.span(), DiagnosticKind::VoidParameter)),
+            Type::Array(..) => Ok((ty.decay(qualifiers), Qualifiers::default())),
+            Type::Function(_) => Ok((
+                Type::Pointer(Box::new(ty), Qualifiers::default()),
+                Qualifiers::default(),
+            )),
+            ty => Ok((ty, qualifiers)),
+        }
+    }
+}
+
+/// <type-name> ::= <decl-specifiers> <declarator>
+impl TypeName {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<TypeName> {
+        let specifiers = DeclSpecifiers::parse(tokens)?;
+        if specifiers.storage.is_some() {
+            return Err(ParseError::new(
+                specifiers.span,
+                DiagnosticKind::StorageClassInTypeName,
+            ));
+        }
+        let declarator = Declarator::parse(tokens)?;
+        if let Some(name) = declarator.ident() {
+            return Err(ParseError::new(
+                declarator.span,
+                DiagnosticKind::IdentifierInTypeName(name.to_owned()),
+            ));
+        }
+        Ok(TypeName {
+            specifiers,
+            declarator,
+        })
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        let base = self.specifiers.ty(env)?;
+        Ok(self
+            .declarator
+            .resolve(&base, self.specifiers.qualifiers, env)?
+            .0)
+    }
+
+    /// the type if it is a basic type, which is known without `Env`
+    pub fn basic_type(&self) -> Option<&Type> {
+        match self.specifiers.specifier {
+            TypeSpecifier::Basic(ref ty) if !self.declarator.is_derived() => Some(ty),
+            _ => None,
+        }
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        let span = self.specifiers.span.to(self.declarator.span);
+        Node::new("type-name", Some(span))
+            .child(self.specifiers.dump(types))
+            .child(self.declarator.dump(types))
+    }
+}
+
+/// <declaration> ::= <decl-specifiers> (<init-declarator> (SyComma <init-declarator>)*)?
+///                   SySemicolon
+/// <init-declarator> ::= <declarator>
+///                     | <declarator> OpAssign <initializer>
+impl Declaration {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Declaration> {
+        let specifiers = DeclSpecifiers::parse(tokens)?;
+        Declaration::parse_rest(specifiers, None, tokens)
+    }
+
+    /// parses the rest of a declaration whose specifiers and possibly first declarator have
+    /// already been read
+    fn parse_rest<'a>(
+        specifiers: DeclSpecifiers,
+        first: Option<Declarator>,
+        tokens: &mut Tokens<'a>,
+    ) -> ParseResult<Declaration> {
+        let mut declarators = Vec::new();
+        let mut first = first;
+        if first.is_some() || tokens.peek() != Some(Token::SySemicolon) {
+            loop {
+                let declarator = match first.take() {
+                    Some(declarator) => declarator,
+                    None => Declarator::parse(tokens)?,
+                };
+                declarators.push(InitDeclarator::parse_rest(declarator, tokens)?);
+                if tokens.peek() != Some(Token::SyComma) {
+                    break;
+                }
+                tokens.eat(Token::SyComma)?;
+            }
+        }
+        if tokens.peek() != Some(Token::SySemicolon) {
+            return Err(tokens.expected(Term::SemicolonAfterDeclaration));
+        }
+        tokens.eat(Token::SySemicolon)?;
+        Ok(Declaration {
+            specifiers,
+            declarators,
+        })
+    }
+
+    pub fn span(&self) -> Span {
+        match self.declarators.last() {
+            Some(declarator) => self.specifiers.span.to(declarator.span()),
+            None => self.specifiers.span,
+        }
+    }
+
+    /// declares the names declared in a block
+    pub fn check(&self, env: &mut Env) -> CheckResult<()> {
+        let base = self.specifiers.ty(env)?;
+        for declarator in &self.declarators {
+            declarator.check(&base, &self.specifiers, env)?;
+        }
+        Ok(())
+    }
+
+    /// allocates and initializes the variables declared in a block
+    pub fn gen_code(self, env: &mut Env) {
+        let base = checked(self.specifiers.ty(env));
+        for declarator in self.declarators {
+            declarator.gen_code(&base, &self.specifiers, env);
+        }
+    }
+
+    /// declares the functions and the global variables declared at file scope
+    pub fn declare_global(&self, env: &mut Env) -> CheckResult<()> {
+        let base = self.specifiers.ty(env)?;
+        for declarator in &self.declarators {
+            declarator.declare_global(&base, &self.specifiers, env)?;
+        }
+        Ok(())
+    }
+
+    /// the node for the dump of the syntax tree
+    pub fn dump(&self, types: &Types) -> Node {
+        Node::new("declaration", Some(self.span()))
+            .child(self.specifiers.dump(types))
+            .children(
+                self.declarators
+                    .iter()
+                    .map(|declarator| declarator.dump(types)),
+            )
+    }
+}
+
+impl InitDeclarator {
+    fn parse_rest<'a>(
+        declarator: Declarator,
+        tokens: &mut Tokens<'a>,
+    ) -> ParseResult<InitDeclarator> {
+        let init = match tokens.peek() {
+            Some(Token::OpAssign) => {
+                tokens.eat(Token::OpAssign)?;
+                Some(Initializer::parse(tokens)?)
+            }
+            _ => None,
+        };
+        Ok(InitDeclarator { declarator, init })
+    }
+
+    fn span(&self) -> Span {
+        match self.init {
+            Some(ref init) => self.declarator.span.to(init.span()),
+            None => self.declarator.span,
+        }
+    }
+
+    fn declare_function(&self, ty: Type, internal: bool, env: &mut Env) -> CheckResult<()> {
+        let name = self.declarator.name()?;
+        if self.init.is_some() {
+            return Err(Diagnostic::new(
+                self.span(),
+                DiagnosticKind::NonVariableInitializer(name.to_owned()),
+            ));
+        }
+        match ty {
+            Type::Function(function) => {
+                env.declare_function(name, *function, internal, false, self.declarator.span)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// matches the initializer with the declared object of type `ty`, and checks the
+    /// expressions in it.  returns the initializer and the type completed by it.
+    fn resolve_init(&self, ty: Type, env: &mut Env) -> CheckResult<(Option<Init>, Type)> {
+        match self.init {
+            Some(ref initializer) => {
+                let (init, ty) = Init::new(initializer.clone(), &ty, env)?;
+                init.check(&ty, env)?;
+                Ok((Some(init), ty))
+            }
+            None => Ok((None, ty)),
+        }
+    }
+
+    /// declares the entity declared by this in a block.  returns the type, the qualifiers and
+    /// the initializer of an automatic variable, which is left to the caller to allocate and
+    /// declare.
+    fn declare(
+        &self,
+        base: &Type,
+        specifiers: &DeclSpecifiers,
+        env: &mut Env,
+    ) -> CheckResult<Option<(Type, Qualifiers, Option<Init>)>> {
+        let (ty, qualifiers) = self.declarator.resolve(base, specifiers.qualifiers, env)?;
+        let name = self.declarator.name()?.to_owned();
+
+        if self.declarator.is_function() {
+            if specifiers.storage == Some(StorageClass::Static) {
+                return Err(Diagnostic::new(
+                    specifiers.span,
+                    DiagnosticKind::StaticBlockFunction(name.to_owned()),
+                ));
+            }
+            self.declare_function(ty, false, env)?;
+            return Ok(None);
+        }
+
+        if specifiers.storage == Some(StorageClass::Extern) {
+            if self.init.is_some() {
+                return Err(Diagnostic::new(
+                    self.span(),
+                    DiagnosticKind::ExternWithInitializer(name.to_owned()),
+                ));
+            }
+            let global = Global {
+                ty,
+                qualifiers,
+                internal: false,
+                init: None,
+                is_definition: false,
+            };
+            env.declare_global(&name, global, true, self.declarator.span)?;
+            return Ok(None);
+        }
+
+        let span = self.span();
+        let (init, ty) = self.resolve_init(ty, env)?;
+        if !env.is_complete(&ty) {
+            return Err(Diagnostic::new(
+                span,
+                DiagnosticKind::IncompleteVariable(name.to_owned(), ty),
+            ));
+        }
+
+        if specifiers.storage == Some(StorageClass::Static) {
+            let global = Global {
+                init: match init {
+                    Some(init) => Some(init.into_static(&ty, env)?),
+                    None => None,
+                },
+                ty,
+                qualifiers,
+                internal: true,
+                is_definition: true,
+            };
+            env.define_static_local(&name, global, span)?;
+            return Ok(None);
+        }
+        Ok(Some((ty, qualifiers, init)))
+    }
+
+    /// declares the automatic variable with a placeholder address
+    pub fn check(
+        &self,
+        base: &Type,
+        specifiers: &DeclSpecifiers,
+        env: &mut Env,
+    ) -> CheckResult<()> {
+        let (ty, qualifiers) = match self.declare(base, specifiers, env) {
+            Ok(Some((ty, qualifiers, _))) => (ty, qualifiers),
+            Ok(None) => return Ok(()),
+            // an automatic variable with an invalid initializer is declared anyway, so that its
+            // uses do not report more errors
+            Err(diagnostic)
+                if self.init.is_some()
+                    && specifiers.storage.is_none()
+                    && !self.declarator.is_function() =>
+            {
+                let (ty, qualifiers) = self.declarator.resolve(base, specifiers.qualifiers, env)?;
+                let variable = Variable::local(Value::Constant(ir::Type::I32, 0), ty, qualifiers);
+                env.declare(self.declarator.name()?, variable, self.span())?;
+                return Err(diagnostic);
+            }
+            Err(diagnostic) => return Err(diagnostic),
+        };
+        let name = self.declarator.name()?;
+        let variable = Variable::local(Value::Constant(ir::Type::I32, 0), ty, qualifiers);
+        env.declare(name, variable, self.span())?;
+        env.track_usage(name, self.declarator.span);
+        Ok(())
+    }
+
+    pub fn gen_code(self, base: &Type, specifiers: &DeclSpecifiers, env: &mut Env) {
+        let (ty, qualifiers, init) = match checked(self.declare(base, specifiers, env)) {
+            Some(variable) => variable,
+            None => return,
+        };
+        let address = env.block().alloca(ty.ir_type());
+        let variable = Variable::local(address, ty, qualifiers);
+        let name = checked(self.declarator.name());
+        checked(env.declare(name, variable.clone(), self.span()));
+        if let Some(init) = init {
+            init.gen_code(&variable, env);
+        }
+    }
+
+    pub fn declare_global(
+        &self,
+        base: &Type,
+        specifiers: &DeclSpecifiers,
+        env: &mut Env,
+    ) -> CheckResult<()> {
+        let (ty, qualifiers) = self.declarator.resolve(base, specifiers.qualifiers, env)?;
+        let internal = specifiers.storage == Some(StorageClass::Static);
+
+        if self.declarator.is_function() {
+            return self.declare_function(ty, internal, env);
+        }
+
+        let name = self.declarator.name()?;
+        let span = self.span();
+        let is_extern = specifiers.storage == Some(StorageClass::Extern);
+
+        // declared before the initializer is checked, so that an error in it does not leave the
+        // variable undeclared
+        let declaration = Global {
+            ty: ty.clone(),
+            qualifiers,
+            internal,
+            init: None,
+            is_definition: false,
+        };
+        env.declare_global(name, declaration, is_extern, span)?;
+
+        let (init, ty) = self.resolve_init(ty, env)?;
+        // `extern` without an initializer only declares the variable
+        let is_definition = !is_extern || init.is_some();
+        if is_definition && !env.is_complete(&ty) {
+            return Err(Diagnostic::new(
+                span,
+                DiagnosticKind::IncompleteVariable(name.to_owned(), ty),
+            ));
+        }
+
+        let global = Global {
+            init: match init {
+                Some(init) => Some(init.into_static(&ty, env)?),
+                None => None,
+            },
+            ty,
+            qualifiers,
+            internal,
+            is_definition,
+        };
+        env.declare_global(name, global, is_extern, span)
+    }
+
+    fn dump(&self, types: &Types) -> Node {
+        Node::new("init-declarator", Some(self.span()))
+            .child(self.declarator.dump(types))
+            .children(self.init.iter().map(|init| init.dump(types)))
+    }
+}
+
+/// the name, the type, the qualifiers and the span of a parameter of a function definition
+type Param = (String, Type, Qualifiers, Span);
+
+impl FunctionDefinition {
+    /// declares the function, and returns its type and the parameters
+    fn declare(&self, env: &mut Env) -> CheckResult<(FunctionType, Vec<Param>)> {
+        let name = self.declarator.name()?;
+        let base = self.specifiers.ty(env)?;
+        let function = match self
+            .declarator
+            .resolve(&base, self.specifiers.qualifiers, env)?
+            .0
+        {
+            Type::Function(function) => *function,
+            _ => unreachable!(),
+        };
+
+        // a definition with an empty parameter list has no parameters
+        let function = FunctionType {
+            params: Some(function.params.unwrap_or_default()),
+            ..function
+        };
+        let params: Vec<_> = self
+            .declarator
+            .params()
+            .and_then(|params| params.params.as_ref())
+            .into_iter()
+            .flatten()
+            .zip(function.params.iter().flatten())
+            .map(|(param, ty)| {
+                let name = match param.declarator.ident() {
+                    Some(name) => name.to_owned(),
+                    None => {
+                        return Err(Diagnostic::new(
+                            param.span(),
+                            DiagnosticKind::ParameterNameOmitted(name.to_owned()),
+                        ))
+                    }
+                };
+                let qualifiers = param.declarator.qualifiers(param.specifiers.qualifiers);
+                Ok((name, ty.clone(), qualifiers, param.span()))
+            })
+            .collect::<CheckResult<_>>()?;
+
+        let internal = self.specifiers.storage == Some(StorageClass::Static);
+        env.declare_function(name, function.clone(), internal, true, self.declarator.span)?;
+        Ok((function, params))
+    }
+
+    /// checks the body.  an error in a statement or a declaration of a parameter is added to
+    /// `errors`.
+    pub fn check(&self, env: &mut Env, errors: &mut Vec<Diagnostic>) -> CheckResult<()> {
+        let (function, params) = self.declare(env)?;
+        let name = self.declarator.name()?;
+        env.begin_function(name, function.ret.clone());
+        env.enter_scope();
+        for (param, ty, qualifiers, span) in params {
+            let variable = Variable::local(Value::Constant(ir::Type::I32, 0), ty, qualifiers);
+            if let Err(error) = env.declare(&param, variable, span) {
+                errors.push(error);
+            }
+        }
+        self.body.check_items(env, errors);
+        env.leave_scope();
+        env.end_function();
+
+        // reaching the end of `main` returns 0
+        if function.ret != Type::Void && name != "main" && !self.body.returns() {
+            env.warn(
+                self.body.end_span(),
+                DiagnosticKind::MissingReturn(name.to_owned()),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn gen_code(self, env: &mut Env) {
+        let name = checked(self.declarator.name()).to_owned();
+        let (function, params) = checked(self.declare(env));
+        let internal = env
+            .function(&name)
+            .is_some_and(|function| function.internal);
+
+        let mut ir = ir::Function::new(&name, function.ret.ir_type());
+        ir.set_internal(internal);
+        let args: Vec<Value> = params
+            .iter()
+            .map(|(_, ty, _, _)| ir.add_param(ty.ir_type()))
+            .collect();
+        ir.add_block();
+
+        env.begin_function(&name, function.ret.clone());
+        env.begin_ir(ir);
+        env.enter_scope();
+
+        for ((param, ty, qualifiers, span), arg) in params.into_iter().zip(args) {
+            let address = env.block().alloca(ty.ir_type());
+            env.block().store(arg, address.clone(), false);
+            let variable = Variable::local(address, ty, qualifiers);
+            checked(env.declare(&param, variable, span));
+        }
+        self.body.gen_items(env);
+
+        // reaching the end of `main` returns 0, and that of other functions returns an
+        // unspecified value
+        match function.ret {
+            Type::Void => env.block().ret_void(),
+            ref ret => env
+                .block()
+                .ret(Value::Literal(ret.ir_type(), ret.llvm_zero())),
+        }
+
+        env.leave_scope();
+        env.end_function();
+        env.end_ir();
+    }
+
+    /// the node for the dump of the syntax tree
+    pub fn dump(&self, types: &Types) -> Node {
+        let span = self.specifiers.span.to(self.body.end_span());
+        Node::new("function-definition", Some(span))
+            .child(self.specifiers.dump(types))
+            .child(self.declarator.dump(types))
+            .child(self.body.dump(types))
+    }
+}
+
+/// <external-declaration> ::= <decl-specifiers> <declarator> <compound>
+///                          | <declaration>
+impl ExternalDeclaration {
+    /// parses an external declaration.  syntax errors in the statements of a function body are
+    /// added to `errors`.
+    pub fn parse<'a>(
+        tokens: &mut Tokens<'a>,
+        errors: &mut Vec<ParseError>,
+    ) -> ParseResult<ExternalDeclaration> {
+        let specifiers = DeclSpecifiers::parse(tokens)?;
+        if tokens.peek() == Some(Token::SySemicolon) {
+            let decl = Declaration::parse_rest(specifiers, None, tokens)?;
+            return Ok(ExternalDeclaration::Declaration(Box::new(decl)));
+        }
+
+        let declarator = Declarator::parse(tokens)?;
+        if declarator.is_function() && tokens.peek() == Some(Token::SyLBrace) {
+            let body = Compound::parse(tokens, errors)?;
+            let definition = FunctionDefinition {
+                specifiers,
+                declarator,
+                body,
+            };
+            return Ok(ExternalDeclaration::FunctionDefinition(Box::new(
+                definition,
+            )));
+        }
+
+        let decl = Declaration::parse_rest(specifiers, Some(declarator), tokens)?;
+        Ok(ExternalDeclaration::Declaration(Box::new(decl)))
+    }
+
+    /// checks this declaration.  errors in the body of a function are added to `errors`, and
+    /// the others are returned.
+    pub fn check(&self, env: &mut Env, errors: &mut Vec<Diagnostic>) -> CheckResult<()> {
+        match self {
+            ExternalDeclaration::FunctionDefinition(definition) => definition.check(env, errors),
+            ExternalDeclaration::Declaration(decl) => decl.declare_global(env),
+        }
+    }
+
+    pub fn gen_code(self, env: &mut Env) {
+        match self {
+            ExternalDeclaration::FunctionDefinition(definition) => definition.gen_code(env),
+            ExternalDeclaration::Declaration(decl) => checked(decl.declare_global(env)),
+        }
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        match self {
+            ExternalDeclaration::FunctionDefinition(definition) => definition.dump(types),
+            ExternalDeclaration::Declaration(declaration) => declaration.dump(types),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specifiers(tokens: &[Token]) -> DeclSpecifiers {
+        DeclSpecifiers::parse(&mut Tokens::new(tokens)).unwrap()
+    }
+
+    fn specifiers_ty(tokens: &[Token]) -> Type {
+        specifiers(tokens).ty(&mut Env::new()).unwrap()
+    }
+
+    #[test]
+    fn type_specifiers() {
+        assert_eq!(specifiers_ty(&[Token::TyInt]), Type::INT);
+        assert_eq!(specifiers_ty(&[Token::TySigned]), Type::INT);
+        assert_eq!(
+            specifiers_ty(&[
+                Token::TyLong,
+                Token::TyUnsigned,
+                Token::TyInt,
+                Token::TyLong
+            ]),
+            Type::Int(IntType::ULLONG)
+        );
+        assert_eq!(
+            specifiers_ty(&[Token::TyUnsigned, Token::TyChar]),
+            Type::Int(IntType::CHAR.to_unsigned())
+        );
+        assert_eq!(specifiers_ty(&[Token::TyDouble]), Type::DOUBLE);
+        assert_eq!(specifiers_ty(&[Token::TyVoid]), Type::Void);
+    }
+
+    fn error_message(tokens: &[Token]) -> String {
+        let error = DeclSpecifiers::parse(&mut Tokens::new(tokens)).unwrap_err();
+        Diagnostic::from(error).message()
+    }
+
+    #[test]
+    fn invalid_type_specifiers() {
+        assert!(error_message(&[Token::TyUnsigned, Token::TyFloat])
+            .contains("invalid combination of type specifiers"));
+    }
+
+    #[test]
+    fn storage_classes_and_qualifiers() {
+        let specifiers = specifiers(&[
+            Token::KwConst,
+            Token::TyInt,
+            Token::KwStatic,
+            Token::KwVolatile,
+        ]);
+        assert_eq!(specifiers.storage, Some(StorageClass::Static));
+        assert_eq!(specifiers.ty(&mut Env::new()).unwrap(), Type::INT);
+        assert!(specifiers.qualifiers.is_const);
+        assert!(specifiers.qualifiers.is_volatile);
+    }
+
+    #[test]
+    fn multiple_storage_classes() {
+        assert!(
+            error_message(&[Token::KwStatic, Token::KwExtern, Token::TyInt])
+                .contains("multiple storage classes")
+        );
+    }
+
+    #[test]
+    fn parse_declaration() {
+        let tokens = &mut Tokens::new(&[
+            Token::TyDouble,
+            Token::Ident("x"),
+            Token::OpAssign,
+            Token::Literal("1"),
+            Token::SyComma,
+            Token::Ident("y"),
+            Token::SySemicolon,
+        ]);
+        let decl = Declaration::parse(tokens).unwrap();
+        assert!(tokens.is_empty());
+        assert_eq!(decl.declarators.len(), 2);
+    }
+
+    fn resolve(decl: &Declaration, env: &mut Env) -> Type {
+        let base = decl.specifiers.ty(env).unwrap();
+        decl.declarators[0]
+            .declarator
+            .resolve(&base, decl.specifiers.qualifiers, env)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn function_prototype() {
+        // int printf(const char *, ...)
+        let tokens = &mut Tokens::new(&[
+            Token::TyInt,
+            Token::Ident("printf"),
+            Token::SyLPar,
+            Token::KwConst,
+            Token::TyChar,
+            Token::OpMul,
+            Token::SyComma,
+            Token::SyEllipsis,
+            Token::SyRPar,
+            Token::SySemicolon,
+        ]);
+        let decl = Declaration::parse(tokens).unwrap();
+        assert!(tokens.is_empty());
+        let const_char = Qualifiers {
+            is_const: true,
+            is_volatile: false,
+        };
+        let expected = FunctionType {
+            ret: Type::INT,
+            params: Some(vec![Type::Pointer(
+                Box::new(Type::Int(IntType::CHAR)),
+                const_char,
+            )]),
+            variadic: true,
+        };
+        assert_eq!(
+            resolve(&decl, &mut Env::new()),
+            Type::Function(Box::new(expected))
+        );
+    }
+
+    #[test]
+    fn void_parameter_list() {
+        let tokens = &mut Tokens::new(&[Token::SyLPar, Token::TyVoid, Token::SyRPar]);
+        let params = ParamList::parse(tokens).unwrap();
+        assert_eq!(params.params.map(|params| params.len()), Some(0));
+        assert!(!params.variadic);
+    }
+
+    #[test]
+    fn nested_declarators() {
+        // int (*fp[2])(int);
+        let tokens = &mut Tokens::new(&[
+            Token::TyInt,
+            Token::SyLPar,
+            Token::OpMul,
+            Token::Ident("fp"),
+            Token::SyLBracket,
+            Token::Literal("2"),
+            Token::SyRBracket,
+            Token::SyRPar,
+            Token::SyLPar,
+            Token::TyInt,
+            Token::SyRPar,
+            Token::SySemicolon,
+        ]);
+        let decl = Declaration::parse(tokens).unwrap();
+        assert!(tokens.is_empty());
+        assert!(!decl.declarators[0].declarator.is_function());
+        assert_eq!(
+            resolve(&decl, &mut Env::new()).llvm_type(),
+            "[2 x i32 (i32)*]"
+        );
+
+        // (void (*)(void))
+        let tokens = &mut Tokens::new(&[
+            Token::TyVoid,
+            Token::SyLPar,
+            Token::OpMul,
+            Token::SyRPar,
+            Token::SyLPar,
+            Token::TyVoid,
+            Token::SyRPar,
+        ]);
+        let type_name = TypeName::parse(tokens).unwrap();
+        assert!(tokens.is_empty());
+        assert_eq!(
+            type_name.ty(&mut Env::new()).unwrap().llvm_type(),
+            "void ()*"
+        );
+    }
+
+    #[test]
+    fn arrays_and_structs() {
+        // struct point { int x, y; } grid[2][3];
+        let tokens = &mut Tokens::new(&[
+            Token::KwStruct,
+            Token::Ident("point"),
+            Token::SyLBrace,
+            Token::TyInt,
+            Token::Ident("x"),
+            Token::SyComma,
+            Token::Ident("y"),
+            Token::SySemicolon,
+            Token::SyRBrace,
+            Token::Ident("grid"),
+            Token::SyLBracket,
+            Token::Literal("2"),
+            Token::SyRBracket,
+            Token::SyLBracket,
+            Token::Literal("3"),
+            Token::SyRBracket,
+            Token::SySemicolon,
+        ]);
+        let decl = Declaration::parse(tokens).unwrap();
+        assert!(tokens.is_empty());
+        let mut env = Env::new();
+        let ty = resolve(&decl, &mut env);
+        assert_eq!(ty.llvm_type(), "[2 x [3 x %struct.point]]");
+        assert_eq!(env.struct_members("point").len(), 2);
+    }
+}
@@ -0,0 +1,188 @@
+//! dumps of the syntax tree, as an indented S-expression (`--emit=ast`) or as JSON
+//! (`--emit=ast-json`)
+
+use super::env::Types;
+use diagnostic::json_string;
+use source::{Source, Span};
+use types::Type;
+
+/// a node of the syntax tree in a uniform shape.  the grammar levels which only wrap the next
+/// one (an `Additive` which is just a `Multiplicative`, and so on) and parentheses have no
+/// nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub kind: &'static str,
+
+    /// the name, the value of a literal, or the keywords of declaration specifiers
+    pub value: Option<String>,
+
+    /// None for the parts of a declarator without their own spans
+    pub span: Option<Span>,
+
+    /// the type given by the semantic analysis
+    pub ty: Option<Type>,
+
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    pub fn new(kind: &'static str, span: Option<Span>) -> Node {
+        Node {
+            kind,
+            value: None,
+            span,
+            ty: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// a node for an expression or a declarator, whose type is looked up in `types`
+    pub fn typed(kind: &'static str, span: Span, types: &Types) -> Node {
+        Node {
+            ty: types.get(&span).cloned(),
+            ..Node::new(kind, Some(span))
+        }
+    }
+
+    pub fn value<S: Into<String>>(self, value: S) -> Node {
+        Node {
+            value: Some(value.into()),
+            ..self
+        }
+    }
+
+    pub fn child(mut self, child: Node) -> Node {
+        self.children.push(child);
+        self
+    }
+
+    pub fn children<I: IntoIterator<Item = Node>>(mut self, children: I) -> Node {
+        self.children.extend(children);
+        self
+    }
+
+    /// `(kind value child...)`, with each child on its own line
+    pub fn to_sexp(&self) -> String {
+        let mut sexp = String::new();
+        self.write_sexp(&mut sexp, 0);
+        sexp
+    }
+
+    fn write_sexp(&self, sexp: &mut String, indent: usize) {
+        sexp.push('(');
+        sexp.push_str(self.kind);
+        if let Some(ref value) = self.value {
+            sexp.push(' ');
+            sexp.push_str(value);
+        }
+        for child in &self.children {
+            sexp.push('\n');
+            sexp.push_str(&" ".repeat(indent + 2));
+            child.write_sexp(sexp, indent + 2);
+        }
+        sexp.push(')');
+    }
+
+    /// the node as a JSON object with the locations of the spans in `source`
+    pub fn to_json(&self, source: &Source) -> String {
+        let span = match self.span {
+            Some(span) => {
+                let (line, column) = source.location(span.start);
+                let (end_line, end_column) = source.location(span.end);
+                let (line, end_line) = (
+                    source.presumed_line(line).1,
+                    source.presumed_line(end_line).1,
+                );
+                format!(
+                    r#"{{"line":{},"column":{},"end_line":{},"end_column":{}}}"#,
+                    line, column, end_line, end_column
+                )
+            }
+            None => "null".to_owned(),
+        };
+        let children: Vec<String> = self
+            .children
+            .iter()
+            .map(|child| child.to_json(source))
+            .collect();
+        format!(
+            r#"{{"kind":{},"value":{},"span":{},"type":{},"children":[{}]}}"#,
+            json_string(self.kind),
+            self.value
+                .as_ref()
+                .map_or("null".to_owned(), |value| json_string(value)),
+            span,
+            self.ty
+                .as_ref()
+                .map_or("null".to_owned(), |ty| json_string(&ty.to_string())),
+            children.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Ast;
+    use super::*;
+    use diagnostic::Diagnostic;
+    use lexer::Lexer;
+    use token::{Token, Tokens};
+    use types::IntType;
+
+    fn dump(text: &str) -> (Node, Source) {
+        let source = Source::new(text.to_owned());
+        let (tokens, spans): (Vec<Token>, Vec<Span>) = Lexer::from_source(&source).unzip();
+        let ast = Ast::parse(Tokens::with_spans(&tokens, &spans)).unwrap();
+        let (errors, types) = ast.analyze();
+        assert!(!errors.iter().any(Diagnostic::is_error));
+        (ast.dump(&types), source)
+    }
+
+    #[test]
+    fn sexp() {
+        let (node, _) = dump("int main(void) { int *p, a[2] = {1}; return (a[0] + 1) * 2; }");
+        assert_eq!(
+            node.to_sexp(),
+            "\
+(translation-unit
+  (function-definition
+    (specifiers int)
+    (declarator main
+      (function))
+    (compound
+      (declaration
+        (specifiers int)
+        (init-declarator
+          (declarator p
+            (pointer)))
+        (init-declarator
+          (declarator a
+            (array
+              (int 2)))
+          (initializer-list
+            (int 1))))
+      (return
+        (mul
+          (add
+            (index
+              (ident a)
+              (int 0))
+            (int 1))
+          (int 2))))))"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let (node, source) = dump("long x;\nint f(void) { return x + 1; }");
+        let add = &node.children[1].children[2].children[0].children[0];
+        assert_eq!(add.kind, "add");
+        assert_eq!(add.ty, Some(Type::Int(IntType::LONG)));
+        assert_eq!(
+            add.children[1].to_json(&source),
+            r#"{"kind":"int","value":"1","span":{"line":2,"column":26,"end_line":2,"end_column":27},"type":"int","children":[]}"#
+        );
+        let declarator = &node.children[0].children[1].children[0];
+        assert_eq!(declarator.ty, Some(Type::Int(IntType::LONG)));
+    }
+}
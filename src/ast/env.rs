@@ -0,0 +1,864 @@
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
+
+use super::init::StaticInit;
+use diagnostic::{CheckResult, Diagnostic};
+use ir::{self, Value};
+use message::{DiagnosticKind, Note};
+use source::Span;
+use types::{FloatType, FunctionType, Member, Qualifiers, Type};
+
+/// the intrinsic which fills memory with a byte
+const MEMSET: &str = "llvm.memset.p0i8.i64";
+
+/// where the value of a variable is stored
+#[derive(Debug, Clone)]
+pub enum Place {
+    /// the register holding the address, which is the result of `alloca` or `getelementptr`
+    Register(Value),
+
+    /// the name of a global variable, without `@`
+    Global(String),
+}
+
+/// the types of the expressions and the declarators by their spans, recorded by the semantic
+/// analysis
+pub type Types = HashMap<Span, Type>;
+
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub place: Place,
+    pub ty: Type,
+    pub qualifiers: Qualifiers,
+}
+
+impl Variable {
+    pub fn local(address: Value, ty: Type, qualifiers: Qualifiers) -> Variable {
+        Variable {
+            place: Place::Register(address),
+            ty,
+            qualifiers,
+        }
+    }
+
+    /// the LLVM operand holding the address of this variable
+    pub fn address(&self) -> Value {
+        match self.place {
+            Place::Register(ref address) => address.clone(),
+            Place::Global(ref name) => Value::Global(self.ty.ir_type().pointer(), name.clone()),
+        }
+    }
+
+    /// appends a load of this variable to `block`
+    pub fn gen_load(&self, block: &mut ir::Builder) -> Value {
+        block.load(self.address(), self.qualifiers.is_volatile)
+    }
+
+    /// appends the value of this variable as an operand of an expression to `block`: a load, or
+    /// the address of the first element of an array
+    pub fn gen_rvalue(&self, block: &mut ir::Builder) -> Value {
+        match self.ty {
+            Type::Array(ref element, _) => {
+                let zero = || Value::Constant(ir::Type::I64, 0);
+                let ty = element.ir_type().pointer();
+                block.getelementptr(ty, self.address(), vec![zero(), zero()])
+            }
+            _ => self.gen_load(block),
+        }
+    }
+
+    /// appends a store of `value` to this variable to `block`
+    pub fn gen_store(&self, value: Value, block: &mut ir::Builder) {
+        block.store(value, self.address(), self.qualifiers.is_volatile);
+    }
+}
+
+/// a global variable, including static local variables
+#[derive(Debug, Clone)]
+pub struct Global {
+    pub ty: Type,
+    pub qualifiers: Qualifiers,
+
+    /// whether it has internal linkage (`static`)
+    pub internal: bool,
+
+    /// None for a tentative definition or a declaration, which are zero-initialized if the
+    /// variable is defined
+    pub init: Option<StaticInit>,
+
+    /// false if only declared with `extern`
+    pub is_definition: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub ty: FunctionType,
+    pub internal: bool,
+    pub defined: bool,
+}
+
+#[derive(Default)]
+struct Scope {
+    variables: HashMap<String, Variable>,
+
+    /// where the variables are declared
+    spans: HashMap<String, Span>,
+
+    /// the unique names of the structures by their tags
+    tags: HashMap<String, String>,
+
+    /// the local variables not referred to yet, and their declarations, in the order of
+    /// declaration
+    unused: Vec<(String, Span)>,
+}
+
+/// state shared by code generation of a whole translation unit: identifiers in scope, and the
+/// globals that are added to the module after the functions.
+pub struct Env {
+    /// innermost scope last.  the first one is the file scope.
+    scopes: Vec<Scope>,
+
+    /// global variables by their LLVM name
+    globals: BTreeMap<String, Global>,
+
+    /// the spans of the last declarations of the global variables
+    global_spans: HashMap<String, Span>,
+
+    /// members of the structures by their unique names.  None for an incomplete structure.
+    structs: BTreeMap<String, Option<Vec<Member>>>,
+
+    /// functions declared (explicitly or implicitly) or defined
+    functions: BTreeMap<String, Function>,
+
+    /// the types of the functions at the end of the translation unit, which their addresses
+    /// have in the module.  a later declaration may give parameters to an earlier one.
+    prototypes: BTreeMap<String, FunctionType>,
+
+    /// the module which the functions, the structure types and the string literals are added
+    /// to
+    module: ir::Module,
+
+    /// the name and the return type of the function being generated
+    current: Option<(String, Type)>,
+
+    /// the IR of the function being generated
+    function: Option<ir::Function>,
+
+    /// the warnings found by the semantic analysis
+    warnings: Vec<Diagnostic>,
+
+    types: Types,
+}
+
+impl Default for Env {
+    fn default() -> Env {
+        Env::new()
+    }
+}
+
+impl Env {
+    pub fn new() -> Env {
+        Env {
+            scopes: vec![Scope::default()],
+            globals: BTreeMap::new(),
+            global_spans: HashMap::new(),
+            structs: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            prototypes: BTreeMap::new(),
+            module: ir::Module::new(),
+            current: None,
+            function: None,
+            warnings: Vec::new(),
+            types: Types::new(),
+        }
+    }
+
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    /// leaves the innermost scope, warning about the variables in it that were never used
+    pub fn leave_scope(&mut self) {
+        assert!(self.scopes.len() > 1, "ICE: cannot leave the file scope");
+        let scope = self.scopes.pop().expect("ICE: no scope to leave");
+        for (name, span) in scope.unused {
+            self.warn(span, DiagnosticKind::UnusedVariable(name.to_owned()));
+        }
+    }
+
+    /// starts tracking the uses of the local variable `name` just declared at `span`
+    pub fn track_usage(&mut self, name: &str, span: Span) {
+        let scope = self.scopes.last_mut().expect("ICE: no scope to declare in");
+        scope.unused.push((name.to_owned(), span));
+    }
+
+    /// records a use of the variable `name`
+    pub fn mark_used(&mut self, name: &str) {
+        let scope = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find(|scope| scope.variables.contains_key(name));
+        if let Some(scope) = scope {
+            scope.unused.retain(|(unused, _)| unused != name);
+        }
+    }
+
+    pub fn warn(&mut self, span: Span, kind: DiagnosticKind) {
+        self.warnings.push(Diagnostic::new(span, kind));
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<Diagnostic> {
+        mem::take(&mut self.warnings)
+    }
+
+    /// records the type of the expression or the declarator at `span`
+    pub fn record_type(&mut self, span: Span, ty: &Type) {
+        self.types.insert(span, ty.clone());
+    }
+
+    pub fn take_types(&mut self) -> Types {
+        mem::take(&mut self.types)
+    }
+
+    /// declares `name` in the current scope.  `span` is the declaration, where errors are
+    /// reported.
+    pub fn declare(&mut self, name: &str, variable: Variable, span: Span) -> CheckResult<()> {
+        let scope = self.scopes.last_mut().expect("ICE: no scope to declare in");
+        if scope.variables.contains_key(name) {
+            return Err(redefinition(name, span, scope.spans[name]));
+        }
+        scope.variables.insert(name.to_owned(), variable);
+        scope.spans.insert(name.to_owned(), span);
+        Ok(())
+    }
+
+    pub fn try_lookup(&self, name: &str) -> Option<&Variable> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter_map(|scope| scope.variables.get(name))
+            .next()
+    }
+
+    /// declares or defines a global variable with its source name, and makes it visible in
+    /// the current scope.  a declaration with `extern` (`is_extern`) takes the linkage of the
+    /// previous one.
+    pub fn declare_global(
+        &mut self,
+        name: &str,
+        global: Global,
+        is_extern: bool,
+        span: Span,
+    ) -> CheckResult<()> {
+        if self.functions.contains_key(name) {
+            return Err(Diagnostic::new(
+                span,
+                DiagnosticKind::DifferentKindOfSymbol(name.to_owned()),
+            ));
+        }
+
+        let merged = match self.globals.get(name) {
+            None => global,
+            Some(prev) => {
+                // an array of unknown size is completed by another declaration
+                let ty = match (&prev.ty, &global.ty) {
+                    _ if prev.qualifiers != global.qualifiers => None,
+                    (Type::Array(prev_element, None), Type::Array(element, Some(_)))
+                        if prev_element == element =>
+                    {
+                        Some(global.ty.clone())
+                    }
+                    (Type::Array(prev_element, Some(_)), Type::Array(element, None))
+                        if prev_element == element =>
+                    {
+                        Some(prev.ty.clone())
+                    }
+                    (prev_ty, ty) if prev_ty == ty => Some(ty.clone()),
+                    _ => None,
+                };
+                let ty = ty.ok_or_else(|| {
+                    Diagnostic::new(span, DiagnosticKind::ConflictingTypes(name.to_owned()))
+                })?;
+                if prev.init.is_some() && global.init.is_some() {
+                    return Err(Diagnostic::new(
+                        span,
+                        DiagnosticKind::Redefinition(name.to_owned()),
+                    ));
+                }
+                // the linkages conflict (C11 6.2.2p7)
+                let kind = if global.internal && !prev.internal {
+                    Some(DiagnosticKind::StaticFollowsNonStatic(name.to_owned()))
+                } else if prev.internal && !global.internal && !is_extern {
+                    Some(DiagnosticKind::NonStaticFollowsStatic(name.to_owned()))
+                } else {
+                    None
+                };
+                if let Some(kind) = kind {
+                    let diagnostic = Diagnostic::new(span, kind);
+                    return Err(match self.global_spans.get(name) {
+                        Some(&previous) => {
+                            diagnostic.with_label(previous, Note::PreviousDeclaration)
+                        }
+                        None => diagnostic,
+                    });
+                }
+                let prev = self
+                    .globals
+                    .remove(name)
+                    .expect("ICE: no previous declaration");
+                Global {
+                    ty,
+                    internal: prev.internal || global.internal,
+                    init: prev.init.or(global.init),
+                    is_definition: prev.is_definition || global.is_definition,
+                    ..global
+                }
+            }
+        };
+
+        let variable = Variable {
+            place: Place::Global(name.to_owned()),
+            ty: merged.ty.clone(),
+            qualifiers: merged.qualifiers,
+        };
+        self.globals.insert(name.to_owned(), merged);
+        self.global_spans.insert(name.to_owned(), span);
+
+        // redeclarations at file scope are allowed
+        let is_file_scope = self.scopes.len() == 1;
+        let scope = self.scopes.last_mut().expect("ICE: no scope to declare in");
+        if scope.variables.insert(name.to_owned(), variable).is_some() && !is_file_scope {
+            return Err(redefinition(name, span, scope.spans[name]));
+        }
+        scope.spans.insert(name.to_owned(), span);
+        Ok(())
+    }
+
+    /// defines a static local variable as a global with a unique name
+    pub fn define_static_local(
+        &mut self,
+        name: &str,
+        global: Global,
+        span: Span,
+    ) -> CheckResult<()> {
+        let function = match self.current {
+            Some((ref function, _)) => function.clone(),
+            None => panic!("ICE: static local variable outside of a function"),
+        };
+        let mut global_name = format!("{}.{}", function, name);
+        let mut suffix = 0;
+        while self.globals.contains_key(&global_name) {
+            suffix += 1;
+            global_name = format!("{}.{}.{}", function, name, suffix);
+        }
+
+        self.declare(
+            name,
+            Variable {
+                place: Place::Global(global_name.clone()),
+                ty: global.ty.clone(),
+                qualifiers: global.qualifiers,
+            },
+            span,
+        )?;
+        self.globals.insert(global_name, global);
+        Ok(())
+    }
+
+    /// declares or defines a function.  declaring a function again with a compatible type is
+    /// allowed, and internal linkage of an earlier declaration is kept.
+    pub fn declare_function(
+        &mut self,
+        name: &str,
+        ty: FunctionType,
+        internal: bool,
+        defined: bool,
+        span: Span,
+    ) -> CheckResult<()> {
+        if self.scopes[0].variables.contains_key(name) {
+            return Err(Diagnostic::new(
+                span,
+                DiagnosticKind::DifferentKindOfSymbol(name.to_owned()),
+            ));
+        }
+
+        let function = match self.functions.get(name) {
+            None => Function {
+                ty,
+                internal,
+                defined,
+            },
+            Some(prev) => {
+                // a declaration without a prototype is compatible with any prototype
+                let compatible = prev.ty.ret == ty.ret
+                    && (prev.ty.params.is_none()
+                        || ty.params.is_none()
+                        || (prev.ty.params == ty.params && prev.ty.variadic == ty.variadic));
+                if !compatible {
+                    return Err(Diagnostic::new(
+                        span,
+                        DiagnosticKind::ConflictingTypes(name.to_owned()),
+                    ));
+                }
+                if prev.defined && defined {
+                    return Err(Diagnostic::new(
+                        span,
+                        DiagnosticKind::Redefinition(name.to_owned()),
+                    ));
+                }
+                Function {
+                    ty: if ty.params.is_some() {
+                        ty
+                    } else {
+                        prev.ty.clone()
+                    },
+                    internal: prev.internal || internal,
+                    defined: prev.defined || defined,
+                }
+            }
+        };
+        self.functions.insert(name.to_owned(), function);
+        Ok(())
+    }
+
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.get(name)
+    }
+
+    /// returns the type of the function `name`, declaring it implicitly as `int name()` if it
+    /// has not been declared (C89 implicit declaration).
+    pub fn function_or_implicit(&mut self, name: &str, span: Span) -> CheckResult<FunctionType> {
+        if let Some(function) = self.functions.get(name) {
+            return Ok(function.ty.clone());
+        }
+        let ty = FunctionType {
+            ret: Type::INT,
+            params: None,
+            variadic: false,
+        };
+        self.declare_function(name, ty.clone(), false, false, span)?;
+        Ok(ty)
+    }
+
+    /// the types of the functions declared so far
+    pub fn prototypes(&self) -> BTreeMap<String, FunctionType> {
+        self.functions
+            .iter()
+            .map(|(name, function)| (name.clone(), function.ty.clone()))
+            .collect()
+    }
+
+    /// gives the types of the functions at the end of the translation unit, found by checking
+    /// it before the code generation
+    pub fn set_prototypes(&mut self, prototypes: BTreeMap<String, FunctionType>) {
+        self.prototypes = prototypes;
+    }
+
+    /// the address of the function `name`, whose type is that of the function at the end of
+    /// the translation unit rather than at the reference
+    pub fn function_address(&self, name: &str) -> Value {
+        let ty = match self.prototypes.get(name) {
+            Some(ty) => ty,
+            None => {
+                &self
+                    .functions
+                    .get(name)
+                    .expect("ICE: undeclared function")
+                    .ty
+            }
+        };
+        Value::Global(ty.ir_type().pointer(), name.to_owned())
+    }
+
+    /// a unique name for a new structure
+    fn new_struct(&mut self, tag: Option<&str>) -> String {
+        let base = tag.unwrap_or("anon");
+        let mut name = base.to_owned();
+        let mut suffix = 0;
+        while self.structs.contains_key(&name) {
+            suffix += 1;
+            name = format!("{}.{}", base, suffix);
+        }
+        self.structs.insert(name.clone(), None);
+        if let Some(tag) = tag {
+            let scope = self.scopes.last_mut().expect("ICE: no scope to declare in");
+            scope.tags.insert(tag.to_owned(), name.clone());
+        }
+        name
+    }
+
+    /// the name of the structure `struct tag`, which is declared as an incomplete structure in
+    /// the current scope if it is not visible
+    pub fn lookup_struct(&mut self, tag: &str) -> String {
+        let found = self
+            .scopes
+            .iter()
+            .rev()
+            .filter_map(|scope| scope.tags.get(tag))
+            .next()
+            .cloned();
+        found.unwrap_or_else(|| self.new_struct(Some(tag)))
+    }
+
+    /// starts the definition of a structure and returns its name.  a structure declared in the
+    /// current scope but not yet defined is completed by this definition.
+    pub fn define_struct(&mut self, tag: Option<&str>, span: Span) -> CheckResult<String> {
+        let declared = tag.and_then(|tag| {
+            let scope = self.scopes.last().expect("ICE: no scope to declare in");
+            scope.tags.get(tag).cloned()
+        });
+        match declared {
+            Some(ref name) if self.structs[name].is_some() => Err(Diagnostic::new(
+                span,
+                DiagnosticKind::Redefinition(format!("struct {}", tag.unwrap_or_default())),
+            )),
+            Some(name) => Ok(name),
+            None => Ok(self.new_struct(tag)),
+        }
+    }
+
+    /// the name of a const-qualified member of `ty`, which makes a structure non-modifiable.
+    /// the members of the member structures and arrays are included (C11 6.3.2.1).
+    pub fn const_member(&self, ty: &Type) -> Option<&str> {
+        match ty {
+            Type::Struct(name) => match self.structs.get(name) {
+                Some(Some(members)) => members.iter().find_map(|member| {
+                    if member.qualifiers.is_const {
+                        Some(member.name.as_str())
+                    } else {
+                        self.const_member(&member.ty)
+                    }
+                }),
+                _ => None,
+            },
+            Type::Array(element, _) => self.const_member(element),
+            _ => None,
+        }
+    }
+
+    /// defines the members of the structure `name`, and its type in the module
+    pub fn complete_struct(
+        &mut self,
+        name: &str,
+        members: Vec<Member>,
+        span: Span,
+    ) -> CheckResult<()> {
+        if self.current.is_some() {
+            return Err(Diagnostic::new(span, DiagnosticKind::StructInFunction));
+        }
+        let types = members.iter().map(|member| member.ty.ir_type()).collect();
+        self.module.define_struct(name, Some(types));
+        self.structs.insert(name.to_owned(), Some(members));
+        Ok(())
+    }
+
+    /// the members of the structure `name`.  panics if it is incomplete.
+    pub fn struct_members(&self, name: &str) -> &[Member] {
+        match self.structs.get(name) {
+            Some(Some(members)) => members,
+            _ => panic!("ICE: incomplete definition of type `struct {}`", name),
+        }
+    }
+
+    /// whether objects of type `ty` can be defined
+    pub fn is_complete(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Void | Type::Function(_) | Type::Array(_, None) => false,
+            Type::Array(element, Some(_)) => self.is_complete(element),
+            Type::Struct(name) => self.structs.get(name).is_some_and(Option::is_some),
+            Type::Int(_) | Type::Float(_) | Type::Pointer(..) => true,
+        }
+    }
+
+    /// the size and the alignment in bytes of an object of the complete type `ty`, as laid out
+    /// by the data layout of the target
+    pub fn layout(&self, ty: &Type) -> (usize, usize) {
+        match ty {
+            Type::Int(ty) => {
+                let size = ty.bits() as usize / 8;
+                (size, size)
+            }
+            Type::Float(FloatType::Float) => (4, 4),
+            Type::Float(FloatType::Double) | Type::Pointer(..) => (8, 8),
+            Type::Array(element, Some(len)) => {
+                let (size, align) = self.layout(element);
+                (size * len, align)
+            }
+            Type::Struct(name) => {
+                let (mut size, mut align) = (0usize, 1);
+                for member in self.struct_members(name) {
+                    let (member_size, member_align) = self.layout(&member.ty);
+                    size = size.next_multiple_of(member_align) + member_size;
+                    align = align.max(member_align);
+                }
+                (size.next_multiple_of(align), align)
+            }
+            Type::Void | Type::Function(_) | Type::Array(_, None) => {
+                panic!("ICE: the layout of the incomplete type {}", ty)
+            }
+        }
+    }
+
+    pub fn begin_function(&mut self, name: &str, ret: Type) {
+        self.current = Some((name.to_owned(), ret));
+    }
+
+    pub fn end_function(&mut self) {
+        self.current = None;
+    }
+
+    /// the return type of the function being generated or analyzed
+    pub fn return_type(&self) -> &Type {
+        match self.current {
+            Some((_, ref ret)) => ret,
+            None => panic!("ICE: return statement outside of a function"),
+        }
+    }
+
+    /// starts generating the IR of `function`, which has the entry block
+    pub fn begin_ir(&mut self, function: ir::Function) {
+        self.function = Some(function);
+    }
+
+    /// finishes the IR of the function being generated, and adds it to the module
+    pub fn end_ir(&mut self) {
+        let function = self.function.take().expect("ICE: no function is generated");
+        self.module.add_function(function)
+    }
+
+    /// the builder of the block of the function being generated which the code is appended to
+    pub fn block(&mut self) -> ir::Builder<'_> {
+        match self.function {
+            Some(ref mut function) => function.last_block(),
+            None => panic!("ICE: code outside of a function"),
+        }
+    }
+
+    /// starts a new unnamed block, after a terminator
+    pub fn start_block(&mut self) {
+        match self.function {
+            Some(ref mut function) => {
+                function.add_block();
+            }
+            None => panic!("ICE: code outside of a function"),
+        }
+    }
+
+    /// appends the zero-fill of the object of type `ty` at `address` by `llvm.memset`, which
+    /// `llc` handles quickly even for a large array unlike a store of `zeroinitializer`
+    pub fn zero_fill(&mut self, address: Value, ty: &Type, volatile: bool) {
+        let memset = ir::Type::Function(
+            Box::new(ir::Type::Void),
+            vec![
+                ir::Type::I8.pointer(),
+                ir::Type::I8,
+                ir::Type::I64,
+                ir::Type::I1,
+            ],
+            false,
+        );
+        self.module.declare(MEMSET, memset.clone());
+
+        let size = self.layout(ty).0;
+        let mut block = self.block();
+        let bytes = block.cast(ir::CastOp::BitCast, address, ir::Type::I8.pointer());
+        let args = vec![
+            bytes,
+            Value::Constant(ir::Type::I8, 0),
+            Value::Constant(ir::Type::I64, size as i64),
+            Value::Constant(ir::Type::I1, volatile as i64),
+        ];
+        block.call_void(Value::Global(memset.pointer(), MEMSET.to_owned()), args);
+    }
+
+    /// registers a string literal and returns its global, which points to the array of the
+    /// characters
+    pub fn add_string(&mut self, bytes: Vec<u8>) -> Value {
+        self.module.add_string(bytes)
+    }
+
+    /// adds the incomplete structure types, the global variables and the declarations of the
+    /// functions that are not defined to the module, and returns it
+    pub fn into_module(mut self) -> ir::Module {
+        for (name, members) in &self.structs {
+            if members.is_none() {
+                self.module.define_struct(name, None);
+            }
+        }
+
+        for (name, global) in &self.globals {
+            let init = if !global.is_definition {
+                None
+            } else if let Some(ref init) = global.init {
+                Some(init.llvm_constant(&global.ty, &self))
+            } else {
+                Some(global.ty.llvm_zero())
+            };
+            self.module.add_global(ir::Global {
+                name: name.clone(),
+                ty: global.ty.ir_type(),
+                init,
+                internal: global.internal,
+                constant: global.qualifiers.is_const,
+            });
+        }
+
+        for (name, function) in &self.functions {
+            if !function.defined {
+                self.module.declare(name, function.ty.ir_type());
+            }
+        }
+        self.module
+    }
+}
+
+/// the error of a redefinition of the variable `name` at `span`, defined before at `previous`
+fn redefinition(name: &str, span: Span, previous: Span) -> Diagnostic {
+    Diagnostic::new(span, DiagnosticKind::Redefinition(name.to_owned()))
+        .with_label(previous, Note::PreviousDefinition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::IntType;
+
+    #[test]
+    fn nested_scopes() {
+        let mut function = ir::Function::new("f", ir::Type::Void);
+        function.add_block();
+        let outer = function.last_block().alloca(ir::Type::I32);
+        let inner = function.last_block().alloca(ir::Type::Double);
+        let mut env = Env::new();
+        env.declare(
+            "x",
+            Variable::local(outer.clone(), Type::INT, Qualifiers::default()),
+            Span::default(),
+        )
+        .unwrap();
+        env.enter_scope();
+        env.declare(
+            "x",
+            Variable::local(inner.clone(), Type::DOUBLE, Qualifiers::default()),
+            Span::default(),
+        )
+        .unwrap();
+        let address = |env: &Env| env.try_lookup("x").unwrap().address();
+        assert_eq!(address(&env), inner);
+        env.leave_scope();
+        assert_eq!(address(&env), outer);
+    }
+
+    #[test]
+    fn redefinition() {
+        let mut env = Env::new();
+        env.enter_scope();
+        let span = Span::new(10, 11);
+        env.declare(
+            "x",
+            Variable::local(
+                Value::Constant(ir::Type::I32, 0),
+                Type::INT,
+                Qualifiers::default(),
+            ),
+            span,
+        )
+        .unwrap();
+        let diagnostic = env
+            .declare(
+                "x",
+                Variable::local(
+                    Value::Constant(ir::Type::I32, 0),
+                    Type::INT,
+                    Qualifiers::default(),
+                ),
+                span,
+            )
+            .unwrap_err();
+        assert_eq!(diagnostic.message(), "redefinition of `x`");
+        assert_eq!(diagnostic.span, span);
+    }
+
+    #[test]
+    fn static_locals_get_unique_names() {
+        let global = Global {
+            ty: Type::INT,
+            qualifiers: Qualifiers::default(),
+            internal: true,
+            init: None,
+            is_definition: true,
+        };
+        let mut env = Env::new();
+        env.begin_function("main", Type::INT);
+        env.enter_scope();
+        env.define_static_local("x", global.clone(), Span::default())
+            .unwrap();
+        let address = |name: &str| Value::Global(ir::Type::I32.pointer(), name.to_owned());
+        assert_eq!(env.try_lookup("x").unwrap().address(), address("main.x"));
+        env.enter_scope();
+        env.define_static_local("x", global, Span::default())
+            .unwrap();
+        assert_eq!(env.try_lookup("x").unwrap().address(), address("main.x.1"));
+    }
+
+    #[test]
+    fn struct_tags() {
+        let mut env = Env::new();
+        let outer = env.define_struct(Some("s"), Span::default()).unwrap();
+        env.complete_struct(&outer, Vec::new(), Span::default())
+            .unwrap();
+        env.enter_scope();
+        assert_eq!(env.lookup_struct("s"), "s");
+        let inner = env.define_struct(Some("s"), Span::default()).unwrap();
+        assert_eq!(inner, "s.1");
+        assert!(!env.is_complete(&Type::Struct(inner)));
+        env.leave_scope();
+        assert_eq!(env.lookup_struct("s"), "s");
+    }
+
+    #[test]
+    fn struct_layout() {
+        let mut env = Env::new();
+        let name = env.define_struct(Some("s"), Span::default()).unwrap();
+        let member = |name: &str, ty: Type| Member {
+            name: name.to_owned(),
+            ty,
+            qualifiers: Qualifiers::default(),
+        };
+        let chars = Type::Array(Box::new(Type::Int(IntType::CHAR)), Some(3));
+        let members = vec![member("c", chars), member("d", Type::DOUBLE)];
+        env.complete_struct(&name, members, Span::default())
+            .unwrap();
+        assert_eq!(env.layout(&Type::Struct(name.clone())), (16, 8));
+        let array = Type::Array(Box::new(Type::Struct(name)), Some(2));
+        assert_eq!(env.layout(&array), (32, 8));
+        let pointer = Type::Pointer(Box::new(Type::INT), Qualifiers::default());
+        assert_eq!(env.layout(&pointer), (8, 8));
+    }
+
+    #[test]
+    fn merge_global_declarations() {
+        let mut env = Env::new();
+        let declaration = Global {
+            ty: Type::INT,
+            qualifiers: Qualifiers::default(),
+            internal: false,
+            init: None,
+            is_definition: false,
+        };
+        env.declare_global("g", declaration.clone(), false, Span::default())
+            .unwrap();
+        env.declare_global(
+            "g",
+            Global {
+                is_definition: true,
+                ..declaration
+            },
+            false,
+            Span::default(),
+        )
+        .unwrap();
+        assert!(env.globals["g"].is_definition);
+    }
+}
@@ -1,22 +1,40 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Expr {
     Assignment(Box<Assignment>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Assignment {
+    Equality(Box<Equality>),
+
+    /// the left operand is checked to be a modifiable lvalue by the semantic analysis
+    Assign(Box<Equality>, Box<Assignment>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Equality {
+    Relational(Box<Relational>),
+    Eq(Box<Equality>, Box<Relational>),
+    Ne(Box<Equality>, Box<Relational>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Relational {
     Additive(Box<Additive>),
-    Assign(Box<Unary>, Box<Assignment>),
+    Lt(Box<Relational>, Box<Additive>),
+    Gt(Box<Relational>, Box<Additive>),
+    Le(Box<Relational>, Box<Additive>),
+    Ge(Box<Relational>, Box<Additive>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Additive {
     Multiplicative(Box<Multiplicative>),
     Add(Box<Additive>, Box<Multiplicative>),
     Sub(Box<Additive>, Box<Multiplicative>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Multiplicative {
     Unary(Box<Unary>),
     Mul(Box<Multiplicative>, Box<Unary>),
@@ -24,64 +42,684 @@ pub enum Multiplicative {
     Rem(Box<Multiplicative>, Box<Unary>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Unary {
+    Postfix(Box<Postfix>),
+    Plus(Box<Unary>, Span),
+    Minus(Box<Unary>, Span),
+    Deref(Box<Unary>, Span),
+    AddressOf(Box<Unary>, Span),
+    Cast(Box<TypeName>, Box<Unary>, Span),
+}
+
+#[derive(Debug, Clone)]
+pub enum Postfix {
     Primary(Box<Primary>),
-    UnaryPlus(Box<Unary>),
-    UnaryMinus(Box<Unary>),
+    Call(Box<Postfix>, Vec<Assignment>, Span),
+    Index(Box<Postfix>, Box<Expr>, Span),
+    Member(Box<Postfix>, String, Span),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Primary {
-    Constant(i32),
-    Paren(Box<Expr>),
+    Constant(IntLiteral, Span),
+    FloatConstant(FloatLiteral, Span),
+    StringLiteral(Vec<u8>, Span),
+    Ident(String, Span),
+    Paren(Box<Expr>, Span),
 }
 
+use super::checked;
+use super::constant::Constant;
+use super::decl::{self, TypeName};
+use super::dump::Node;
+use super::env::{Env, Place, Types, Variable};
+use super::literal::{self, FloatLiteral, IntLiteral};
+use diagnostic::{CheckResult, Diagnostic, ParseResult};
+use ir::{self, BinaryBuilder, CastOp, FloatPredicate, IntPredicate, Value};
+use message::{Conversion, DiagnosticKind, Term};
+use source::Span;
 use token::{Token, Tokens};
+use types::{FunctionType, IntType, Qualifiers, Type};
+
+/// converts `value` from type `from` to type `to`.  returns the converted value, which is
+/// `value` itself if no conversion is needed.
+pub fn gen_conversion(value: Value, from: &Type, to: &Type, env: &mut Env) -> Value {
+    let op = match (from, to) {
+        (Type::Int(from), Type::Int(to)) if from.bits() < to.bits() => {
+            if from.signed {
+                CastOp::SExt
+            } else {
+                CastOp::ZExt
+            }
+        }
+        (Type::Int(from), Type::Int(to)) if from.bits() > to.bits() => CastOp::Trunc,
+        (Type::Int(from), Type::Float(_)) => {
+            if from.signed {
+                CastOp::SIToFP
+            } else {
+                CastOp::UIToFP
+            }
+        }
+        (Type::Float(_), Type::Int(to)) => {
+            if to.signed {
+                CastOp::FPToSI
+            } else {
+                CastOp::FPToUI
+            }
+        }
+        (Type::Float(from), Type::Float(to)) if from < to => CastOp::FPExt,
+        (Type::Float(from), Type::Float(to)) if from > to => CastOp::FPTrunc,
+        (Type::Pointer(..), Type::Pointer(..)) if from.ir_type() != to.ir_type() => CastOp::BitCast,
+        (Type::Pointer(..), Type::Int(_)) => CastOp::PtrToInt,
+        (Type::Int(_), Type::Pointer(..)) => CastOp::IntToPtr,
+        _ if from.ir_type() == to.ir_type() => return value,
+        _ => panic!("ICE: cannot convert {:?} to {:?}", from, to),
+    };
+    env.block().cast(op, value, to.ir_type())
+}
+
+/// the variable `name`.  reports an error at `span` if it is not declared.
+fn lookup<'e>(env: &'e Env, name: &str, span: Span) -> CheckResult<&'e Variable> {
+    match env.try_lookup(name) {
+        Some(variable) => Ok(variable),
+        None => Err(Diagnostic::new(
+            span,
+            DiagnosticKind::UndeclaredIdentifier(name.to_owned()),
+        )),
+    }
+}
+
+/// the type of both operands of a binary expression at `span` after the usual arithmetic
+/// conversions
+fn common_type(lhs: &Type, rhs: &Type, span: Span) -> CheckResult<Type> {
+    match lhs.common_checked(rhs) {
+        Some(ty) => Ok(ty),
+        None => Err(Diagnostic::new(
+            span,
+            DiagnosticKind::InvalidOperands(lhs.clone(), rhs.clone()),
+        )),
+    }
+}
+
+/// the type of `lhs op rhs` if it is pointer arithmetic: a pointer to a complete object type
+/// plus or minus an integer (C11 6.5.6)
+fn pointer_arith_type(op: Token, lhs: &Type, rhs: &Type, env: &Env) -> Option<Type> {
+    let pointer = match (op, lhs, rhs) {
+        (Token::OpAdd, Type::Pointer(..), Type::Int(_))
+        | (Token::OpSub, Type::Pointer(..), Type::Int(_)) => lhs,
+        (Token::OpAdd, Type::Int(_), Type::Pointer(..)) => rhs,
+        _ => return None,
+    };
+    match pointer {
+        Type::Pointer(pointee, _) if env.is_complete(pointee) => Some(pointer.clone()),
+        _ => None,
+    }
+}
+
+/// the type which both operands of the comparison `op` at `span` are converted to.  besides
+/// arithmetic operands, pointers to compatible types are compared, and for equality also a
+/// pointer with a pointer to `void` or with a null pointer constant (C11 6.5.8, 6.5.9).
+fn comparison_type(
+    op: Token,
+    (lhs, lhs_constant): (&Type, Option<Constant>),
+    (rhs, rhs_constant): (&Type, Option<Constant>),
+    span: Span,
+) -> CheckResult<Type> {
+    let equality = op == Token::OpEq || op == Token::OpNe;
+    let is_null = |constant| matches!(constant, Some(Constant::Int(0, _)));
+    match (lhs, rhs) {
+        (Type::Pointer(lhs_pointee, _), Type::Pointer(rhs_pointee, _)) => {
+            if lhs_pointee.is_compatible(rhs_pointee) {
+                return Ok(lhs.clone());
+            }
+            if equality && **lhs_pointee == Type::Void {
+                return Ok(lhs.clone());
+            }
+            if equality && **rhs_pointee == Type::Void {
+                return Ok(rhs.clone());
+            }
+        }
+        (Type::Pointer(..), Type::Int(_)) if equality && is_null(rhs_constant) => {
+            return Ok(lhs.clone())
+        }
+        (Type::Int(_), Type::Pointer(..)) if equality && is_null(lhs_constant) => {
+            return Ok(rhs.clone())
+        }
+        _ => {}
+    }
+    common_type(lhs, rhs, span)
+}
+
+/// checks `expr` and that its value can be converted to `ty` as if by assignment (C11 6.5.16.1).
+/// `conversion` is the kind of the conversion reported on an error.
+pub fn check_assignable(
+    ty: &Type,
+    expr: &Assignment,
+    env: &mut Env,
+    conversion: Conversion,
+) -> CheckResult<()> {
+    let expr_ty = expr.check(env)?;
+    let assignable = match (ty, &expr_ty) {
+        (to, from) if to.is_arithmetic() && from.is_arithmetic() => true,
+        (Type::Struct(to), Type::Struct(from)) => to == from,
+        // either pointer may point to `void`, and the pointed-to type may gain qualifiers but
+        // not lose them
+        (Type::Pointer(to, to_qualifiers), Type::Pointer(from, from_qualifiers)) => {
+            (**to == Type::Void || **from == Type::Void || to.is_compatible(from))
+                && to_qualifiers.contains(*from_qualifiers)
+        }
+        // a null pointer constant
+        (Type::Pointer(..), Type::Int(_)) => matches!(expr.constant(), Some(Constant::Int(0, _))),
+        _ => false,
+    };
+    if !assignable {
+        return Err(Diagnostic::new(
+            expr.span(),
+            DiagnosticKind::IncompatibleTypes(conversion, ty.clone(), expr_ty),
+        ));
+    }
+    Ok(())
+}
+
+/// warns if `lhs op rhs` is a constant expression at `span` whose result overflows
+fn check_overflow(
+    op: Token,
+    lhs: Option<Constant>,
+    rhs: Option<Constant>,
+    span: Span,
+    env: &mut Env,
+) {
+    if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+        if Constant::overflows(op, lhs, rhs) {
+            env.warn(span, DiagnosticKind::Overflow);
+        }
+    }
+}
+
+/// the builder of the LLVM instruction for the arithmetic operator `op` applied to operands of
+/// type `ty`
+fn arith_inst<'a>(op: Token, ty: &Type) -> BinaryBuilder<'a> {
+    match (op, ty) {
+        (Token::OpAdd, Type::Int(_)) => ir::Builder::add,
+        (Token::OpSub, Type::Int(_)) => ir::Builder::sub,
+        (Token::OpMul, Type::Int(_)) => ir::Builder::mul,
+        (Token::OpDiv, Type::Int(ty)) if ty.signed => ir::Builder::sdiv,
+        (Token::OpDiv, Type::Int(_)) => ir::Builder::udiv,
+        (Token::OpRem, Type::Int(ty)) if ty.signed => ir::Builder::srem,
+        (Token::OpRem, Type::Int(_)) => ir::Builder::urem,
+        (Token::OpAdd, Type::Float(_)) => ir::Builder::fadd,
+        (Token::OpSub, Type::Float(_)) => ir::Builder::fsub,
+        (Token::OpMul, Type::Float(_)) => ir::Builder::fmul,
+        (Token::OpDiv, Type::Float(_)) => ir::Builder::fdiv,
+        _ => panic!("ICE: invalid operand to binary {:?} ({:?})", op, ty),
+    }
+}
+
+/// appends the LLVM comparison (`icmp` or `fcmp` with its predicate) for the operator `op`
+/// applied to operands of type `ty`
+fn compare_inst(op: Token, ty: &Type, lhs: Value, rhs: Value, block: &mut ir::Builder) -> Value {
+    let (signed, unsigned, float) = match op {
+        Token::OpEq => (IntPredicate::Eq, IntPredicate::Eq, FloatPredicate::Oeq),
+        Token::OpNe => (IntPredicate::Ne, IntPredicate::Ne, FloatPredicate::Une),
+        Token::OpLt => (IntPredicate::Slt, IntPredicate::Ult, FloatPredicate::Olt),
+        Token::OpGt => (IntPredicate::Sgt, IntPredicate::Ugt, FloatPredicate::Ogt),
+        Token::OpLe => (IntPredicate::Sle, IntPredicate::Ule, FloatPredicate::Ole),
+        Token::OpGe => (IntPredicate::Sge, IntPredicate::Uge, FloatPredicate::Oge),
+        _ => panic!("ICE: {:?} is not a comparison operator", op),
+    };
+    match ty {
+        Type::Int(int) if int.signed => block.icmp(signed, lhs, rhs),
+        Type::Int(_) => block.icmp(unsigned, lhs, rhs),
+        Type::Float(_) => block.fcmp(float, lhs, rhs),
+        Type::Pointer(..) => block.icmp(unsigned, lhs, rhs),
+        _ => panic!("ICE: invalid operands to comparison {:?} ({:?})", op, ty),
+    }
+}
 
 impl Expr {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Expr {
-        Expr::Assignment(Box::new(Assignment::parse(tokens)))
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Expr> {
+        Ok(Expr::Assignment(Box::new(Assignment::parse(tokens)?)))
     }
 
-    pub fn gen_code(self, reg: usize) -> usize {
+    pub fn span(&self) -> Span {
         match self {
-            Expr::Assignment(assignment) => assignment.gen_code(reg),
+            Expr::Assignment(assignment) => assignment.span(),
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Expr::Assignment(assignment) => assignment.ty(env),
+        }
+    }
+
+    /// checks this expression and all its subexpressions, and returns its type
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Expr::Assignment(assignment) => assignment.check(env),
+        }
+    }
+
+    /// the value of this expression if it is an arithmetic constant expression
+    pub fn constant(&self) -> Option<Constant> {
+        match self {
+            Expr::Assignment(assignment) => assignment.constant(),
+        }
+    }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        match self {
+            Expr::Assignment(assignment) => assignment.gen_code(env),
+        }
+    }
+
+    fn into_unary(self) -> Option<Unary> {
+        match self {
+            Expr::Assignment(assignment) => match *assignment {
+                Assignment::Equality(equality) => equality.into_unary(),
+                Assignment::Assign(..) => None,
+            },
+        }
+    }
+
+    fn as_unary(&self) -> Option<&Unary> {
+        match self {
+            Expr::Assignment(assignment) => match **assignment {
+                Assignment::Equality(ref equality) => equality.as_unary(),
+                Assignment::Assign(..) => None,
+            },
+        }
+    }
+
+    /// the node for the dump of the syntax tree
+    pub fn dump(&self, types: &Types) -> Node {
+        match self {
+            Expr::Assignment(assignment) => assignment.dump(types),
         }
     }
 }
 
-/// <assignment> ::= <additive>
+/// <assignment> ::= <equality>
 ///                | <unary> OpAssign <assignment>
 impl Assignment {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Assignment {
-        let lhs = Additive::parse(tokens);
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Assignment> {
+        let lhs = Equality::parse(tokens)?;
         match tokens.peek() {
             Some(Token::OpAssign) => {
-                tokens.eat(Token::OpAssign);
-                let rhs = Assignment::parse(tokens);
-                let lhs = match lhs {
-                    Additive::Multiplicative(lhs) => *lhs,
-                    _ => panic!("left operand of assignment must be unary expression")
+                tokens.eat(Token::OpAssign)?;
+                let rhs = Assignment::parse(tokens)?;
+                Ok(Assignment::Assign(Box::new(lhs), Box::new(rhs)))
+            }
+            _ => Ok(Assignment::Equality(Box::new(lhs))),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Assignment::Equality(equality) => equality.span(),
+            Assignment::Assign(lhs, assignment) => lhs.span().to(assignment.span()),
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Assignment::Equality(equality) => equality.ty(env),
+            Assignment::Assign(lhs, _) => lhs.ty(env),
+        }
+    }
+
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
+        let (lhs, rhs) = match self {
+            Assignment::Equality(equality) => return equality.check(env),
+            Assignment::Assign(lhs, rhs) => (lhs, rhs),
+        };
+        let span = lhs.span();
+        let unary = match lhs.as_unary() {
+            Some(unary) => unary,
+            None => return Err(Diagnostic::new(span, DiagnosticKind::NotAssignable)),
+        };
+        unary.check(env)?;
+        let ty = match unary.lvalue(env)? {
+            None => return Err(Diagnostic::new(span, DiagnosticKind::NotAssignable)),
+            Some((Type::Array(..), _)) => {
+                return Err(Diagnostic::new(span, DiagnosticKind::ArrayNotAssignable))
+            }
+            Some((_, qualifiers)) if qualifiers.is_const => {
+                let kind = match unary.as_ident() {
+                    Some(name) => DiagnosticKind::ConstVariable(name.to_owned()),
+                    None => DiagnosticKind::ConstExpression,
                 };
-                let lhs = match lhs {
-                    Multiplicative::Unary(lhs) => *lhs,
-                    _ => panic!("left operand of assignment must be unary expression")
+                return Err(Diagnostic::new(span, kind));
+            }
+            Some((ty, _)) => ty,
+        };
+        if let Some(member) = env.const_member(&ty) {
+            let kind = DiagnosticKind::ConstMember(member.to_owned());
+            return Err(Diagnostic::new(span, kind));
+        }
+        check_assignable(&ty, rhs, env, Conversion::Assignment)?;
+        let ty = unary.ty(env)?;
+        env.record_type(self.span(), &ty);
+        Ok(ty)
+    }
+
+    pub fn constant(&self) -> Option<Constant> {
+        match self {
+            Assignment::Equality(equality) => equality.constant(),
+            Assignment::Assign(..) => None,
+        }
+    }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        match self {
+            Assignment::Equality(equality) => equality.gen_code(env),
+            Assignment::Assign(lhs, assignment) => {
+                let unary = lhs.into_unary().expect("ICE: assignment to non-lvalue");
+                let variable = unary.gen_address(env);
+                let rhs_ty = checked(assignment.ty(env));
+                let value = assignment.gen_code(env);
+                let value = gen_conversion(value, &rhs_ty, &variable.ty, env);
+                variable.gen_store(value.clone(), &mut env.block());
+                value
+            }
+        }
+    }
+
+    /// the contents of the string literal if this expression is just a string literal
+    pub fn string_literal(&self) -> Option<&[u8]> {
+        match self {
+            Assignment::Equality(equality) => equality.as_unary()?.string_literal(),
+            Assignment::Assign(..) => None,
+        }
+    }
+
+    /// the name of the function if this expression is its address (`f` or `&f`), which is an
+    /// address constant
+    pub fn function_address(&self, env: &Env) -> Option<&str> {
+        match self {
+            Assignment::Equality(equality) => equality.as_unary()?.function_address(env),
+            Assignment::Assign(..) => None,
+        }
+    }
+
+    /// the address if this expression is an address constant other than a function: the
+    /// address of a variable with static storage duration, or an array of static storage
+    /// duration or a string literal converted to the pointer to its first element (C11 6.6)
+    pub fn address_constant(&self, env: &mut Env) -> Option<Value> {
+        match self {
+            Assignment::Equality(equality) => equality.as_unary()?.address_constant(env),
+            Assignment::Assign(..) => None,
+        }
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        match self {
+            Assignment::Equality(equality) => equality.dump(types),
+            Assignment::Assign(lhs, rhs) => Node::typed("assign", self.span(), types)
+                .child(lhs.dump(types))
+                .child(rhs.dump(types)),
+        }
+    }
+}
+
+/// <equality> ::= <relational>
+///              | <relational> <equality-dash>
+/// <equality-dash> ::= OpEq <relational> <equality-dash>
+///                   | OpNe <relational> <equality-dash>
+impl Equality {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Equality> {
+        let lhs = Relational::parse(tokens)?;
+        Equality::parse_equality_dash(Equality::Relational(Box::new(lhs)), tokens)
+    }
+
+    fn parse_equality_dash<'a>(lhs: Equality, tokens: &mut Tokens<'a>) -> ParseResult<Equality> {
+        match tokens.peek() {
+            Some(Token::OpEq) => {
+                tokens.eat(Token::OpEq)?;
+                let rhs = Relational::parse(tokens)?;
+                let equality = Equality::Eq(Box::new(lhs), Box::new(rhs));
+                Equality::parse_equality_dash(equality, tokens)
+            }
+            Some(Token::OpNe) => {
+                tokens.eat(Token::OpNe)?;
+                let rhs = Relational::parse(tokens)?;
+                let equality = Equality::Ne(Box::new(lhs), Box::new(rhs));
+                Equality::parse_equality_dash(equality, tokens)
+            }
+            _ => Ok(lhs),
+        }
+    }
+
+    fn into_unary(self) -> Option<Unary> {
+        match self {
+            Equality::Relational(relational) => relational.into_unary(),
+            _ => None,
+        }
+    }
+
+    fn as_unary(&self) -> Option<&Unary> {
+        match self {
+            Equality::Relational(relational) => relational.as_unary(),
+            _ => None,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Equality::Relational(relational) => relational.span(),
+            Equality::Eq(lhs, rhs) | Equality::Ne(lhs, rhs) => lhs.span().to(rhs.span()),
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Equality::Relational(relational) => relational.ty(env),
+            Equality::Eq(..) | Equality::Ne(..) => Ok(Type::INT),
+        }
+    }
+
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Equality::Relational(relational) => relational.check(env),
+            Equality::Eq(lhs, rhs) | Equality::Ne(lhs, rhs) => {
+                let (lhs_ty, rhs_ty) = (lhs.check(env)?, rhs.check(env)?);
+                let op = match self {
+                    Equality::Eq(..) => Token::OpEq,
+                    _ => Token::OpNe,
                 };
-                Assignment::Assign(Box::new(lhs), Box::new(rhs))
+                comparison_type(
+                    op,
+                    (&lhs_ty, lhs.constant()),
+                    (&rhs_ty, rhs.constant()),
+                    self.span(),
+                )?;
+                env.record_type(self.span(), &Type::INT);
+                Ok(Type::INT)
             }
-            _ => Assignment::Additive(Box::new(lhs))
         }
     }
 
-    pub fn gen_code(self, reg: usize) -> usize {
+    pub fn constant(&self) -> Option<Constant> {
         match self {
-            Assignment::Additive(additive) => additive.gen_code(reg),
-            Assignment::Assign(_unary, _assignment) => {
-                unimplemented!()
+            Equality::Relational(relational) => relational.constant(),
+            Equality::Eq(lhs, rhs) => {
+                Constant::binary(Token::OpEq, lhs.constant()?, rhs.constant()?)
+            }
+            Equality::Ne(lhs, rhs) => {
+                Constant::binary(Token::OpNe, lhs.constant()?, rhs.constant()?)
             }
         }
     }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        let span = self.span();
+        let (op, lhs, rhs) = match self {
+            Equality::Relational(relational) => return relational.gen_code(env),
+            Equality::Eq(lhs, rhs) => (Token::OpEq, lhs, rhs),
+            Equality::Ne(lhs, rhs) => (Token::OpNe, lhs, rhs),
+        };
+        let (lhs_ty, rhs_ty) = (checked(lhs.ty(env)), checked(rhs.ty(env)));
+        let ty = checked(comparison_type(
+            op,
+            (&lhs_ty, lhs.constant()),
+            (&rhs_ty, rhs.constant()),
+            span,
+        ));
+        let lhs = gen_conversion(lhs.gen_code(env), &lhs_ty, &ty, env);
+        let rhs = gen_conversion(rhs.gen_code(env), &rhs_ty, &ty, env);
+        gen_comparison(op, &ty, lhs, rhs, env)
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        let (kind, lhs, rhs) = match self {
+            Equality::Relational(relational) => return relational.dump(types),
+            Equality::Eq(lhs, rhs) => ("eq", lhs, rhs),
+            Equality::Ne(lhs, rhs) => ("ne", lhs, rhs),
+        };
+        Node::typed(kind, self.span(), types)
+            .child(lhs.dump(types))
+            .child(rhs.dump(types))
+    }
+}
+
+/// appends a comparison of `lhs` and `rhs` and the widening of its `i1` result to `int`
+fn gen_comparison(op: Token, ty: &Type, lhs: Value, rhs: Value, env: &mut Env) -> Value {
+    let mut block = env.block();
+    let result = compare_inst(op, ty, lhs, rhs, &mut block);
+    block.cast(CastOp::ZExt, result, ir::Type::I32)
+}
+
+/// <relational> ::= <additive>
+///                | <additive> <relational-dash>
+/// <relational-dash> ::= OpLt <additive> <relational-dash>
+///                     | OpGt <additive> <relational-dash>
+///                     | OpLe <additive> <relational-dash>
+///                     | OpGe <additive> <relational-dash>
+impl Relational {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Relational> {
+        let lhs = Additive::parse(tokens)?;
+        Relational::parse_relational_dash(Relational::Additive(Box::new(lhs)), tokens)
+    }
+
+    fn parse_relational_dash<'a>(
+        lhs: Relational,
+        tokens: &mut Tokens<'a>,
+    ) -> ParseResult<Relational> {
+        let constructor = match tokens.peek() {
+            Some(Token::OpLt) => Relational::Lt,
+            Some(Token::OpGt) => Relational::Gt,
+            Some(Token::OpLe) => Relational::Le,
+            Some(Token::OpGe) => Relational::Ge,
+            _ => return Ok(lhs),
+        };
+        tokens.next();
+        let rhs = Additive::parse(tokens)?;
+        let relational = constructor(Box::new(lhs), Box::new(rhs));
+        Relational::parse_relational_dash(relational, tokens)
+    }
+
+    fn into_unary(self) -> Option<Unary> {
+        match self {
+            Relational::Additive(additive) => additive.into_unary(),
+            _ => None,
+        }
+    }
+
+    fn as_unary(&self) -> Option<&Unary> {
+        match self {
+            Relational::Additive(additive) => additive.as_unary(),
+            _ => None,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Relational::Additive(additive) => additive.span(),
+            Relational::Lt(lhs, rhs)
+            | Relational::Gt(lhs, rhs)
+            | Relational::Le(lhs, rhs)
+            | Relational::Ge(lhs, rhs) => lhs.span().to(rhs.span()),
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Relational::Additive(additive) => additive.ty(env),
+            _ => Ok(Type::INT),
+        }
+    }
+
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Relational::Additive(additive) => additive.check(env),
+            Relational::Lt(lhs, rhs)
+            | Relational::Gt(lhs, rhs)
+            | Relational::Le(lhs, rhs)
+            | Relational::Ge(lhs, rhs) => {
+                let (lhs_ty, rhs_ty) = (lhs.check(env)?, rhs.check(env)?);
+                let op = match self {
+                    Relational::Lt(..) => Token::OpLt,
+                    Relational::Gt(..) => Token::OpGt,
+                    Relational::Le(..) => Token::OpLe,
+                    _ => Token::OpGe,
+                };
+                comparison_type(
+                    op,
+                    (&lhs_ty, lhs.constant()),
+                    (&rhs_ty, rhs.constant()),
+                    self.span(),
+                )?;
+                env.record_type(self.span(), &Type::INT);
+                Ok(Type::INT)
+            }
+        }
+    }
+
+    pub fn constant(&self) -> Option<Constant> {
+        let (op, lhs, rhs) = match self {
+            Relational::Additive(additive) => return additive.constant(),
+            Relational::Lt(lhs, rhs) => (Token::OpLt, lhs, rhs),
+            Relational::Gt(lhs, rhs) => (Token::OpGt, lhs, rhs),
+            Relational::Le(lhs, rhs) => (Token::OpLe, lhs, rhs),
+            Relational::Ge(lhs, rhs) => (Token::OpGe, lhs, rhs),
+        };
+        Constant::binary(op, lhs.constant()?, rhs.constant()?)
+    }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        let span = self.span();
+        let (op, lhs, rhs) = match self {
+            Relational::Additive(additive) => return additive.gen_code(env),
+            Relational::Lt(lhs, rhs) => (Token::OpLt, lhs, rhs),
+            Relational::Gt(lhs, rhs) => (Token::OpGt, lhs, rhs),
+            Relational::Le(lhs, rhs) => (Token::OpLe, lhs, rhs),
+            Relational::Ge(lhs, rhs) => (Token::OpGe, lhs, rhs),
+        };
+        let (lhs_ty, rhs_ty) = (checked(lhs.ty(env)), checked(rhs.ty(env)));
+        let ty = checked(comparison_type(
+            op,
+            (&lhs_ty, lhs.constant()),
+            (&rhs_ty, rhs.constant()),
+            span,
+        ));
+        let lhs = gen_conversion(lhs.gen_code(env), &lhs_ty, &ty, env);
+        let rhs = gen_conversion(rhs.gen_code(env), &rhs_ty, &ty, env);
+        gen_comparison(op, &ty, lhs, rhs, env)
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        let (kind, lhs, rhs) = match self {
+            Relational::Additive(additive) => return additive.dump(types),
+            Relational::Lt(lhs, rhs) => ("lt", lhs, rhs),
+            Relational::Gt(lhs, rhs) => ("gt", lhs, rhs),
+            Relational::Le(lhs, rhs) => ("le", lhs, rhs),
+            Relational::Ge(lhs, rhs) => ("ge", lhs, rhs),
+        };
+        Node::typed(kind, self.span(), types)
+            .child(lhs.dump(types))
+            .child(rhs.dump(types))
+    }
 }
 
 /// <additive> ::= <multiplicative>
@@ -89,168 +727,1173 @@ impl Assignment {
 /// <additive-dash> ::= OpAdd <multiplicative> <additive-dash>
 ///                   | OpSub <multiplicative> <additive-dash>
 impl Additive {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Additive {
-        let lhs = Multiplicative::parse(tokens);
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Additive> {
+        let lhs = Multiplicative::parse(tokens)?;
         Additive::parse_additive_dash(Additive::Multiplicative(Box::new(lhs)), tokens)
     }
 
-    fn parse_additive_dash<'a>(lhs: Additive, tokens: &mut Tokens<'a>) -> Additive {
+    fn parse_additive_dash<'a>(lhs: Additive, tokens: &mut Tokens<'a>) -> ParseResult<Additive> {
         match tokens.peek() {
             Some(Token::OpAdd) => {
-                tokens.eat(Token::OpAdd);
-                let rhs = Multiplicative::parse(tokens);
+                tokens.eat(Token::OpAdd)?;
+                let rhs = Multiplicative::parse(tokens)?;
                 let additive = Additive::Add(Box::new(lhs), Box::new(rhs));
                 Additive::parse_additive_dash(additive, tokens)
             }
             Some(Token::OpSub) => {
-                tokens.eat(Token::OpSub);
-                let rhs = Multiplicative::parse(tokens);
+                tokens.eat(Token::OpSub)?;
+                let rhs = Multiplicative::parse(tokens)?;
                 let additive = Additive::Sub(Box::new(lhs), Box::new(rhs));
                 Additive::parse_additive_dash(additive, tokens)
             }
-            _ => lhs,
+            _ => Ok(lhs),
         }
     }
 
-    pub fn gen_code(self, reg: usize) -> usize {
+    fn into_unary(self) -> Option<Unary> {
         match self {
-            Additive::Multiplicative(multiplicative) => multiplicative.gen_code(reg),
-            Additive::Add(additive, multiplicative) => {
-                let lhs = additive.gen_code(reg);
-                let rhs = multiplicative.gen_code(lhs + 1);
-                println!("  %{} = add i32 %{}, %{}", rhs + 1, lhs, rhs);
-                rhs + 1
+            Additive::Multiplicative(multiplicative) => multiplicative.into_unary(),
+            _ => None,
+        }
+    }
+
+    fn as_unary(&self) -> Option<&Unary> {
+        match self {
+            Additive::Multiplicative(multiplicative) => multiplicative.as_unary(),
+            _ => None,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Additive::Multiplicative(multiplicative) => multiplicative.span(),
+            Additive::Add(lhs, rhs) | Additive::Sub(lhs, rhs) => lhs.span().to(rhs.span()),
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Additive::Multiplicative(multiplicative) => multiplicative.ty(env),
+            Additive::Add(additive, multiplicative) | Additive::Sub(additive, multiplicative) => {
+                let op = match self {
+                    Additive::Add(..) => Token::OpAdd,
+                    _ => Token::OpSub,
+                };
+                let (lhs_ty, rhs_ty) = (additive.ty(env)?, multiplicative.ty(env)?);
+                match pointer_arith_type(op, &lhs_ty, &rhs_ty, env) {
+                    Some(ty) => Ok(ty),
+                    None => common_type(&lhs_ty, &rhs_ty, self.span()),
+                }
             }
-            Additive::Sub(additive, multiplicative) => {
-                let lhs = additive.gen_code(reg);
-                let rhs = multiplicative.gen_code(lhs + 1);
-                println!("  %{} = sub i32 %{}, %{}", rhs + 1, lhs, rhs);
-                rhs + 1
+        }
+    }
+
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Additive::Multiplicative(multiplicative) => multiplicative.check(env),
+            Additive::Add(lhs, rhs) | Additive::Sub(lhs, rhs) => {
+                lhs.check(env)?;
+                rhs.check(env)?;
+                let op = match self {
+                    Additive::Add(..) => Token::OpAdd,
+                    _ => Token::OpSub,
+                };
+                check_overflow(op, lhs.constant(), rhs.constant(), self.span(), env);
+                let ty = self.ty(env)?;
+                env.record_type(self.span(), &ty);
+                Ok(ty)
             }
         }
     }
+
+    pub fn constant(&self) -> Option<Constant> {
+        let (op, lhs, rhs) = match self {
+            Additive::Multiplicative(multiplicative) => return multiplicative.constant(),
+            Additive::Add(lhs, rhs) => (Token::OpAdd, lhs, rhs),
+            Additive::Sub(lhs, rhs) => (Token::OpSub, lhs, rhs),
+        };
+        Constant::binary(op, lhs.constant()?, rhs.constant()?)
+    }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        let ty = checked(self.ty(env));
+        let (op, lhs, rhs) = match self {
+            Additive::Multiplicative(multiplicative) => return multiplicative.gen_code(env),
+            Additive::Add(lhs, rhs) => (Token::OpAdd, lhs, rhs),
+            Additive::Sub(lhs, rhs) => (Token::OpSub, lhs, rhs),
+        };
+        let (lhs_ty, rhs_ty) = (checked(lhs.ty(env)), checked(rhs.ty(env)));
+        if let Type::Pointer(..) = ty {
+            let (lhs, rhs) = (lhs.gen_code(env), rhs.gen_code(env));
+            let (pointer, index, index_ty) = match lhs_ty {
+                Type::Pointer(..) => (lhs, rhs, rhs_ty),
+                _ => (rhs, lhs, lhs_ty),
+            };
+            let mut index = gen_conversion(index, &index_ty, &Type::Int(IntType::LONG), env);
+            if op == Token::OpSub {
+                index = env.block().sub(Value::Constant(ir::Type::I64, 0), index);
+            }
+            return env
+                .block()
+                .getelementptr(ty.ir_type(), pointer, vec![index]);
+        }
+        let lhs = gen_conversion(lhs.gen_code(env), &lhs_ty, &ty, env);
+        let rhs = gen_conversion(rhs.gen_code(env), &rhs_ty, &ty, env);
+        arith_inst(op, &ty)(&mut env.block(), lhs, rhs)
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        let (kind, lhs, rhs) = match self {
+            Additive::Multiplicative(multiplicative) => return multiplicative.dump(types),
+            Additive::Add(lhs, rhs) => ("add", lhs, rhs),
+            Additive::Sub(lhs, rhs) => ("sub", lhs, rhs),
+        };
+        Node::typed(kind, self.span(), types)
+            .child(lhs.dump(types))
+            .child(rhs.dump(types))
+    }
 }
 
 /// <multiplicative> ::= <unary>
 ///                    | <unary> <multiplicative-dash>
 /// <multiplicative-dash> ::= OpMul <unary> <multiplicative-dash>
 ///                         | OpDiv <unary> <multiplicative-dash>
+///                         | OpRem <unary> <multiplicative-dash>
 impl Multiplicative {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Multiplicative {
-        let lhs = Unary::parse(tokens);
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Multiplicative> {
+        let lhs = Unary::parse(tokens)?;
         Multiplicative::parse_multiplicative_dash(Multiplicative::Unary(Box::new(lhs)), tokens)
     }
 
     fn parse_multiplicative_dash<'a>(
         lhs: Multiplicative,
         tokens: &mut Tokens<'a>,
-    ) -> Multiplicative {
+    ) -> ParseResult<Multiplicative> {
         match tokens.peek() {
             Some(Token::OpMul) => {
-                tokens.eat(Token::OpMul);
-                let rhs = Unary::parse(tokens);
+                tokens.eat(Token::OpMul)?;
+                let rhs = Unary::parse(tokens)?;
                 let multiplicative = Multiplicative::Mul(Box::new(lhs), Box::new(rhs));
                 Multiplicative::parse_multiplicative_dash(multiplicative, tokens)
             }
             Some(Token::OpDiv) => {
-                tokens.eat(Token::OpDiv);
-                let rhs = Unary::parse(tokens);
+                tokens.eat(Token::OpDiv)?;
+                let rhs = Unary::parse(tokens)?;
                 let multiplicative = Multiplicative::Div(Box::new(lhs), Box::new(rhs));
                 Multiplicative::parse_multiplicative_dash(multiplicative, tokens)
             }
             Some(Token::OpRem) => {
-                tokens.eat(Token::OpRem);
-                let rhs = Unary::parse(tokens);
+                tokens.eat(Token::OpRem)?;
+                let rhs = Unary::parse(tokens)?;
                 let multiplicative = Multiplicative::Rem(Box::new(lhs), Box::new(rhs));
                 Multiplicative::parse_multiplicative_dash(multiplicative, tokens)
             }
-            _ => lhs,
+            _ => Ok(lhs),
         }
     }
 
-    pub fn gen_code(self, reg: usize) -> usize {
+    fn into_unary(self) -> Option<Unary> {
         match self {
-            Multiplicative::Unary(unary) => unary.gen_code(reg),
-            Multiplicative::Mul(multiplicative, unary) => {
-                let lhs = multiplicative.gen_code(reg);
-                let rhs = unary.gen_code(lhs + 1);
-                println!("  %{} = mul i32 %{}, %{}", rhs + 1, lhs, rhs);
-                rhs + 1
-            }
-            Multiplicative::Div(multiplicative, unary) => {
-                let lhs = multiplicative.gen_code(reg);
-                let rhs = unary.gen_code(lhs + 1);
-                println!("  %{} = sdiv i32 %{}, %{}", rhs + 1, lhs, rhs);
-                rhs + 1
+            Multiplicative::Unary(unary) => Some(*unary),
+            _ => None,
+        }
+    }
+
+    fn as_unary(&self) -> Option<&Unary> {
+        match self {
+            Multiplicative::Unary(unary) => Some(unary),
+            _ => None,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Multiplicative::Unary(unary) => unary.span(),
+            Multiplicative::Mul(lhs, rhs)
+            | Multiplicative::Div(lhs, rhs)
+            | Multiplicative::Rem(lhs, rhs) => lhs.span().to(rhs.span()),
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Multiplicative::Unary(unary) => unary.ty(env),
+            Multiplicative::Mul(multiplicative, unary)
+            | Multiplicative::Div(multiplicative, unary) => {
+                common_type(&multiplicative.ty(env)?, &unary.ty(env)?, self.span())
             }
             Multiplicative::Rem(multiplicative, unary) => {
-                let lhs = multiplicative.gen_code(reg);
-                let rhs = unary.gen_code(lhs + 1);
-                println!("  %{} = srem i32 %{}, %{}", rhs + 1, lhs, rhs);
-                rhs + 1
+                match common_type(&multiplicative.ty(env)?, &unary.ty(env)?, self.span())? {
+                    ty @ Type::Int(_) => Ok(ty),
+                    ty => Err(Diagnostic::new(
+                        self.span(),
+                        DiagnosticKind::InvalidRemainderOperands(ty),
+                    )),
+                }
             }
         }
     }
+
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Multiplicative::Unary(unary) => unary.check(env),
+            Multiplicative::Mul(lhs, rhs)
+            | Multiplicative::Div(lhs, rhs)
+            | Multiplicative::Rem(lhs, rhs) => {
+                lhs.check(env)?;
+                rhs.check(env)?;
+                let ty = self.ty(env)?;
+                let op = match self {
+                    Multiplicative::Mul(..) => Token::OpMul,
+                    Multiplicative::Div(..) => Token::OpDiv,
+                    _ => Token::OpRem,
+                };
+                let rhs_constant = rhs.constant();
+                if op != Token::OpMul && matches!(rhs_constant, Some(Constant::Int(0, _))) {
+                    if let Type::Int(_) = ty {
+                        let what = if op == Token::OpDiv {
+                            Term::Division
+                        } else {
+                            Term::Remainder
+                        };
+                        env.warn(rhs.span(), DiagnosticKind::DivisionByZero(what));
+                    }
+                }
+                check_overflow(op, lhs.constant(), rhs_constant, self.span(), env);
+                env.record_type(self.span(), &ty);
+                Ok(ty)
+            }
+        }
+    }
+
+    pub fn constant(&self) -> Option<Constant> {
+        let (op, lhs, rhs) = match self {
+            Multiplicative::Unary(unary) => return unary.constant(),
+            Multiplicative::Mul(lhs, rhs) => (Token::OpMul, lhs, rhs),
+            Multiplicative::Div(lhs, rhs) => (Token::OpDiv, lhs, rhs),
+            Multiplicative::Rem(lhs, rhs) => (Token::OpRem, lhs, rhs),
+        };
+        Constant::binary(op, lhs.constant()?, rhs.constant()?)
+    }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        let ty = checked(self.ty(env));
+        let (op, lhs, rhs) = match self {
+            Multiplicative::Unary(unary) => return unary.gen_code(env),
+            Multiplicative::Mul(lhs, rhs) => (Token::OpMul, lhs, rhs),
+            Multiplicative::Div(lhs, rhs) => (Token::OpDiv, lhs, rhs),
+            Multiplicative::Rem(lhs, rhs) => (Token::OpRem, lhs, rhs),
+        };
+        let (lhs_ty, rhs_ty) = (checked(lhs.ty(env)), checked(rhs.ty(env)));
+        let lhs = gen_conversion(lhs.gen_code(env), &lhs_ty, &ty, env);
+        let rhs = gen_conversion(rhs.gen_code(env), &rhs_ty, &ty, env);
+        arith_inst(op, &ty)(&mut env.block(), lhs, rhs)
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        let (kind, lhs, rhs) = match self {
+            Multiplicative::Unary(unary) => return unary.dump(types),
+            Multiplicative::Mul(lhs, rhs) => ("mul", lhs, rhs),
+            Multiplicative::Div(lhs, rhs) => ("div", lhs, rhs),
+            Multiplicative::Rem(lhs, rhs) => ("rem", lhs, rhs),
+        };
+        Node::typed(kind, self.span(), types)
+            .child(lhs.dump(types))
+            .child(rhs.dump(types))
+    }
 }
 
 impl Unary {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Unary {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Unary> {
+        let start = tokens.span();
         match tokens.peek() {
             Some(Token::OpAdd) => {
-                tokens.eat(Token::OpAdd);
-                let unary = Unary::parse(tokens);
-                Unary::UnaryPlus(Box::new(unary))
+                tokens.eat(Token::OpAdd)?;
+                let unary = Unary::parse(tokens)?;
+                Ok(Unary::Plus(Box::new(unary), start.to(tokens.prev_span())))
             }
             Some(Token::OpSub) => {
-                tokens.eat(Token::OpSub);
-                let unary = Unary::parse(tokens);
-                Unary::UnaryMinus(Box::new(unary))
+                tokens.eat(Token::OpSub)?;
+                let unary = Unary::parse(tokens)?;
+                Ok(Unary::Minus(Box::new(unary), start.to(tokens.prev_span())))
+            }
+            Some(Token::OpMul) => {
+                tokens.eat(Token::OpMul)?;
+                let unary = Unary::parse(tokens)?;
+                Ok(Unary::Deref(Box::new(unary), start.to(tokens.prev_span())))
+            }
+            Some(Token::OpAnd) => {
+                tokens.eat(Token::OpAnd)?;
+                let unary = Unary::parse(tokens)?;
+                Ok(Unary::AddressOf(
+                    Box::new(unary),
+                    start.to(tokens.prev_span()),
+                ))
+            }
+            Some(Token::SyLPar) if Unary::starts_cast(*tokens) => {
+                tokens.eat(Token::SyLPar)?;
+                let type_name = TypeName::parse(tokens)?;
+                tokens.eat_err(
+                    Token::SyRPar,
+                    DiagnosticKind::Unclosed(Term::Parens, Term::Cast),
+                )?;
+                let unary = Unary::parse(tokens)?;
+                let span = start.to(tokens.prev_span());
+                Ok(Unary::Cast(Box::new(type_name), Box::new(unary), span))
             }
             _ => {
-                let primary = Primary::parse(tokens);
-                Unary::Primary(Box::new(primary))
+                let postfix = Postfix::parse(tokens)?;
+                Ok(Unary::Postfix(Box::new(postfix)))
+            }
+        }
+    }
+
+    /// whether `(` at the head of `tokens` starts a cast rather than a parenthesized expression
+    fn starts_cast(mut tokens: Tokens) -> bool {
+        tokens.next();
+        tokens.peek().is_some_and(decl::is_declaration_specifier)
+    }
+
+    /// the name of the variable if this expression is just an identifier
+    fn as_ident(&self) -> Option<&str> {
+        match self {
+            Unary::Postfix(postfix) => postfix.callee_name(),
+            _ => None,
+        }
+    }
+
+    fn string_literal(&self) -> Option<&[u8]> {
+        match self {
+            Unary::Postfix(postfix) => postfix.string_literal(),
+            _ => None,
+        }
+    }
+
+    fn address_constant(&self, env: &mut Env) -> Option<Value> {
+        match self {
+            Unary::Postfix(postfix) => postfix.address_constant(env),
+            Unary::AddressOf(unary, _) => Some(unary.static_variable(env)?.address()),
+            _ => None,
+        }
+    }
+
+    /// the variable with static storage duration if this expression is just its name
+    fn static_variable(&self, env: &Env) -> Option<Variable> {
+        match self {
+            Unary::Postfix(postfix) => postfix.static_variable(env),
+            _ => None,
+        }
+    }
+
+    fn function_address(&self, env: &Env) -> Option<&str> {
+        match self {
+            Unary::Postfix(postfix) => postfix.function_address(env),
+            Unary::AddressOf(unary, _) => unary.function_address(env),
+            _ => None,
+        }
+    }
+
+    /// the type and the qualifiers of the object designated by this expression, or None if
+    /// this is not an lvalue
+    pub fn lvalue(&self, env: &mut Env) -> CheckResult<Option<(Type, Qualifiers)>> {
+        match self {
+            Unary::Postfix(postfix) => postfix.lvalue(env),
+            Unary::Deref(unary, span) => match unary.ty(env)? {
+                Type::Pointer(pointee, _) if *pointee == Type::Void => {
+                    Err(Diagnostic::new(*span, DiagnosticKind::VoidIndirection))
+                }
+                // a function designator is not an lvalue
+                ref ty if ty.is_function_pointer() => Ok(None),
+                Type::Pointer(pointee, qualifiers) => Ok(Some((*pointee, qualifiers))),
+                ty => Err(Diagnostic::new(
+                    *span,
+                    DiagnosticKind::NonPointerIndirection(ty),
+                )),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// whether this expression designates a function
+    fn is_function_designator(&self, env: &mut Env) -> CheckResult<bool> {
+        match self {
+            Unary::Postfix(postfix) => Ok(postfix.function(env).is_some()),
+            Unary::Deref(unary, _) => Ok(unary.ty(env)?.is_function_pointer()),
+            _ => Ok(false),
+        }
+    }
+
+    /// appends the computation of the address of the object designated by this expression.
+    /// returns the object.
+    pub fn gen_address(self, env: &mut Env) -> Variable {
+        if let Unary::Postfix(postfix) = self {
+            return postfix.gen_address(env);
+        }
+        let lvalue = checked(self.lvalue(env));
+        match (self, lvalue) {
+            (Unary::Deref(unary, _), Some((ty, qualifiers))) => Variable {
+                place: Place::Register(unary.gen_code(env)),
+                ty,
+                qualifiers,
+            },
+            _ => panic!("ICE: the address of a non-lvalue"),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match *self {
+            Unary::Postfix(ref postfix) => postfix.span(),
+            Unary::Plus(_, span)
+            | Unary::Minus(_, span)
+            | Unary::Deref(_, span)
+            | Unary::AddressOf(_, span)
+            | Unary::Cast(_, _, span) => span,
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Unary::Postfix(postfix) => postfix.ty(env),
+            Unary::Plus(unary, span) | Unary::Minus(unary, span) => {
+                let ty = unary.ty(env)?;
+                if !ty.is_arithmetic() {
+                    return Err(Diagnostic::new(
+                        *span,
+                        DiagnosticKind::InvalidUnaryOperand(ty),
+                    ));
+                }
+                Ok(ty.promote())
+            }
+            Unary::Deref(unary, _) => match self.lvalue(env)? {
+                Some((ty, qualifiers)) => Ok(ty.decay(qualifiers)),
+                // a function designator is converted back to the pointer
+                None => unary.ty(env),
+            },
+            Unary::AddressOf(unary, _) => {
+                if unary.is_function_designator(env)? {
+                    return unary.ty(env);
+                }
+                match unary.lvalue(env)? {
+                    Some((ty, qualifiers)) => Ok(Type::Pointer(Box::new(ty), qualifiers)),
+                    None => Err(Diagnostic::new(
+                        unary.span(),
+                        DiagnosticKind::AddressOfRvalue,
+                    )),
+                }
+            }
+            Unary::Cast(type_name, _, span) => {
+                let ty = type_name.ty(env)?;
+                match ty {
+                    Type::Void | Type::Int(_) | Type::Float(_) | Type::Pointer(..) => Ok(ty),
+                    _ => Err(Diagnostic::new(*span, DiagnosticKind::NonScalarCast(ty))),
+                }
+            }
+        }
+    }
+
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Unary::Postfix(postfix) => return postfix.check(env),
+            Unary::Minus(unary, span) => {
+                unary.check(env)?;
+                // negation overflows as subtraction from zero does
+                let operand = unary
+                    .constant()
+                    .and_then(|constant| constant.convert(&constant.ty().promote()));
+                if let Some(Constant::Int(_, ty)) = operand {
+                    check_overflow(
+                        Token::OpSub,
+                        Some(Constant::Int(0, ty)),
+                        operand,
+                        *span,
+                        env,
+                    );
+                }
+            }
+            Unary::Plus(unary, _) | Unary::Deref(unary, _) | Unary::AddressOf(unary, _) => {
+                unary.check(env)?;
+            }
+            Unary::Cast(_, unary, span) => {
+                let from = unary.check(env)?;
+                let to = self.ty(env)?;
+                let valid = match (&to, &from) {
+                    (Type::Void, _) => true,
+                    (Type::Pointer(..), Type::Float(_)) | (Type::Float(_), Type::Pointer(..)) => {
+                        false
+                    }
+                    (to, from) => to.is_scalar() && from.is_scalar(),
+                };
+                if !valid {
+                    return Err(Diagnostic::new(
+                        *span,
+                        DiagnosticKind::InvalidCast(from, to),
+                    ));
+                }
+            }
+        }
+        let ty = self.ty(env)?;
+        env.record_type(self.span(), &ty);
+        Ok(ty)
+    }
+
+    pub fn constant(&self) -> Option<Constant> {
+        match self {
+            Unary::Postfix(postfix) => postfix.constant(),
+            Unary::Cast(type_name, unary, _) => match type_name.basic_type() {
+                Some(ty) if ty.is_arithmetic() => unary.constant()?.convert(ty),
+                _ => None,
+            },
+            Unary::Deref(_, _) | Unary::AddressOf(_, _) => None,
+            Unary::Plus(unary, _) => {
+                let constant = unary.constant()?;
+                constant.convert(&constant.ty().promote())
+            }
+            Unary::Minus(unary, _) => {
+                let constant = unary.constant()?;
+                Some(constant.convert(&constant.ty().promote())?.negate())
+            }
+        }
+    }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        let ty = checked(self.ty(env));
+        match self {
+            Unary::Postfix(postfix) => postfix.gen_code(env),
+            Unary::Plus(unary, _) => {
+                let unary_ty = checked(unary.ty(env));
+                gen_conversion(unary.gen_code(env), &unary_ty, &ty, env)
+            }
+            Unary::Minus(unary, _) => {
+                let unary_ty = checked(unary.ty(env));
+                let value = gen_conversion(unary.gen_code(env), &unary_ty, &ty, env);
+                match ty {
+                    Type::Float(_) => env.block().fneg(value),
+                    _ => env.block().sub(Value::Constant(ty.ir_type(), 0), value),
+                }
+            }
+            Unary::Deref(unary, span) => {
+                // the function designator is converted back to the pointer
+                if checked(unary.ty(env)).is_function_pointer() {
+                    return unary.gen_code(env);
+                }
+                let variable = Unary::Deref(unary, span).gen_address(env);
+                variable.gen_rvalue(&mut env.block())
+            }
+            Unary::AddressOf(unary, _) => {
+                if checked(unary.is_function_designator(env)) {
+                    return unary.gen_code(env);
+                }
+                let variable = unary.gen_address(env);
+                let ty = variable.ty.ir_type().pointer();
+                // copies the address into a new register
+                env.block().cast(CastOp::BitCast, variable.address(), ty)
+            }
+            Unary::Cast(_, unary, _) => {
+                let unary_ty = checked(unary.ty(env));
+                let value = unary.gen_code(env);
+                match ty {
+                    // the value is discarded
+                    Type::Void => value,
+                    _ => gen_conversion(value, &unary_ty, &ty, env),
+                }
+            }
+        }
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        let (kind, operand) = match self {
+            Unary::Postfix(postfix) => return postfix.dump(types),
+            Unary::Plus(operand, _) => ("plus", operand),
+            Unary::Minus(operand, _) => ("minus", operand),
+            Unary::Deref(operand, _) => ("deref", operand),
+            Unary::AddressOf(operand, _) => ("address-of", operand),
+            Unary::Cast(type_name, operand, _) => {
+                return Node::typed("cast", self.span(), types)
+                    .child(type_name.dump(types))
+                    .child(operand.dump(types))
+            }
+        };
+        Node::typed(kind, self.span(), types).child(operand.dump(types))
+    }
+}
+
+/// <postfix> ::= <primary>
+///             | <postfix> SyLPar (<assignment> (SyComma <assignment>)*)? SyRPar
+///             | <postfix> SyLBracket <expr> SyRBracket
+///             | <postfix> SyDot Ident
+impl Postfix {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Postfix> {
+        let start = tokens.span();
+        let mut postfix = Postfix::Primary(Box::new(Primary::parse(tokens)?));
+        loop {
+            match tokens.peek() {
+                Some(Token::SyLPar) => {
+                    tokens.eat(Token::SyLPar)?;
+                    let mut args = Vec::new();
+                    if tokens.peek() != Some(Token::SyRPar) {
+                        loop {
+                            args.push(Assignment::parse(tokens)?);
+                            if tokens.peek() != Some(Token::SyComma) {
+                                break;
+                            }
+                            tokens.eat(Token::SyComma)?;
+                        }
+                    }
+                    tokens.eat_err(
+                        Token::SyRPar,
+                        DiagnosticKind::Unclosed(Term::Parens, Term::FunctionCall),
+                    )?;
+                    let span = start.to(tokens.prev_span());
+                    postfix = Postfix::Call(Box::new(postfix), args, span);
+                }
+                Some(Token::SyLBracket) => {
+                    tokens.eat(Token::SyLBracket)?;
+                    let index = Expr::parse(tokens)?;
+                    tokens.eat_err(
+                        Token::SyRBracket,
+                        DiagnosticKind::Unclosed(Term::Brackets, Term::ArraySubscript),
+                    )?;
+                    let span = start.to(tokens.prev_span());
+                    postfix = Postfix::Index(Box::new(postfix), Box::new(index), span);
+                }
+                Some(Token::SyDot) => {
+                    tokens.eat(Token::SyDot)?;
+                    let name = match tokens.peek() {
+                        Some(Token::Ident(name)) => name.to_owned(),
+                        _ => return Err(tokens.expected(Term::MemberName)),
+                    };
+                    tokens.next();
+                    let span = start.to(tokens.prev_span());
+                    postfix = Postfix::Member(Box::new(postfix), name, span);
+                }
+                _ => return Ok(postfix),
+            }
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match *self {
+            Postfix::Primary(ref primary) => primary.span(),
+            Postfix::Call(_, _, span)
+            | Postfix::Index(_, _, span)
+            | Postfix::Member(_, _, span) => span,
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Postfix::Primary(primary) => primary.ty(env),
+            Postfix::Call(callee, _, _) => match callee.direct_callee(env) {
+                Some(name) => match env.function(name) {
+                    Some(function) => Ok(function.ty.ret.clone()),
+                    // undeclared functions are implicitly declared to return `int`
+                    None => Ok(Type::INT),
+                },
+                None => Ok(callee.pointee_function(env)?.ret),
+            },
+            Postfix::Index(..) | Postfix::Member(..) => {
+                let (ty, qualifiers) = self.lvalue(env)?.expect("ICE: subscript is not an lvalue");
+                Ok(ty.decay(qualifiers))
             }
         }
     }
 
-    pub fn gen_code(self, reg: usize) -> usize {
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
         match self {
-            Unary::Primary(primary) => primary.gen_code(reg),
-            Unary::UnaryPlus(unary) => unary.gen_code(reg),
-            Unary::UnaryMinus(unary) => {
-                let reg = unary.gen_code(reg);
-                println!("  %{} = sub i32 0, %{}", reg + 1, reg);
-                reg + 1
+            Postfix::Primary(primary) => return primary.check(env),
+            Postfix::Call(callee, args, span) => {
+                // an undeclared function is declared implicitly by the call
+                let (function, target) = match callee.direct_callee(env) {
+                    Some(name) => {
+                        let name = name.to_owned();
+                        (env.function_or_implicit(&name, callee.span())?, name)
+                    }
+                    None => {
+                        callee.check(env)?;
+                        (callee.pointee_function(env)?, "function pointer".to_owned())
+                    }
+                };
+
+                if let Some(ref params) = function.params {
+                    if args.len() < params.len() {
+                        return Err(Diagnostic::new(
+                            *span,
+                            DiagnosticKind::TooFewArguments(target),
+                        ));
+                    }
+                    if args.len() > params.len() && !function.variadic {
+                        return Err(Diagnostic::new(
+                            *span,
+                            DiagnosticKind::TooManyArguments(target),
+                        ));
+                    }
+                }
+
+                for (idx, arg) in args.iter().enumerate() {
+                    match function.params {
+                        Some(ref params) if idx < params.len() => {
+                            let conversion = Conversion::Argument(idx + 1, target.clone());
+                            check_assignable(&params[idx], arg, env, conversion)?;
+                        }
+                        _ => {
+                            if arg.check(env)? == Type::Void {
+                                return Err(Diagnostic::new(
+                                    arg.span(),
+                                    DiagnosticKind::VoidArgument,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+            Postfix::Index(array, index, _) => {
+                array.check(env)?;
+                index.check(env)?;
+            }
+            Postfix::Member(object, _, _) => {
+                object.check(env)?;
+            }
+        }
+        let ty = self.ty(env)?;
+        env.record_type(self.span(), &ty);
+        Ok(ty)
+    }
+
+    pub fn constant(&self) -> Option<Constant> {
+        match self {
+            Postfix::Primary(primary) => primary.constant(),
+            Postfix::Call(..) | Postfix::Index(..) | Postfix::Member(..) => None,
+        }
+    }
+
+    /// the name of the called function if this expression is just an identifier
+    fn callee_name(&self) -> Option<&str> {
+        match self {
+            Postfix::Primary(primary) => match **primary {
+                Primary::Ident(ref name, _) => Some(name),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// the name of the called function if this expression calls it directly rather than
+    /// through a pointer
+    fn direct_callee(&self, env: &Env) -> Option<&str> {
+        self.callee_name()
+            .filter(|name| env.try_lookup(name).is_none())
+    }
+
+    /// the type of the function this expression points to
+    fn pointee_function(&self, env: &mut Env) -> CheckResult<FunctionType> {
+        match self.ty(env)? {
+            Type::Pointer(pointee, _) => match *pointee {
+                Type::Function(function) => Ok(*function),
+                pointee => Err(Diagnostic::new(
+                    self.span(),
+                    DiagnosticKind::NotFunction(pointee),
+                )),
+            },
+            ty => Err(Diagnostic::new(
+                self.span(),
+                DiagnosticKind::NotCallable(ty),
+            )),
+        }
+    }
+
+    fn function_address(&self, env: &Env) -> Option<&str> {
+        match self {
+            Postfix::Primary(primary) => match **primary {
+                Primary::Paren(ref expr, _) => match **expr {
+                    Expr::Assignment(ref assignment) => assignment.function_address(env),
+                },
+                _ => self
+                    .direct_callee(env)
+                    .filter(|name| env.function(name).is_some()),
+            },
+            _ => None,
+        }
+    }
+
+    fn address_constant(&self, env: &mut Env) -> Option<Value> {
+        if let Some(bytes) = self.string_literal() {
+            let string = env.add_string(bytes.to_vec());
+            return Some(ir::first_element(&string));
+        }
+        match self {
+            Postfix::Primary(primary) => match **primary {
+                Primary::Paren(ref expr, _) => expr.as_unary()?.address_constant(env),
+                _ => {
+                    let variable = self.static_variable(env)?;
+                    match variable.ty {
+                        Type::Array(..) => Some(ir::first_element(&variable.address())),
+                        _ => None,
+                    }
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn static_variable(&self, env: &Env) -> Option<Variable> {
+        match self {
+            Postfix::Primary(primary) => match **primary {
+                Primary::Ident(ref name, _) => match env.try_lookup(name)? {
+                    variable @ Variable {
+                        place: Place::Global(_),
+                        ..
+                    } => Some(variable.clone()),
+                    _ => None,
+                },
+                Primary::Paren(ref expr, _) => expr.as_unary()?.static_variable(env),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// the type of the function if this expression is the name of a function
+    fn function(&self, env: &Env) -> Option<FunctionType> {
+        let name = self.direct_callee(env)?;
+        env.function(name).map(|function| function.ty.clone())
+    }
+
+    fn string_literal(&self) -> Option<&[u8]> {
+        match self {
+            Postfix::Primary(primary) => match **primary {
+                Primary::StringLiteral(ref bytes, _) => Some(bytes),
+                Primary::Paren(ref expr, _) => match **expr {
+                    Expr::Assignment(ref assignment) => assignment.string_literal(),
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// the type and the qualifiers of the object designated by this expression, or None if
+    /// this is not an lvalue
+    fn lvalue(&self, env: &mut Env) -> CheckResult<Option<(Type, Qualifiers)>> {
+        match self {
+            Postfix::Primary(primary) => match **primary {
+                // a function designator is not an lvalue
+                Primary::Ident(_, _) if self.function(env).is_some() => Ok(None),
+                Primary::Ident(ref name, span) => {
+                    let variable = lookup(env, name, span)?;
+                    Ok(Some((variable.ty.clone(), variable.qualifiers)))
+                }
+                // a parenthesized lvalue is still an lvalue
+                Primary::Paren(ref expr, _) => match expr.as_unary() {
+                    Some(unary) => unary.lvalue(env),
+                    None => Ok(None),
+                },
+                _ => Ok(None),
+            },
+            Postfix::Call(..) => Ok(None),
+            Postfix::Index(array, index, _) => {
+                match index.ty(env)? {
+                    Type::Int(_) => {}
+                    _ => {
+                        return Err(Diagnostic::new(
+                            index.span(),
+                            DiagnosticKind::NonIntegerSubscript,
+                        ))
+                    }
+                }
+                match array.ty(env)? {
+                    Type::Pointer(element, qualifiers) => Ok(Some((*element, qualifiers))),
+                    _ => Err(Diagnostic::new(
+                        array.span(),
+                        DiagnosticKind::NotSubscriptable,
+                    )),
+                }
+            }
+            Postfix::Member(object, name, span) => match object.lvalue(env)? {
+                Some((Type::Struct(ref tag), qualifiers)) => {
+                    let member = env
+                        .struct_members(tag)
+                        .iter()
+                        .find(|member| member.name == *name)
+                        .ok_or_else(|| {
+                            Diagnostic::new(
+                                *span,
+                                DiagnosticKind::NoMember(name.clone(), tag.clone()),
+                            )
+                        })?;
+                    Ok(Some((
+                        member.ty.clone(),
+                        qualifiers.merge(member.qualifiers),
+                    )))
+                }
+                Some(_) => Err(Diagnostic::new(object.span(), DiagnosticKind::NotStruct)),
+                None => Err(Diagnostic::new(
+                    object.span(),
+                    DiagnosticKind::MemberOfRvalue,
+                )),
+            },
+        }
+    }
+
+    fn gen_address(self, env: &mut Env) -> Variable {
+        let (ty, qualifiers) = match checked(self.lvalue(env)) {
+            Some(lvalue) => lvalue,
+            None => panic!("ICE: the address of a non-lvalue"),
+        };
+        match self {
+            Postfix::Primary(primary) => match *primary {
+                Primary::Ident(name, span) => checked(lookup(env, &name, span)).clone(),
+                Primary::Paren(expr, _) => expr
+                    .into_unary()
+                    .expect("ICE: the address of a non-lvalue")
+                    .gen_address(env),
+                _ => unreachable!(),
+            },
+            Postfix::Call(..) => unreachable!(),
+            Postfix::Index(array, index, _) => {
+                let index_ty = checked(index.ty(env));
+                let pointer = array.gen_code(env);
+                let index = gen_conversion(
+                    index.gen_code(env),
+                    &index_ty,
+                    &Type::Int(IntType::LONG),
+                    env,
+                );
+                let element =
+                    env.block()
+                        .getelementptr(ty.ir_type().pointer(), pointer, vec![index]);
+                Variable {
+                    place: Place::Register(element),
+                    ty,
+                    qualifiers,
+                }
+            }
+            Postfix::Member(object, name, _) => {
+                let object = object.gen_address(env);
+                let tag = match object.ty {
+                    Type::Struct(ref tag) => tag.clone(),
+                    _ => unreachable!(),
+                };
+                let idx = env
+                    .struct_members(&tag)
+                    .iter()
+                    .position(|member| member.name == name)
+                    .expect("ICE: unknown member");
+                let indices = vec![
+                    Value::Constant(ir::Type::I32, 0),
+                    Value::Constant(ir::Type::I32, idx as i64),
+                ];
+                let member =
+                    env.block()
+                        .getelementptr(ty.ir_type().pointer(), object.address(), indices);
+                Variable {
+                    place: Place::Register(member),
+                    ty,
+                    qualifiers,
+                }
+            }
+        }
+    }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        match self {
+            Postfix::Primary(primary) => primary.gen_code(env),
+            Postfix::Index(..) | Postfix::Member(..) => {
+                let variable = self.gen_address(env);
+                variable.gen_rvalue(&mut env.block())
+            }
+            Postfix::Call(callee, args, _) => {
+                // a function is called by its name if it is visible, and through the pointer
+                // otherwise
+                let (function, target) = match callee.direct_callee(env) {
+                    Some(name) => {
+                        let name = name.to_owned();
+                        let function = checked(env.function_or_implicit(&name, callee.span()));
+                        // the function is called with the type declared here, which a later
+                        // declaration may change
+                        let pointer = function.ir_type().pointer();
+                        let mut address = env.function_address(&name);
+                        if address.ty() != pointer {
+                            address = env.block().cast(CastOp::BitCast, address, pointer);
+                        }
+                        (function, address)
+                    }
+                    None => {
+                        let function = checked(callee.pointee_function(env));
+                        (function, callee.gen_code(env))
+                    }
+                };
+
+                // arguments are converted to the types of the parameters.  the others (to
+                // functions without prototypes or as variadic arguments) get default argument
+                // promotions
+                let mut operands = Vec::new();
+                for (idx, arg) in args.into_iter().enumerate() {
+                    let arg_ty = checked(arg.ty(env));
+                    let param_ty = match function.params {
+                        Some(ref params) if idx < params.len() => params[idx].clone(),
+                        _ => arg_ty.promote_argument(),
+                    };
+                    let arg = gen_conversion(arg.gen_code(env), &arg_ty, &param_ty, env);
+                    operands.push(arg);
+                }
+
+                if function.ret == Type::Void {
+                    env.block().call_void(target, operands);
+                    // the value of a void expression is never used
+                    return Value::Literal(ir::Type::Void, "undef".to_owned());
+                }
+                env.block().call(target, operands)
+            }
+        }
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        let node = |kind| Node::typed(kind, self.span(), types);
+        match self {
+            Postfix::Primary(primary) => primary.dump(types),
+            Postfix::Call(callee, args, _) => node("call")
+                .child(callee.dump(types))
+                .children(args.iter().map(|arg| arg.dump(types))),
+            Postfix::Index(array, index, _) => node("index")
+                .child(array.dump(types))
+                .child(index.dump(types)),
+            Postfix::Member(object, name, _) => {
+                node("member").value(name.clone()).child(object.dump(types))
             }
         }
     }
 }
 
 impl Primary {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Primary {
-        match tokens.next() {
-            Some(Token::Literal(n)) => {
-                let constant = n.parse().expect("internal error: could not parse literal.");
-                Primary::Constant(constant)
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Primary> {
+        let span = tokens.span();
+        let token = match tokens.peek() {
+            Some(token) => token,
+            None => return Err(tokens.expected(Term::Expression)),
+        };
+        let primary = match token {
+            Token::Literal(n) => Primary::Constant(IntLiteral::parse(n, span)?, span),
+            Token::FloatLiteral(n) => Primary::FloatConstant(FloatLiteral::parse(n, span)?, span),
+            Token::StringLiteral(s) => {
+                Primary::StringLiteral(literal::parse_string(s, span)?, span)
+            }
+            Token::Ident(name) => Primary::Ident(name.to_owned(), span),
+            Token::SyLPar => {
+                tokens.eat(Token::SyLPar)?;
+                let expr = Expr::parse(tokens)?;
+                tokens.eat_or_insert(
+                    Token::SyRPar,
+                    DiagnosticKind::Unclosed(Term::Parens, Term::PrimaryExpression),
+                )?;
+                return Ok(Primary::Paren(Box::new(expr), span.to(tokens.prev_span())));
+            }
+            _ => return Err(tokens.expected(Term::Expression)),
+        };
+        tokens.next();
+        Ok(primary)
+    }
+
+    pub fn span(&self) -> Span {
+        match *self {
+            Primary::Constant(_, span)
+            | Primary::FloatConstant(_, span)
+            | Primary::StringLiteral(_, span)
+            | Primary::Ident(_, span)
+            | Primary::Paren(_, span) => span,
+        }
+    }
+
+    pub fn ty(&self, env: &mut Env) -> CheckResult<Type> {
+        match self {
+            Primary::Constant(literal, _) => Ok(Type::Int(literal.ty)),
+            Primary::FloatConstant(literal, _) => Ok(Type::Float(literal.ty)),
+            Primary::StringLiteral(_, _) => Ok(Type::Pointer(
+                Box::new(Type::Int(IntType::CHAR)),
+                Qualifiers::default(),
+            )),
+            Primary::Ident(name, span) => {
+                if let Some(variable) = env.try_lookup(name) {
+                    return Ok(variable.ty.decay(variable.qualifiers));
+                }
+                // a function designator is converted to the pointer to the function
+                match env.function(name) {
+                    Some(function) => Ok(Type::Pointer(
+                        Box::new(Type::Function(Box::new(function.ty.clone()))),
+                        Qualifiers::default(),
+                    )),
+                    None => Ok(lookup(env, name, *span)?.ty.clone()),
+                }
+            }
+            Primary::Paren(expr, _) => expr.ty(env),
+        }
+    }
+
+    pub fn check(&self, env: &mut Env) -> CheckResult<Type> {
+        let ty = match self {
+            Primary::Paren(expr, _) => expr.check(env)?,
+            Primary::Ident(name, _) => {
+                env.mark_used(name);
+                self.ty(env)?
             }
-            Some(Token::SyLPar) => {
-                let expr = Expr::parse(tokens);
-                tokens.eat_err(Token::SyRPar, "no matching parens for primary expression.");
-                Primary::Paren(Box::new(expr))
+            _ => self.ty(env)?,
+        };
+        env.record_type(self.span(), &ty);
+        Ok(ty)
+    }
+
+    pub fn constant(&self) -> Option<Constant> {
+        match self {
+            Primary::Constant(literal, _) => Some(Constant::Int(literal.value, literal.ty)),
+            Primary::FloatConstant(literal, _) => Some(Constant::Float(literal.value, literal.ty)),
+            Primary::Paren(expr, _) => expr.constant(),
+            Primary::StringLiteral(_, _) | Primary::Ident(_, _) => None,
+        }
+    }
+
+    pub fn gen_code(self, env: &mut Env) -> Value {
+        match self {
+            Primary::Constant(literal, _) => {
+                let ty = literal.ty;
+                let value = Value::Literal(ty.ir_type(), ty.llvm_constant(literal.value));
+                env.block().add(value, Value::Constant(ty.ir_type(), 0))
             }
-            other => {
-                panic!("expected primary expression, found {:?}", other);
+            Primary::FloatConstant(literal, _) => {
+                let ty = literal.ty;
+                let value = Value::Literal(ty.ir_type(), ty.llvm_constant(literal.value));
+                let zero = Value::Literal(ty.ir_type(), "0.0".to_owned());
+                env.block().fadd(value, zero)
             }
+            Primary::StringLiteral(bytes, _) => {
+                let string = env.add_string(bytes);
+                let zero = || Value::Constant(ir::Type::I64, 0);
+                let ty = ir::Type::I8.pointer();
+                env.block().getelementptr(ty, string, vec![zero(), zero()])
+            }
+            Primary::Ident(name, span) => {
+                if env.try_lookup(&name).is_none() {
+                    if let Some(function) = env.function(&name) {
+                        let ty = function.ty.ir_type().pointer();
+                        let function = env.function_address(&name);
+                        return env.block().cast(CastOp::BitCast, function, ty);
+                    }
+                }
+                let variable = checked(lookup(env, &name, span)).clone();
+                variable.gen_rvalue(&mut env.block())
+            }
+            Primary::Paren(expr, _) => expr.gen_code(env),
         }
     }
 
-    pub fn gen_code(self, reg: usize) -> usize {
+    pub fn dump(&self, types: &Types) -> Node {
+        let node = |kind| Node::typed(kind, self.span(), types);
         match self {
-            Primary::Constant(n) => {
-                println!("  %{} = add i32 {}, 0", reg, n);
-                reg
+            Primary::Constant(literal, _) => node("int").value(literal.value.to_string()),
+            Primary::FloatConstant(literal, _) => node("float").value(literal.value.to_string()),
+            Primary::StringLiteral(bytes, _) => {
+                node("string").value(format!("{:?}", String::from_utf8_lossy(bytes)))
             }
-            Primary::Paren(expr) => expr.gen_code(reg),
+            Primary::Ident(name, _) => node("ident").value(name.clone()),
+            Primary::Paren(expr, _) => expr.dump(types),
         }
     }
 }
@@ -285,11 +1928,59 @@ mod tests {
                 Token::SyRPar,
                 Token::SyRPar,
             ]),
+            Tokens::new(&[
+                Token::Ident("x"),
+                Token::OpAssign,
+                Token::FloatLiteral("1.5"),
+                Token::OpLt,
+                Token::Literal("2"),
+                Token::OpEq,
+                Token::Literal("1"),
+            ]),
+            Tokens::new(&[
+                Token::Ident("printf"),
+                Token::SyLPar,
+                Token::StringLiteral(r#""%f\n""#),
+                Token::SyComma,
+                Token::Ident("x"),
+                Token::SyRPar,
+            ]),
+            Tokens::new(&[
+                Token::SyLPar,
+                Token::TyLong,
+                Token::SyRPar,
+                Token::OpMul,
+                Token::OpAnd,
+                Token::Ident("x"),
+            ]),
         ];
 
         for mut tokens in tests {
-            println!("{:?}", Expr::parse(&mut tokens));
+            Expr::parse(&mut tokens).unwrap();
             assert!(tokens.is_empty());
         }
     }
+
+    #[test]
+    fn floating_types() {
+        let mut env = Env::new();
+        let tokens = &mut Tokens::new(&[
+            Token::Literal("1"),
+            Token::OpAdd,
+            Token::FloatLiteral("2.0f"),
+        ]);
+        assert_eq!(
+            Expr::parse(tokens).unwrap().ty(&mut env).unwrap(),
+            Type::Float(::types::FloatType::Float)
+        );
+        let tokens = &mut Tokens::new(&[
+            Token::FloatLiteral("1.0"),
+            Token::OpLt,
+            Token::FloatLiteral("2.0f"),
+        ]);
+        assert_eq!(
+            Expr::parse(tokens).unwrap().ty(&mut env).unwrap(),
+            Type::INT
+        );
+    }
 }
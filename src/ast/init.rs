@@ -0,0 +1,664 @@
+use std::collections::VecDeque;
+use std::iter;
+
+use super::checked;
+use super::constant::Constant;
+use super::dump::Node;
+use super::env::{Env, Place, Types, Variable};
+use super::expr::{self, Assignment};
+use diagnostic::{CheckResult, Diagnostic, ParseResult};
+use ir::{self, Value};
+use message::{Conversion, DiagnosticKind, Term};
+use source::Span;
+use token::{Token, Tokens};
+use types::{IntRank, Type};
+
+/// an initializer as written
+#[derive(Debug, Clone)]
+pub enum Initializer {
+    Expr(Box<Assignment>),
+    List(Vec<(Vec<Designator>, Initializer)>, Span),
+}
+
+#[derive(Debug, Clone)]
+pub enum Designator {
+    Index(Box<Assignment>),
+    Member(String, Span),
+}
+
+/// an initializer matched with the object it initializes.  subobjects without initializers are
+/// zero-initialized.
+#[derive(Debug)]
+pub enum Init {
+    Zero,
+    Expr(Box<Assignment>),
+
+    /// a string literal initializing an array of characters
+    String(Vec<u8>),
+
+    /// the elements of an array or the members of a structure.  trailing ones may be omitted.
+    Aggregate(Vec<Init>),
+}
+
+/// the value of an initializer of an object with static storage duration
+#[derive(Debug, Clone, PartialEq)]
+pub enum StaticInit {
+    Zero,
+    Scalar(Constant),
+
+    /// the address of a global variable, a function or a string literal: an `ir::Value::Global`,
+    /// or the address of the first element of an array as an `ir::Value::Literal`
+    Address(Value),
+
+    String(Vec<u8>),
+    Aggregate(Vec<StaticInit>),
+}
+
+type Items = VecDeque<(Vec<Designator>, Initializer)>;
+
+/// <initializer> ::= <assignment>
+///                 | SyLBrace (<designation>? <initializer> (SyComma <designation>? <initializer>)*
+///                   SyComma?)? SyRBrace
+/// <designation> ::= (SyLBracket <assignment> SyRBracket | SyDot Ident)+ OpAssign
+impl Initializer {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Initializer> {
+        if tokens.peek() != Some(Token::SyLBrace) {
+            return Ok(Initializer::Expr(Box::new(Assignment::parse(tokens)?)));
+        }
+
+        let start = tokens.span();
+        tokens.eat(Token::SyLBrace)?;
+        let mut items = Vec::new();
+        while tokens.peek() != Some(Token::SyRBrace) {
+            let designators = Designator::parse_designation(tokens)?;
+            items.push((designators, Initializer::parse(tokens)?));
+            if tokens.peek() != Some(Token::SyComma) {
+                break;
+            }
+            tokens.eat(Token::SyComma)?;
+        }
+        tokens.eat_err(
+            Token::SyRBrace,
+            DiagnosticKind::Unclosed(Term::Braces, Term::InitializerList),
+        )?;
+        Ok(Initializer::List(items, start.to(tokens.prev_span())))
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Initializer::Expr(expr) => expr.span(),
+            Initializer::List(_, span) => *span,
+        }
+    }
+
+    /// whether this initializer initializes an object of type `ty` itself, rather than its first
+    /// subobject with the braces omitted
+    fn is_direct(&self, ty: &Type, env: &mut Env) -> CheckResult<bool> {
+        match self {
+            Initializer::List(..) => Ok(true),
+            Initializer::Expr(_) if !ty.is_aggregate() => Ok(true),
+            Initializer::Expr(expr) => {
+                Ok((is_char_array(ty) && expr.string_literal().is_some()) || expr.ty(env)? == *ty)
+            }
+        }
+    }
+
+    /// the node for the dump of the syntax tree
+    pub fn dump(&self, types: &Types) -> Node {
+        let (items, span) = match self {
+            Initializer::Expr(expr) => return expr.dump(types),
+            Initializer::List(items, span) => (items, *span),
+        };
+        let items = items.iter().map(|(designators, initializer)| {
+            if designators.is_empty() {
+                return initializer.dump(types);
+            }
+            Node::new(
+                "designation",
+                Some(designators[0].span().to(initializer.span())),
+            )
+            .children(designators.iter().map(|designator| designator.dump(types)))
+            .child(initializer.dump(types))
+        });
+        Node::new("initializer-list", Some(span)).children(items)
+    }
+}
+
+impl Designator {
+    /// reads designators followed by `=`, if any
+    fn parse_designation<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Vec<Designator>> {
+        let mut designators = Vec::new();
+        loop {
+            match tokens.peek() {
+                Some(Token::SyLBracket) => {
+                    tokens.eat(Token::SyLBracket)?;
+                    let index = Assignment::parse(tokens)?;
+                    tokens.eat_err(
+                        Token::SyRBracket,
+                        DiagnosticKind::Unclosed(Term::Brackets, Term::Designator),
+                    )?;
+                    designators.push(Designator::Index(Box::new(index)));
+                }
+                Some(Token::SyDot) => {
+                    let start = tokens.span();
+                    tokens.eat(Token::SyDot)?;
+                    match tokens.peek() {
+                        Some(Token::Ident(name)) => {
+                            tokens.next();
+                            let span = start.to(tokens.prev_span());
+                            designators.push(Designator::Member(name.to_owned(), span))
+                        }
+                        _ => return Err(tokens.expected(Term::FieldDesignator)),
+                    }
+                }
+                _ => break,
+            }
+        }
+        if !designators.is_empty() {
+            tokens.eat_err(
+                Token::OpAssign,
+                DiagnosticKind::MissingEqualsAfterDesignator,
+            )?;
+        }
+        Ok(designators)
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Designator::Index(index) => index.span(),
+            Designator::Member(_, span) => *span,
+        }
+    }
+
+    /// the index of the element or the member of an object of type `ty` this designates
+    fn index(&self, ty: &Type, env: &Env) -> CheckResult<usize> {
+        let span = self.span();
+        let kind = match (self, ty) {
+            (Designator::Index(index), Type::Array(_, len)) => {
+                let index = match index.constant() {
+                    Some(constant @ Constant::Int(..)) if constant.is_negative() => {
+                        return Err(Diagnostic::new(span, DiagnosticKind::NegativeDesignator))
+                    }
+                    Some(Constant::Int(value, _)) => value as usize,
+                    _ => return Err(Diagnostic::new(span, DiagnosticKind::NonConstantDesignator)),
+                };
+                if len.is_some_and(|len| index >= len) {
+                    DiagnosticKind::DesignatorOutOfBounds(index)
+                } else {
+                    return Ok(index);
+                }
+            }
+            (Designator::Member(name, _), Type::Struct(struct_name)) => {
+                let position = env
+                    .struct_members(struct_name)
+                    .iter()
+                    .position(|member| member.name == *name);
+                match position {
+                    Some(idx) => return Ok(idx),
+                    None => DiagnosticKind::UnknownField(name.clone()),
+                }
+            }
+            (Designator::Index(_), _) => DiagnosticKind::ArrayDesignatorMismatch(ty.clone()),
+            (Designator::Member(name, _), _) => {
+                DiagnosticKind::FieldDesignatorMismatch(name.clone(), ty.clone())
+            }
+        };
+        Err(Diagnostic::new(span, kind))
+    }
+
+    fn dump(&self, types: &Types) -> Node {
+        match self {
+            Designator::Index(index) => {
+                Node::new("index-designator", Some(self.span())).child(index.dump(types))
+            }
+            Designator::Member(name, span) => {
+                Node::new("member-designator", Some(*span)).value(name.clone())
+            }
+        }
+    }
+}
+
+fn is_char_array(ty: &Type) -> bool {
+    match ty {
+        Type::Array(element, _) => matches!(**element, Type::Int(ty) if ty.rank == IntRank::Char),
+        _ => false,
+    }
+}
+
+/// the number of elements or members of an aggregate, or None for an array of unknown size
+fn aggregate_len(ty: &Type, env: &Env) -> Option<usize> {
+    match ty {
+        Type::Array(_, len) => *len,
+        Type::Struct(name) => Some(env.struct_members(name).len()),
+        _ => panic!("ICE: {:?} is not an aggregate type", ty),
+    }
+}
+
+/// the type of the element or member `idx` of an aggregate
+fn child_type(ty: &Type, idx: usize, env: &Env) -> Type {
+    match ty {
+        Type::Array(element, _) => (**element).clone(),
+        Type::Struct(name) => env.struct_members(name)[idx].ty.clone(),
+        _ => panic!("ICE: {:?} is not an aggregate type", ty),
+    }
+}
+
+impl Init {
+    /// matches `initializer` with an object of type `ty`.  returns the initializer and the type
+    /// of the object, which is completed if it is an array of unknown size.
+    pub fn new(initializer: Initializer, ty: &Type, env: &mut Env) -> CheckResult<(Init, Type)> {
+        let span = initializer.span();
+        let mut init = Init::Zero;
+        let len = init.assign(initializer, ty, env)?;
+        let ty = match ty {
+            Type::Array(_, Some(_)) => ty.clone(),
+            Type::Array(_, None) if len == 0 => {
+                return Err(Diagnostic::new(span, DiagnosticKind::ZeroSizeArray))
+            }
+            Type::Array(element, None) => Type::Array(element.clone(), Some(len)),
+            _ => ty.clone(),
+        };
+        Ok((init, ty))
+    }
+
+    /// initializes this object of type `ty` with `initializer`.  returns the number of elements
+    /// initialized if it is an array.
+    fn assign(&mut self, initializer: Initializer, ty: &Type, env: &mut Env) -> CheckResult<usize> {
+        match initializer {
+            // braces around a scalar initializer
+            Initializer::List(items, span) if !ty.is_aggregate() => {
+                let mut items = items.into_iter();
+                match items.next() {
+                    Some((ref designators, _)) if !designators.is_empty() => {
+                        return Err(Diagnostic::new(
+                            designators[0].span(),
+                            DiagnosticKind::DesignatorForScalar(ty.clone()),
+                        ))
+                    }
+                    Some((_, initializer)) => self.assign(initializer, ty, env)?,
+                    None => {
+                        return Err(Diagnostic::new(
+                            span,
+                            DiagnosticKind::EmptyScalarInitializer,
+                        ))
+                    }
+                };
+                if let Some((_, initializer)) = items.next() {
+                    return Err(Diagnostic::new(
+                        initializer.span(),
+                        DiagnosticKind::ExcessScalarElements,
+                    ));
+                }
+                Ok(0)
+            }
+            Initializer::List(items, _) => {
+                let mut items: Items = items.into_iter().collect();
+                let len = self.fill(ty, &mut items, true, false, env)?;
+                if let Some((_, initializer)) = items.front() {
+                    return Err(Diagnostic::new(
+                        initializer.span(),
+                        DiagnosticKind::ExcessElements(ty.clone()),
+                    ));
+                }
+                Ok(len)
+            }
+            Initializer::Expr(expr) => match expr.string_literal() {
+                Some(bytes) if is_char_array(ty) => {
+                    if let Type::Array(_, Some(len)) = *ty {
+                        // the terminating null character is dropped if it does not fit
+                        if bytes.len() > len {
+                            return Err(Diagnostic::new(
+                                expr.span(),
+                                DiagnosticKind::StringTooLong,
+                            ));
+                        }
+                    }
+                    *self = Init::String(bytes.to_vec());
+                    Ok(bytes.len() + 1)
+                }
+                _ if ty.is_aggregate() && expr.ty(env)? != *ty => Err(Diagnostic::new(
+                    expr.span(),
+                    DiagnosticKind::BracesRequired(ty.clone()),
+                )),
+                _ => {
+                    *self = Init::Expr(expr);
+                    Ok(0)
+                }
+            },
+        }
+    }
+
+    /// the element or member `idx` of this aggregate
+    fn child(&mut self, idx: usize) -> &mut Init {
+        if let Init::Aggregate(_) = *self {
+        } else {
+            *self = Init::Aggregate(Vec::new());
+        }
+        match self {
+            Init::Aggregate(children) => {
+                while children.len() <= idx {
+                    children.push(Init::Zero);
+                }
+                &mut children[idx]
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// initializes the elements or members of this aggregate with `items`, as many as they
+    /// initialize.  if `braced` is false, the braces around this aggregate are omitted and it
+    /// ends before the next designator, except the first one if `designated` is true.  returns
+    /// the number of elements initialized.
+    fn fill(
+        &mut self,
+        ty: &Type,
+        items: &mut Items,
+        braced: bool,
+        designated: bool,
+        env: &mut Env,
+    ) -> CheckResult<usize> {
+        let len = aggregate_len(ty, env);
+        let mut idx = 0;
+        let mut max_len = 0;
+        let mut first = true;
+        while let Some((designators, _)) = items.front() {
+            let (designators, initializer) = if designators.is_empty() {
+                if len.is_some_and(|len| idx >= len) {
+                    break;
+                }
+                items.pop_front().expect("ICE: no initializer")
+            } else {
+                if !(braced || first && designated) {
+                    break;
+                }
+                let (mut designators, initializer) =
+                    items.pop_front().expect("ICE: no initializer");
+                idx = designators.remove(0).index(ty, env)?;
+                (designators, initializer)
+            };
+            first = false;
+
+            let child_ty = child_type(ty, idx, env);
+            let child = self.child(idx);
+            if designators.is_empty() && initializer.is_direct(&child_ty, env)? {
+                child.assign(initializer, &child_ty, env)?;
+            } else {
+                if !child_ty.is_aggregate() {
+                    return Err(Diagnostic::new(
+                        designators[0].span(),
+                        DiagnosticKind::DesignatorForScalar(child_ty.clone()),
+                    ));
+                }
+                // the braces around the child are omitted, and the rest of the items may
+                // initialize it
+                let has_designators = !designators.is_empty();
+                items.push_front((designators, initializer));
+                child.fill(&child_ty, items, false, has_designators, env)?;
+            }
+
+            idx += 1;
+            max_len = max_len.max(idx);
+        }
+        Ok(max_len)
+    }
+
+    /// checks that the expressions in this initializer of an object of type `ty` can be
+    /// converted to the types of the subobjects they initialize
+    pub fn check(&self, ty: &Type, env: &mut Env) -> CheckResult<()> {
+        match self {
+            Init::Zero | Init::String(_) => Ok(()),
+            Init::Expr(expr) => expr::check_assignable(ty, expr, env, Conversion::Initialization),
+            Init::Aggregate(children) => {
+                for (idx, child) in children.iter().enumerate() {
+                    child.check(&child_type(ty, idx, env), env)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// appends the initialization of `variable`, which has just been allocated
+    pub fn gen_code(self, variable: &Variable, env: &mut Env) {
+        if variable.ty.is_aggregate() {
+            // zero-fill first, then store the initialized subobjects
+            env.zero_fill(
+                variable.address(),
+                &variable.ty,
+                variable.qualifiers.is_volatile,
+            );
+        }
+        self.gen_stores(variable, &variable.ty, &mut Vec::new(), env)
+    }
+
+    /// appends the stores to the subobject at `path` (indices from `variable`) of type `ty`
+    fn gen_stores(self, variable: &Variable, ty: &Type, path: &mut Vec<usize>, env: &mut Env) {
+        // the address of the subobject
+        let subobject = |env: &mut Env, path: &[usize]| {
+            if path.is_empty() {
+                return variable.clone();
+            }
+            let indices = iter::once(0)
+                .chain(path.iter().cloned())
+                .map(|idx| Value::Constant(ir::Type::I32, idx as i64))
+                .collect();
+            let address =
+                env.block()
+                    .getelementptr(ty.ir_type().pointer(), variable.address(), indices);
+            Variable {
+                place: Place::Register(address),
+                ty: ty.clone(),
+                qualifiers: variable.qualifiers,
+            }
+        };
+
+        match self {
+            Init::Zero => {}
+            Init::Expr(expr) => {
+                let subobject = subobject(env, path);
+                let expr_ty = checked(expr.ty(env));
+                let value = expr::gen_conversion(expr.gen_code(env), &expr_ty, ty, env);
+                subobject.gen_store(value, &mut env.block());
+            }
+            Init::String(bytes) => {
+                let subobject = subobject(env, path);
+                let value = ir::llvm_string(&string_bytes(&bytes, &subobject.ty));
+                env.block().store(
+                    Value::Literal(ty.ir_type(), value),
+                    subobject.address(),
+                    subobject.qualifiers.is_volatile,
+                );
+            }
+            Init::Aggregate(children) => {
+                for (idx, child) in children.into_iter().enumerate() {
+                    let child_ty = child_type(ty, idx, env);
+                    path.push(idx);
+                    child.gen_stores(variable, &child_ty, path, env);
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    /// evaluates this initializer of an object of type `ty` with static storage duration
+    pub fn into_static(self, ty: &Type, env: &mut Env) -> CheckResult<StaticInit> {
+        match self {
+            Init::Zero => Ok(StaticInit::Zero),
+            Init::Expr(expr) => {
+                let address = match expr.function_address(env) {
+                    Some(name) if ty.is_function_pointer() => Some(env.function_address(name)),
+                    _ if matches!(ty, Type::Pointer(..)) => expr.address_constant(env),
+                    _ => None,
+                };
+                if let Some(address) = address {
+                    return Ok(StaticInit::Address(address));
+                }
+                match expr.constant().and_then(|constant| constant.convert(ty)) {
+                    Some(constant) => Ok(StaticInit::Scalar(constant)),
+                    None => Err(Diagnostic::new(
+                        expr.span(),
+                        DiagnosticKind::NonConstantInitializer,
+                    )),
+                }
+            }
+            Init::String(bytes) => Ok(StaticInit::String(bytes)),
+            Init::Aggregate(children) => children
+                .into_iter()
+                .enumerate()
+                .map(|(idx, child)| child.into_static(&child_type(ty, idx, env), env))
+                .collect::<CheckResult<_>>()
+                .map(StaticInit::Aggregate),
+        }
+    }
+}
+
+/// the contents of an array of characters of type `ty` initialized with a string literal
+fn string_bytes(bytes: &[u8], ty: &Type) -> Vec<u8> {
+    let len = match *ty {
+        Type::Array(_, Some(len)) => len,
+        _ => panic!("ICE: {:?} is not a complete array type", ty),
+    };
+    let mut bytes = bytes.to_vec();
+    bytes.resize(len, 0);
+    bytes
+}
+
+impl StaticInit {
+    /// formats this initializer of an object of type `ty` as an LLVM constant
+    pub fn llvm_constant(&self, ty: &Type, env: &Env) -> String {
+        match self {
+            StaticInit::Zero => ty.llvm_zero(),
+            StaticInit::Scalar(constant) => constant.llvm_constant(),
+            StaticInit::Address(address) => {
+                let constant = match address {
+                    Value::Global(_, name) => format!("@{}", name),
+                    Value::Literal(_, constant) => constant.clone(),
+                    _ => panic!("ICE: {:?} is not an address constant", address),
+                };
+                let (from, to) = (address.ty(), ty.ir_type());
+                if from == to {
+                    constant
+                } else {
+                    format!("bitcast ({} {} to {})", from, constant, to)
+                }
+            }
+            StaticInit::String(bytes) => ir::llvm_string(&string_bytes(bytes, ty)),
+            StaticInit::Aggregate(children) => {
+                let len = aggregate_len(ty, env).expect("ICE: incomplete array type");
+                let elements: Vec<String> = (0..len)
+                    .map(|idx| {
+                        let child_ty = child_type(ty, idx, env);
+                        let child = children.get(idx).unwrap_or(&StaticInit::Zero);
+                        format!(
+                            "{} {}",
+                            child_ty.llvm_type(),
+                            child.llvm_constant(&child_ty, env)
+                        )
+                    })
+                    .collect();
+                match ty {
+                    Type::Array(..) => format!("[{}]", elements.join(", ")),
+                    _ => format!("{{ {} }}", elements.join(", ")),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{IntType, Member, Qualifiers};
+
+    fn parse(tokens: &[Token]) -> Initializer {
+        let tokens = &mut Tokens::new(tokens);
+        let initializer = Initializer::parse(tokens).unwrap();
+        assert!(tokens.is_empty());
+        initializer
+    }
+
+    fn point(env: &mut Env) -> Type {
+        let name = env.define_struct(Some("point"), Span::default()).unwrap();
+        let member = |name: &str| Member {
+            name: name.to_owned(),
+            ty: Type::INT,
+            qualifiers: Qualifiers::default(),
+        };
+        env.complete_struct(&name, vec![member("x"), member("y")], Span::default())
+            .unwrap();
+        Type::Struct(name)
+    }
+
+    #[test]
+    fn nested_braces_and_elision() {
+        let mut env = Env::new();
+        let ty = Type::Array(Box::new(point(&mut env)), None);
+        // { { 1, 2 }, 3, 4, [3].y = 5 }
+        let initializer = parse(&[
+            Token::SyLBrace,
+            Token::SyLBrace,
+            Token::Literal("1"),
+            Token::SyComma,
+            Token::Literal("2"),
+            Token::SyRBrace,
+            Token::SyComma,
+            Token::Literal("3"),
+            Token::SyComma,
+            Token::Literal("4"),
+            Token::SyComma,
+            Token::SyLBracket,
+            Token::Literal("3"),
+            Token::SyRBracket,
+            Token::SyDot,
+            Token::Ident("y"),
+            Token::OpAssign,
+            Token::Literal("5"),
+            Token::SyRBrace,
+        ]);
+        let (init, ty) = Init::new(initializer, &ty, &mut env).unwrap();
+        assert_eq!(ty.llvm_type(), "[4 x %struct.point]");
+
+        let int = |value| StaticInit::Scalar(Constant::Int(value, IntType::INT));
+        assert_eq!(
+            init.into_static(&ty, &mut env).unwrap(),
+            StaticInit::Aggregate(vec![
+                StaticInit::Aggregate(vec![int(1), int(2)]),
+                StaticInit::Aggregate(vec![int(3), int(4)]),
+                StaticInit::Zero,
+                StaticInit::Aggregate(vec![StaticInit::Zero, int(5)]),
+            ])
+        );
+    }
+
+    #[test]
+    fn string_literal() {
+        let mut env = Env::new();
+        let ty = Type::Array(Box::new(Type::Int(IntType::CHAR)), None);
+        let initializer = parse(&[Token::StringLiteral(r#""hi""#)]);
+        let (init, ty) = Init::new(initializer, &ty, &mut env).unwrap();
+        assert_eq!(ty.llvm_type(), "[3 x i8]");
+        assert_eq!(
+            init.into_static(&ty, &mut env)
+                .unwrap()
+                .llvm_constant(&ty, &env),
+            r#"c"hi\00""#
+        );
+    }
+
+    #[test]
+    fn excess_elements() {
+        let mut env = Env::new();
+        let ty = Type::Array(Box::new(Type::INT), Some(1));
+        let initializer = parse(&[
+            Token::SyLBrace,
+            Token::Literal("1"),
+            Token::SyComma,
+            Token::Literal("2"),
+            Token::SyRBrace,
+        ]);
+        let diagnostic = Init::new(initializer, &ty, &mut env).unwrap_err();
+        assert_eq!(
+            diagnostic.message(),
+            "excess elements in initializer of int [1]"
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use diagnostic::{ParseError, ParseResult};
+use message::DiagnosticKind;
+use source::Span;
+use types::{FloatType, IntType};
+
+/// an integer constant with the type given by C11 6.4.4.1
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct IntLiteral {
+    pub value: u64,
+    pub ty: IntType,
+}
+
+impl IntLiteral {
+    /// parses an integer constant token.  the token must already be matched against the
+    /// integer constant syntax by the lexer.  errors are reported at `span`, the span of the
+    /// token.
+    pub fn parse(literal: &str, span: Span) -> ParseResult<IntLiteral> {
+        let suffix_start = literal
+            .rfind(|ch: char| !"uUlL".contains(ch))
+            .map(|pos| pos + 1)
+            .unwrap_or(literal.len());
+        let (digits, suffix) = literal.split_at(suffix_start);
+        let suffix = suffix.to_lowercase();
+
+        let (digits, radix) = if digits.starts_with("0x") || digits.starts_with("0X") {
+            (&digits[2..], 16)
+        } else if digits.starts_with("0b") || digits.starts_with("0B") {
+            (&digits[2..], 2)
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            (&digits[1..], 8)
+        } else {
+            (digits, 10)
+        };
+
+        let value = u64::from_str_radix(digits, radix).map_err(|_| {
+            ParseError::new(span, DiagnosticKind::IntegerTooLarge(literal.to_owned()))
+        })?;
+
+        let unsigned = suffix.contains('u');
+        let long = suffix.matches('l').count();
+
+        let candidates: &[IntType] = match (radix == 10, unsigned, long) {
+            (true, false, 0) => &[IntType::INT, IntType::LONG, IntType::LLONG],
+            (true, false, 1) => &[IntType::LONG, IntType::LLONG],
+            (true, false, _) => &[IntType::LLONG],
+            (false, false, 0) => &[
+                IntType::INT,
+                IntType::UINT,
+                IntType::LONG,
+                IntType::ULONG,
+                IntType::LLONG,
+                IntType::ULLONG,
+            ],
+            (false, false, 1) => &[
+                IntType::LONG,
+                IntType::ULONG,
+                IntType::LLONG,
+                IntType::ULLONG,
+            ],
+            (false, false, _) => &[IntType::LLONG, IntType::ULLONG],
+            (_, true, 0) => &[IntType::UINT, IntType::ULONG, IntType::ULLONG],
+            (_, true, 1) => &[IntType::ULONG, IntType::ULLONG],
+            (_, true, _) => &[IntType::ULLONG],
+        };
+
+        let ty = candidates
+            .iter()
+            .cloned()
+            .find(|ty| value <= ty.max_value())
+            .ok_or_else(|| {
+                ParseError::new(
+                    span,
+                    DiagnosticKind::IntegerTooLargeForType(literal.to_owned()),
+                )
+            })?;
+
+        Ok(IntLiteral { value, ty })
+    }
+}
+
+/// a floating constant (C11 6.4.4.2)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatLiteral {
+    pub value: f64,
+    pub ty: FloatType,
+}
+
+impl FloatLiteral {
+    /// parses a floating constant token, either decimal (`1.5e-3`) or hexadecimal (`0x1.8p3`).
+    pub fn parse(literal: &str, span: Span) -> ParseResult<FloatLiteral> {
+        let (body, ty) = match literal.chars().last() {
+            Some('f') | Some('F') => (&literal[..literal.len() - 1], FloatType::Float),
+            Some('l') | Some('L') => {
+                return Err(ParseError::new(
+                    span,
+                    DiagnosticKind::LongDoubleLiteral(literal.to_owned()),
+                ))
+            }
+            _ => (literal, FloatType::Double),
+        };
+
+        let value = if body.starts_with("0x") || body.starts_with("0X") {
+            FloatLiteral::parse_hex(&body[2..])
+        } else {
+            match ty {
+                FloatType::Float => body.parse::<f32>().map(f64::from),
+                FloatType::Double => body.parse::<f64>(),
+            }
+            .expect("ICE: floating constant accepted by the lexer could not be parsed")
+        };
+
+        Ok(FloatLiteral { value, ty })
+    }
+
+    /// parses `hex-digits[.hex-digits]p[+-]digits` (without the `0x` prefix)
+    fn parse_hex(body: &str) -> f64 {
+        let exponent_start = body
+            .find(['p', 'P'])
+            .expect("ICE: hexadecimal floating constant without exponent");
+        let (mantissa, exponent) = body.split_at(exponent_start);
+        let mut exponent: i32 = exponent[1..]
+            .parse()
+            .expect("ICE: invalid exponent of hexadecimal floating constant");
+
+        let mut value = 0.0;
+        let mut after_point = false;
+        for ch in mantissa.chars() {
+            match ch.to_digit(16) {
+                Some(digit) => {
+                    value = value * 16.0 + f64::from(digit);
+                    if after_point {
+                        exponent -= 4;
+                    }
+                }
+                None => after_point = true,
+            }
+        }
+
+        value * 2f64.powi(exponent)
+    }
+}
+
+/// decodes the escape sequences in a string literal token (with its quotes) into the bytes of
+/// the array it denotes, not including the terminating null character.
+pub fn parse_string(literal: &str, span: Span) -> ParseResult<Vec<u8>> {
+    let body = &literal[1..literal.len() - 1];
+    let mut bytes = Vec::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+
+        let escaped = chars
+            .next()
+            .expect("ICE: string literal ends with a backslash");
+        let byte = match escaped {
+            'n' => b'\n',
+            't' => b'\t',
+            'r' => b'\r',
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0c,
+            'v' => 0x0b,
+            '\\' | '\'' | '"' | '?' => escaped as u8,
+            'x' => {
+                let mut value: u32 = 0;
+                while let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(16)) {
+                    value = value.wrapping_mul(16) + digit;
+                    chars.next();
+                }
+                value as u8
+            }
+            '0'..='7' => {
+                let mut value = escaped.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|ch| ch.to_digit(8)) {
+                        Some(digit) => value = value * 8 + digit,
+                        None => break,
+                    }
+                    chars.next();
+                }
+                value as u8
+            }
+            other => return Err(ParseError::new(span, DiagnosticKind::UnknownEscape(other))),
+        };
+        bytes.push(byte);
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_radix() {
+        assert_eq!(IntLiteral::parse("42", Span::default()).unwrap().value, 42);
+        assert_eq!(IntLiteral::parse("0", Span::default()).unwrap().value, 0);
+        assert_eq!(IntLiteral::parse("052", Span::default()).unwrap().value, 42);
+        assert_eq!(
+            IntLiteral::parse("0x2a", Span::default()).unwrap().value,
+            42
+        );
+        assert_eq!(
+            IntLiteral::parse("0X2A", Span::default()).unwrap().value,
+            42
+        );
+        assert_eq!(
+            IntLiteral::parse("0b101010", Span::default())
+                .unwrap()
+                .value,
+            42
+        );
+        assert_eq!(
+            IntLiteral::parse("0xffULL", Span::default()).unwrap().value,
+            255
+        );
+    }
+
+    #[test]
+    fn literal_types() {
+        assert_eq!(
+            IntLiteral::parse("2147483647", Span::default()).unwrap().ty,
+            IntType::INT
+        );
+        assert_eq!(
+            IntLiteral::parse("3000000000", Span::default()).unwrap().ty,
+            IntType::LONG
+        );
+        assert_eq!(
+            IntLiteral::parse("0xb2d05e00", Span::default()).unwrap().ty,
+            IntType::UINT
+        );
+        assert_eq!(
+            IntLiteral::parse("3000000000u", Span::default())
+                .unwrap()
+                .ty,
+            IntType::UINT
+        );
+        assert_eq!(
+            IntLiteral::parse("1l", Span::default()).unwrap().ty,
+            IntType::LONG
+        );
+        assert_eq!(
+            IntLiteral::parse("1lu", Span::default()).unwrap().ty,
+            IntType::ULONG
+        );
+        assert_eq!(
+            IntLiteral::parse("1LL", Span::default()).unwrap().ty,
+            IntType::LLONG
+        );
+        assert_eq!(
+            IntLiteral::parse("0xffffffffffffffff", Span::default())
+                .unwrap()
+                .ty,
+            IntType::ULONG
+        );
+        assert_eq!(
+            IntLiteral::parse("0xffffffffffffffffll", Span::default())
+                .unwrap()
+                .ty,
+            IntType::ULLONG
+        );
+    }
+
+    #[test]
+    fn parse_float() {
+        assert_eq!(
+            FloatLiteral::parse("1.5", Span::default()).unwrap().value,
+            1.5
+        );
+        assert_eq!(
+            FloatLiteral::parse("1.", Span::default()).unwrap().value,
+            1.0
+        );
+        assert_eq!(
+            FloatLiteral::parse(".25", Span::default()).unwrap().value,
+            0.25
+        );
+        assert_eq!(
+            FloatLiteral::parse("1e3", Span::default()).unwrap().value,
+            1000.0
+        );
+        assert_eq!(
+            FloatLiteral::parse("2.5E-1", Span::default())
+                .unwrap()
+                .value,
+            0.25
+        );
+        assert_eq!(
+            FloatLiteral::parse("0x1.8p3", Span::default())
+                .unwrap()
+                .value,
+            12.0
+        );
+        assert_eq!(
+            FloatLiteral::parse("0x.1P4", Span::default())
+                .unwrap()
+                .value,
+            1.0
+        );
+        assert_eq!(
+            FloatLiteral::parse("1.5", Span::default()).unwrap().ty,
+            FloatType::Double
+        );
+        assert_eq!(
+            FloatLiteral::parse("1.5f", Span::default()).unwrap().ty,
+            FloatType::Float
+        );
+        assert_eq!(
+            FloatLiteral::parse("0x1p-1F", Span::default())
+                .unwrap()
+                .value,
+            0.5
+        );
+    }
+
+    #[test]
+    fn parse_escapes() {
+        assert_eq!(
+            parse_string(r#""a\tb\n""#, Span::default()).unwrap(),
+            b"a\tb\n"
+        );
+        assert_eq!(
+            parse_string(r#""\"\\\x41\101\0""#, Span::default()).unwrap(),
+            b"\"\\AA\0"
+        );
+    }
+
+    #[test]
+    fn decimal_does_not_become_unsigned() {
+        let span = Span::new(3, 23);
+        let error = IntLiteral::parse("18446744073709551615", span).unwrap_err();
+        assert_eq!(
+            *error.kind,
+            DiagnosticKind::IntegerTooLargeForType("18446744073709551615".to_owned())
+        );
+        assert_eq!(error.span, span);
+    }
+}
@@ -1,25 +1,501 @@
+pub mod constant;
+pub mod decl;
+pub mod dump;
+pub mod env;
 pub mod expr;
+pub mod init;
+pub mod literal;
 pub mod stmt;
 
-use self::stmt::Stmt;
-use token::Tokens;
+use self::decl::ExternalDeclaration;
+use self::dump::Node;
+use self::env::{Env, Types};
+use diagnostic::{CheckResult, Diagnostic, ParseError};
+use token::{Token, Tokens};
 
+#[derive(Debug)]
 pub enum Ast {
-    Stmt(Stmt),
+    TranslationUnit(Vec<ExternalDeclaration>),
 }
 
 impl Ast {
-    pub fn parse<'a>(mut tokens: Tokens<'a>) -> Ast {
-        let stmt = Stmt::parse(&mut tokens);
-        if !tokens.is_empty() {
-            panic!("invalid tokens: {:?}", tokens);
+    /// parses a translation unit.  after a syntax error, the parsing resumes at the next
+    /// declaration or statement, so that all the errors are reported.
+    pub fn parse<'a>(mut tokens: Tokens<'a>) -> Result<Ast, Vec<ParseError>> {
+        let mut decls = Vec::new();
+        let mut errors = Vec::new();
+        while !tokens.is_empty() {
+            match ExternalDeclaration::parse(&mut tokens, &mut errors) {
+                Ok(decl) => decls.push(decl),
+                Err(error) => {
+                    errors.push(error);
+                    tokens.synchronize();
+                    // a `}` left unmatched at file scope is skipped
+                    if tokens.peek() == Some(Token::SyRBrace) {
+                        tokens.next();
+                    }
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(Ast::TranslationUnit(decls))
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// the semantic analysis, between the parsing and the code generation.  it resolves the
+    /// names and checks the types of all the expressions and the statements, and returns the
+    /// errors and the warnings found.  after an error in a declaration or a statement, the
+    /// analysis goes on with the next one.
+    pub fn check(&self) -> Vec<Diagnostic> {
+        self.analyze().0
+    }
+
+    /// `check`, which also returns the types of the expressions and the declarators checked
+    pub fn analyze(&self) -> (Vec<Diagnostic>, Types) {
+        let mut env = Env::new();
+        let mut errors = self.check_decls(&mut env);
+        errors.extend(env.take_warnings());
+        errors.sort_by_key(|diagnostic| diagnostic.span.start);
+        (errors, env.take_types())
+    }
+
+    /// checks the declarations in `env`, and returns the errors
+    fn check_decls(&self, env: &mut Env) -> Vec<Diagnostic> {
+        let mut errors = Vec::new();
+        match self {
+            Ast::TranslationUnit(decls) => {
+                for decl in decls {
+                    if let Err(error) = decl.check(env, &mut errors) {
+                        errors.push(error);
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    /// the node for the dump of the syntax tree, with `types` from `analyze`
+    pub fn dump(&self, types: &Types) -> Node {
+        match self {
+            Ast::TranslationUnit(decls) => Node::new("translation-unit", None)
+                .children(decls.iter().map(|decl| decl.dump(types))),
         }
-        Ast::Stmt(stmt)
     }
 
-    pub fn gen_code(self) -> usize {
+    /// adds the function definitions to the module of `env`.  the globals are added by
+    /// `Env::into_module` afterwards.
+    pub fn gen_code(self, env: &mut Env) {
+        // a function is referred to by its type at the end of the translation unit, which the
+        // declarations are checked once more to find before the lowering
+        let mut declarations = Env::new();
+        self.check_decls(&mut declarations);
+        env.set_prototypes(declarations.prototypes());
+
         match self {
-            Ast::Stmt(stmt) => stmt.gen_code(1),
+            Ast::TranslationUnit(decls) => {
+                for decl in decls {
+                    decl.gen_code(env);
+                }
+            }
         }
     }
 }
+
+/// the result of a check repeated by the code generation, which has passed the semantic
+/// analysis before
+fn checked<T>(result: CheckResult<T>) -> T {
+    result.unwrap_or_else(|diagnostic| panic!("ICE: unchecked error: {}", diagnostic.message()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lexer::Lexer;
+    use source::{Source, Span};
+    use warning::Warning;
+
+    /// the errors and the warnings in `text`
+    fn diagnostics(text: &str) -> Vec<Diagnostic> {
+        let source = Source::new(text.to_owned());
+        let (tokens, spans): (Vec<Token>, Vec<Span>) = Lexer::from_source(&source).unzip();
+        let ast = Ast::parse(Tokens::with_spans(&tokens, &spans)).unwrap();
+        ast.check()
+    }
+
+    /// the messages of the semantic errors in `text`
+    fn check(text: &str) -> Vec<String> {
+        diagnostics(text)
+            .into_iter()
+            .filter(Diagnostic::is_error)
+            .map(|diagnostic| diagnostic.message())
+            .collect()
+    }
+
+    #[test]
+    fn valid_program() {
+        let text = "
+            struct s { int x; };
+            int twice(int n) { return n * 2; }
+            int main(void) {
+                struct s v;
+                int a[2];
+                int *p = a;
+                void *q = p;
+                int (*f)(int) = twice;
+                v.x = f(1);
+                *p = v.x;
+                return g(a[0]);
+            }";
+        assert_eq!(check(text), Vec::<String>::new());
+    }
+
+    #[test]
+    fn lvalues_and_assignments() {
+        let text = "
+            int main(void) {
+                int a[2];
+                int x;
+                x + 1 = 2;
+                a = 0;
+                x = a;
+                return x;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "expression is not assignable",
+                "array type is not assignable",
+                "incompatible types in assignment (int from int *)",
+            ]
+        );
+    }
+
+    #[test]
+    fn const_members() {
+        let text = "
+            struct s { const int v; int w; };
+            struct t { struct s m[2]; };
+            int main(void) {
+                struct s a;
+                struct s b;
+                struct t c;
+                struct t d;
+                a.w = 1;
+                a = b;
+                c = d;
+                return a.v = 1;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "cannot assign to structure with const-qualified member `v`",
+                "cannot assign to structure with const-qualified member `v`",
+                "cannot assign to expression with const-qualified type",
+            ]
+        );
+    }
+
+    #[test]
+    fn parenthesized_lvalues() {
+        let text = "
+            struct s { int v; };
+            int main(void) {
+                struct s t;
+                struct s *p = &t;
+                int x;
+                (x) = 3;
+                (*p).v = x;
+                ((t)).v = (*p).v;
+                return (*p).v + *&(x);
+            }";
+        assert_eq!(check(text), Vec::<String>::new());
+        let text = "
+            int main(void) {
+                int x;
+                (x + 1) = 2;
+                return (x = 1) = 2;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "expression is not assignable",
+                "expression is not assignable"
+            ]
+        );
+    }
+
+    #[test]
+    fn operand_types() {
+        let text = "
+            struct s { int x; };
+            int main(void) {
+                struct s v;
+                double d;
+                int *p;
+                v + 1;
+                d % 2;
+                return p * 2;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "invalid operands to binary expression (struct s and int)",
+                "invalid operands to binary `%` (double)",
+                "invalid operands to binary expression (int * and int)",
+            ]
+        );
+        let text = "
+            struct s { int x; };
+            int main(void) {
+                struct s v;
+                int n;
+                -v;
+                *n;
+                (struct s) n;
+                (int *) 1.0;
+                return n(1);
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "invalid argument type struct s to unary expression",
+                "indirection requires pointer operand (int invalid)",
+                "used type struct s where arithmetic or pointer type is required",
+                "cannot cast double to int *",
+                "called object type int is not a function or function pointer",
+            ]
+        );
+    }
+
+    #[test]
+    fn pointer_operands() {
+        let text = "
+            int main(void) {
+                int a[2];
+                int *p = a;
+                const int *q = p + 1;
+                void *v = p;
+                p = 1 + p - 1;
+                return (p == 0) + (0 != q) + (p == q) + (q != v) + (p < q);
+            }";
+        assert_eq!(check(text), Vec::<String>::new());
+        let text = "
+            struct s;
+            int main(void) {
+                int *p;
+                double *d;
+                void *v;
+                struct s *t;
+                p + p;
+                1 - p;
+                v + 1;
+                t - 1;
+                p == 1;
+                p == d;
+                return p < v;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "invalid operands to binary expression (int * and int *)",
+                "invalid operands to binary expression (int and int *)",
+                "invalid operands to binary expression (void * and int)",
+                "invalid operands to binary expression (struct s * and int)",
+                "invalid operands to binary expression (int * and int)",
+                "invalid operands to binary expression (int * and double *)",
+                "invalid operands to binary expression (int * and void *)",
+            ]
+        );
+    }
+
+    #[test]
+    fn incomplete_types() {
+        let text = "
+            struct t;
+            struct u { struct t m; };
+            struct t a[2];
+            int main(void) {
+                struct t v;
+                int b[2] = { 1, 2, 3 };
+                return 0;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "field `m` has incomplete type struct t",
+                "array has incomplete element type struct t",
+                "variable `v` has incomplete type struct t",
+                "excess elements in initializer of int [2]",
+            ]
+        );
+    }
+
+    #[test]
+    fn negative_sizes() {
+        let text = "
+            int a[-1];
+            int b[2 - 3];
+            int c[4294967295u];
+            int main(void) {
+                int d[2] = { [-1] = 1 };
+                return 0;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "array has negative size",
+                "array has negative size",
+                "array designator value is negative",
+            ]
+        );
+    }
+
+    #[test]
+    fn discarded_qualifiers() {
+        let text = "
+            void set(int *p) { *p = 1; }
+            int main(void) {
+                const int c = 1;
+                int *p = &c;
+                const volatile int *q = &c;
+                set(&c);
+                p = q;
+                return *q;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "incompatible types in initialization (int * from const int *)",
+                "incompatible type for argument 1 of `set` (int * from const int *)",
+                "incompatible types in assignment (int * from const volatile int *)",
+            ]
+        );
+    }
+
+    #[test]
+    fn linkage_conflicts() {
+        let text = "
+            int z;
+            static int z;
+            static int s;
+            extern int s;
+            int s;
+            int main(void) {
+                extern int s;
+                return s;
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "static declaration of `z` follows non-static declaration",
+                "non-static declaration of `s` follows static declaration",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_global_initializers() {
+        // the variables are declared in spite of the errors
+        let text = "
+            int g = 1;
+            int h = g;
+            int a[] = { 1, g };
+            int *p = a + 1;
+            int main(void) {
+                int l;
+                static int *q = &l;
+                return h + a[0];
+            }";
+        assert_eq!(
+            check(text),
+            [
+                "initializer element is not a compile-time constant",
+                "initializer element is not a compile-time constant",
+                "initializer element is not a compile-time constant",
+                "initializer element is not a compile-time constant",
+            ]
+        );
+        // address constants
+        let text = "
+            int g;
+            int a[2];
+            int *p = &g;
+            void *v = &(g);
+            char *s = \"s\";
+            int *e = a;
+            int (*pa)[2] = &a;
+            int main(void) {
+                static int *q = a;
+                return *p + *s + *e + *q;
+            }";
+        assert_eq!(check(text), Vec::<String>::new());
+    }
+
+    #[test]
+    fn calls_and_returns() {
+        let text = "
+            struct s { int x; };
+            void f(int a, double b) { return; }
+            int main(void) {
+                struct s v;
+                f(1);
+                f(1, 2.0, 3);
+                f(v, 1);
+                return f(1, 2);
+            }
+            double g(void) { return; }";
+        let errors = check(text);
+        assert_eq!(errors.len(), 5);
+        assert_eq!(errors[0], "too few arguments to function call of `f`");
+        assert_eq!(errors[1], "too many arguments to function call of `f`");
+        assert!(errors[2].starts_with("incompatible type for argument 1 of `f`"));
+        assert!(errors[3].starts_with("incompatible types in return"));
+        assert_eq!(errors[4], "non-void function should return a value");
+    }
+
+    #[test]
+    fn warnings() {
+        let text = "
+            int f(int n) {
+                int unused;
+                int x = n / 0;
+                g(x);
+            }
+            int main(void) {
+                int y = 2147483647 + 1;
+                return -y;
+                y = 1;
+            }";
+        let warnings: Vec<(Option<Warning>, String)> = diagnostics(text)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.warning, diagnostic.message()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    Some(Warning::UnusedVariable),
+                    "unused variable `unused`".to_owned()
+                ),
+                (
+                    Some(Warning::DivisionByZero),
+                    "division by zero is undefined".to_owned()
+                ),
+                (
+                    Some(Warning::ReturnType),
+                    "non-void function `f` does not return a value".to_owned()
+                ),
+                (
+                    Some(Warning::Overflow),
+                    "signed integer overflow in constant expression".to_owned()
+                ),
+                (
+                    Some(Warning::UnreachableCode),
+                    "code will never be executed".to_owned()
+                ),
+            ]
+        );
+    }
+}
@@ -1,88 +1,280 @@
-use super::expr::Expr;
+use super::checked;
+use super::decl::{self, Declaration};
+use super::dump::Node;
+use super::env::{Env, Types};
+use super::expr::{self, Expr};
+use diagnostic::{CheckResult, Diagnostic, ParseError, ParseResult};
+use message::{Conversion, DiagnosticKind, Term};
+use source::Span;
 use token::{Token, Tokens};
+use types::Type;
 
 #[derive(Debug)]
 pub struct Compound {
-    stmts: Vec<Stmt>,
+    items: Vec<BlockItem>,
+    span: Span,
+}
+
+#[derive(Debug)]
+pub enum BlockItem {
+    Declaration(Box<Declaration>),
+    Stmt(Box<Stmt>),
 }
 
 #[derive(Debug)]
 pub enum Stmt {
     Compound(Box<Compound>),
+    Expr(Box<Expr>),
     Jump(Box<Jump>),
 }
 
 #[derive(Debug)]
 pub enum Jump {
-    Return(Box<Expr>),
+    Return(Option<Box<Expr>>, Span),
 }
 
 impl Compound {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Compound {
-        match tokens.next() {
-            Some(Token::SyLBrace) => {
-                let mut stmts = Vec::new();
-                loop {
-                    stmts.push(Stmt::parse(tokens));
-                    if let Some(Token::SyRBrace) = tokens.peek() {
-                        tokens.eat(Token::SyRBrace);
-                        break;
-                    }
+    /// parses a block.  a syntax error in an item is added to `errors`, and the parsing resumes
+    /// after the item.
+    pub fn parse<'a>(
+        tokens: &mut Tokens<'a>,
+        errors: &mut Vec<ParseError>,
+    ) -> ParseResult<Compound> {
+        if tokens.peek() != Some(Token::SyLBrace) {
+            return Err(tokens.expected(Term::CompoundStatement));
+        }
+        let start = tokens.span();
+        tokens.eat(Token::SyLBrace)?;
+        let mut items = Vec::new();
+        loop {
+            let item = match tokens.peek() {
+                Some(Token::SyRBrace) => {
+                    tokens.eat(Token::SyRBrace)?;
+                    break;
+                }
+                None => return Err(tokens.expected(Term::Token("}".to_owned()))),
+                Some(token) if decl::is_declaration_specifier(token) => {
+                    Declaration::parse(tokens).map(|decl| BlockItem::Declaration(Box::new(decl)))
+                }
+                Some(_) => Stmt::parse(tokens, errors).map(|stmt| BlockItem::Stmt(Box::new(stmt))),
+            };
+            match item {
+                Ok(item) => items.push(item),
+                Err(error) => {
+                    errors.push(error);
+                    tokens.synchronize();
                 }
-                Compound { stmts }
             }
-            other => {
-                panic!("expected compound statement (`{{`), found {:?}", other);
+        }
+        Ok(Compound {
+            items,
+            span: start.to(tokens.prev_span()),
+        })
+    }
+
+    /// the closing brace
+    pub fn end_span(&self) -> Span {
+        Span::new(self.span.end - 1, self.span.end)
+    }
+
+    /// whether the end of this block is unreachable because of a `return` in it
+    pub fn returns(&self) -> bool {
+        self.items.iter().any(|item| match item {
+            BlockItem::Stmt(stmt) => match **stmt {
+                Stmt::Jump(_) => true,
+                Stmt::Compound(ref compound) => compound.returns(),
+                Stmt::Expr(_) => false,
+            },
+            BlockItem::Declaration(_) => false,
+        })
+    }
+
+    /// checks the items in a new scope.  an error in an item is added to `errors`, and the
+    /// analysis goes on with the next item.
+    pub fn check(&self, env: &mut Env, errors: &mut Vec<Diagnostic>) {
+        env.enter_scope();
+        self.check_items(env, errors);
+        env.leave_scope();
+    }
+
+    /// checks the items in the current scope, as `gen_items`.  the first item after a `return`
+    /// is warned about.
+    pub fn check_items(&self, env: &mut Env, errors: &mut Vec<Diagnostic>) {
+        let mut after_return = false;
+        for item in &self.items {
+            if after_return {
+                let span = match item {
+                    BlockItem::Declaration(decl) => decl.span(),
+                    BlockItem::Stmt(stmt) => stmt.span(),
+                };
+                env.warn(span, DiagnosticKind::UnreachableCode);
+                after_return = false;
+            }
+            if let BlockItem::Stmt(ref stmt) = *item {
+                if let Stmt::Jump(_) = **stmt {
+                    after_return = true;
+                }
+            }
+            let result = match item {
+                BlockItem::Declaration(decl) => decl.check(env),
+                BlockItem::Stmt(stmt) => stmt.check(env, errors),
+            };
+            if let Err(error) = result {
+                errors.push(error);
             }
         }
     }
 
-    pub fn gen_code(self, mut reg: usize) -> usize {
-        for stmt in self.stmts {
-            reg = stmt.gen_code(reg);
-            reg += 1;
+    pub fn gen_code(self, env: &mut Env) {
+        env.enter_scope();
+        self.gen_items(env);
+        env.leave_scope();
+    }
+
+    /// generates code for the items in the current scope.  the body of a function shares its
+    /// scope with the parameters.
+    pub fn gen_items(self, env: &mut Env) {
+        for item in self.items {
+            match item {
+                BlockItem::Declaration(decl) => decl.gen_code(env),
+                BlockItem::Stmt(stmt) => stmt.gen_code(env),
+            }
         }
-        reg
+    }
+
+    /// the node for the dump of the syntax tree
+    pub fn dump(&self, types: &Types) -> Node {
+        let items = self.items.iter().map(|item| match item {
+            BlockItem::Declaration(declaration) => declaration.dump(types),
+            BlockItem::Stmt(stmt) => stmt.dump(types),
+        });
+        Node::new("compound", Some(self.span)).children(items)
     }
 }
 
 impl Stmt {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Stmt {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>, errors: &mut Vec<ParseError>) -> ParseResult<Stmt> {
         match tokens.peek() {
-            Some(Token::SyLBrace) => Stmt::Compound(Box::new(Compound::parse(tokens))),
-            _ => Stmt::Jump(Box::new(Jump::parse(tokens))),
+            Some(Token::SyLBrace) => Ok(Stmt::Compound(Box::new(Compound::parse(tokens, errors)?))),
+            Some(Token::KwReturn) => Ok(Stmt::Jump(Box::new(Jump::parse(tokens)?))),
+            _ => {
+                let expr = Expr::parse(tokens)?;
+                tokens.eat_err(
+                    Token::SySemicolon,
+                    DiagnosticKind::MissingSemicolon(Term::ExpressionStatement),
+                )?;
+                Ok(Stmt::Expr(Box::new(expr)))
+            }
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Compound(compound) => compound.span,
+            Stmt::Expr(expr) => expr.span(),
+            Stmt::Jump(jump) => match **jump {
+                Jump::Return(_, span) => span,
+            },
+        }
+    }
+
+    pub fn check(&self, env: &mut Env, errors: &mut Vec<Diagnostic>) -> CheckResult<()> {
+        match self {
+            Stmt::Compound(compound) => {
+                compound.check(env, errors);
+                Ok(())
+            }
+            Stmt::Expr(expr) => expr.check(env).map(|_| ()),
+            Stmt::Jump(jump) => jump.check(env),
         }
     }
 
-    pub fn gen_code(self, reg: usize) -> usize {
+    /// appends the code for this statement to the current block
+    pub fn gen_code(self, env: &mut Env) {
         match self {
-            Stmt::Compound(compound) => compound.gen_code(reg),
-            Stmt::Jump(jump) => jump.gen_code(reg),
+            Stmt::Compound(compound) => compound.gen_code(env),
+            Stmt::Expr(expr) => {
+                expr.gen_code(env);
+            }
+            Stmt::Jump(jump) => jump.gen_code(env),
+        }
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        match self {
+            Stmt::Compound(compound) => compound.dump(types),
+            Stmt::Expr(expr) => {
+                Node::new("expression-statement", Some(self.span())).child(expr.dump(types))
+            }
+            Stmt::Jump(jump) => jump.dump(types),
         }
     }
 }
 
 impl Jump {
-    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> Jump {
-        match tokens.next() {
+    pub fn parse<'a>(tokens: &mut Tokens<'a>) -> ParseResult<Jump> {
+        let start = tokens.span();
+        match tokens.peek() {
             Some(Token::KwReturn) => {
-                let expr = Expr::parse(tokens);
-                tokens.eat_err(
+                tokens.eat(Token::KwReturn)?;
+                let expr = match tokens.peek() {
+                    Some(Token::SySemicolon) => None,
+                    _ => Some(Box::new(Expr::parse(tokens)?)),
+                };
+                tokens.eat_or_insert(
                     Token::SySemicolon,
-                    "missing semicolon after jump statement.",
-                );
-                Jump::Return(Box::new(expr))
+                    DiagnosticKind::MissingSemicolon(Term::JumpStatement),
+                )?;
+                Ok(Jump::Return(expr, start.to(tokens.prev_span())))
             }
-            other => panic!("expected jump statement, found {:?}", other),
+            _ => Err(tokens.expected(Term::JumpStatement)),
         }
     }
 
-    pub fn gen_code(self, reg: usize) -> usize {
+    /// checks that the returned value matches the return type of the function
+    pub fn check(&self, env: &mut Env) -> CheckResult<()> {
+        let ret = env.return_type().clone();
         match self {
-            Jump::Return(expr) => {
-                let reg = expr.gen_code(reg);
-                println!("  ret i32 %{}", reg);
-                reg + 1
+            Jump::Return(Some(expr), _) => {
+                if ret == Type::Void {
+                    return Err(Diagnostic::new(
+                        expr.span(),
+                        DiagnosticKind::VoidReturnValue,
+                    ));
+                }
+                match **expr {
+                    Expr::Assignment(ref assignment) => {
+                        expr::check_assignable(&ret, assignment, env, Conversion::Return)
+                    }
+                }
+            }
+            Jump::Return(None, span) => {
+                if ret != Type::Void {
+                    return Err(Diagnostic::new(*span, DiagnosticKind::MissingReturnValue));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn gen_code(self, env: &mut Env) {
+        match self {
+            Jump::Return(Some(expr), _) => {
+                let ret = env.return_type().clone();
+                let ty = checked(expr.ty(env));
+                let value = expr::gen_conversion(expr.gen_code(env), &ty, &ret, env);
+                env.block().ret(value);
+            }
+            Jump::Return(None, _) => env.block().ret_void(),
+        }
+        // the instructions after `ret` belong to a new unnamed block
+        env.start_block();
+    }
+
+    pub fn dump(&self, types: &Types) -> Node {
+        match self {
+            Jump::Return(expr, span) => {
+                Node::new("return", Some(*span)).children(expr.iter().map(|expr| expr.dump(types)))
             }
         }
     }
@@ -95,6 +287,53 @@ mod tests {
     #[test]
     fn parse_return() {
         let tokens = &mut Tokens::new(&[Token::KwReturn, Token::Literal("42"), Token::SySemicolon]);
-        let _ = Stmt::parse(tokens);
+        Stmt::parse(tokens, &mut Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn parse_compound() {
+        let tokens = &mut Tokens::new(&[
+            Token::SyLBrace,
+            Token::TyFloat,
+            Token::Ident("x"),
+            Token::SySemicolon,
+            Token::Ident("x"),
+            Token::OpAssign,
+            Token::Literal("1"),
+            Token::SySemicolon,
+            Token::SyLBrace,
+            Token::SyRBrace,
+            Token::SyRBrace,
+        ]);
+        Stmt::parse(tokens, &mut Vec::new()).unwrap();
+        assert!(tokens.is_empty());
+    }
+
+    #[test]
+    fn recover_from_syntax_errors() {
+        let tokens = &mut Tokens::new(&[
+            Token::SyLBrace,
+            Token::Ident("x"),
+            Token::OpAdd,
+            Token::SySemicolon,
+            Token::SyLBrace,
+            Token::KwReturn,
+            Token::SyRBrace,
+            Token::Ident("x"),
+            Token::SySemicolon,
+            Token::SyRBrace,
+        ]);
+        let mut errors = Vec::new();
+        let stmt = Stmt::parse(tokens, &mut errors).unwrap();
+        assert!(tokens.is_empty());
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            *errors[0].kind,
+            DiagnosticKind::Expected(Term::Expression, Some(";".to_owned()))
+        );
+        match stmt {
+            Stmt::Compound(compound) => assert_eq!(compound.items.len(), 2),
+            _ => panic!("expected compound statement"),
+        }
     }
 }
@@ -0,0 +1,422 @@
+use message::{DiagnosticKind, Language, Note};
+use source::{Source, Span};
+use warning::Warning;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// how diagnostics are printed (`--diagnostics-format=`)
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+
+    /// a JSON object per line
+    Json,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// a span related to the primary span of a diagnostic, and what it is
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub note: Note,
+}
+
+/// a suggested edit of the source code: `span` is replaced with `replacement`.  an empty span
+/// is an insertion.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FixIt {
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl FixIt {
+    /// inserts `text` at `pos`
+    pub fn insertion<S: Into<String>>(pos: usize, text: S) -> FixIt {
+        FixIt {
+            span: Span::new(pos, pos),
+            replacement: text.into(),
+        }
+    }
+}
+
+/// the original text of `source` with `fixits` applied.  a fix-it overlapping one applied
+/// before is skipped.
+pub fn apply_fixits(source: &Source, fixits: &[&FixIt]) -> String {
+    let mut fixits = fixits.to_vec();
+    fixits.sort_by_key(|fixit| (fixit.span.start, fixit.span.end));
+
+    let original = source.original();
+    let mut fixed = String::with_capacity(original.len());
+    let mut copied = 0;
+    for fixit in fixits {
+        let start = source.original_offset(fixit.span.start);
+        let end = source.original_offset(fixit.span.end);
+        if start < copied {
+            continue;
+        }
+        fixed.push_str(&original[copied..start]);
+        fixed.push_str(&fixit.replacement);
+        copied = end;
+    }
+    fixed.push_str(&original[copied..]);
+    fixed
+}
+
+/// an error or a warning in the source code and the span it refers to
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub span: Span,
+
+    /// boxed to keep the results carrying diagnostics small
+    pub kind: Box<DiagnosticKind>,
+    pub severity: Severity,
+
+    /// the kind of the warning, which is kept when it is made an error by `-Werror`
+    pub warning: Option<Warning>,
+
+    /// other spans the diagnostic refers to, printed as notes
+    pub secondary: Vec<Label>,
+
+    pub fixits: Vec<FixIt>,
+}
+
+impl Diagnostic {
+    /// an error, or a warning if `kind` is one
+    pub fn new(span: Span, kind: DiagnosticKind) -> Diagnostic {
+        let warning = kind.warning();
+        Diagnostic {
+            span,
+            kind: Box::new(kind),
+            severity: if warning.is_some() {
+                Severity::Warning
+            } else {
+                Severity::Error
+            },
+            warning,
+            secondary: Vec::new(),
+            fixits: Vec::new(),
+        }
+    }
+
+    /// adds a secondary span
+    pub fn with_label(mut self, span: Span, note: Note) -> Diagnostic {
+        self.secondary.push(Label { span, note });
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// the stable code identifying the kind of this diagnostic, such as `E0401`
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    /// the message in English
+    pub fn message(&self) -> String {
+        self.kind.message(Language::English)
+    }
+
+    /// formats this diagnostic as `file:line:col: error[code]: message`, followed by the source
+    /// line and a caret underlining the span.  a warning is followed by the option controlling
+    /// it, and the secondary spans and the fix-its follow as notes.
+    pub fn render(&self, file_name: &str, source: &Source, language: Language) -> String {
+        let option = match (self.warning, self.severity) {
+            (Some(warning), Severity::Warning) => format!(" [-W{}]", warning.name()),
+            (Some(warning), Severity::Error) => format!(" [-Werror,-W{}]", warning.name()),
+            (None, _) => String::new(),
+        };
+        let severity = format!("{}[{}]", self.severity.name(), self.code());
+        let mut rendered = render_snippet(
+            file_name,
+            source,
+            self.span,
+            &severity,
+            &format!("{}{}", self.kind.message(language), option),
+        );
+        for label in &self.secondary {
+            rendered.push('\n');
+            rendered.push_str(&render_snippet(
+                file_name,
+                source,
+                label.span,
+                "note",
+                &label.note.message(language),
+            ));
+        }
+        for fixit in &self.fixits {
+            let note = if fixit.span.start == fixit.span.end {
+                Note::Insert(fixit.replacement.clone())
+            } else {
+                Note::Replace(fixit.replacement.clone())
+            };
+            rendered.push('\n');
+            rendered.push_str(&render_snippet(
+                file_name,
+                source,
+                fixit.span,
+                "note",
+                &note.message(language),
+            ));
+        }
+        rendered
+    }
+
+    /// formats this diagnostic as a JSON object on a line
+    pub fn to_json(&self, file_name: &str, source: &Source, language: Language) -> String {
+        let location = |span: Span| {
+            let (line, column) = source.location(span.start);
+            let (end_line, end_column) = source.location(span.end);
+            let (file, line) = source.presumed_line(line);
+            let (_, end_line) = source.presumed_line(end_line);
+            format!(
+                r#""file":{},"line":{},"column":{},"end_line":{},"end_column":{}"#,
+                json_string(file.unwrap_or(file_name)),
+                line,
+                column,
+                end_line,
+                end_column
+            )
+        };
+        let secondary: Vec<String> = self
+            .secondary
+            .iter()
+            .map(|label| {
+                format!(
+                    r#"{{{},"label":{}}}"#,
+                    location(label.span),
+                    json_string(&label.note.message(language))
+                )
+            })
+            .collect();
+        let fixits: Vec<String> = self
+            .fixits
+            .iter()
+            .map(|fixit| {
+                format!(
+                    r#"{{{},"replacement":{}}}"#,
+                    location(fixit.span),
+                    json_string(&fixit.replacement)
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"severity":{},"code":{},"option":{},"message":{},"primary":{{{}}},"secondary":[{}],"fixits":[{}]}}"#,
+            json_string(self.severity.name()),
+            json_string(self.code()),
+            self.warning
+                .map_or("null".to_owned(), |warning| json_string(warning.name())),
+            json_string(&self.kind.message(language)),
+            location(self.span),
+            secondary.join(","),
+            fixits.join(",")
+        )
+    }
+}
+
+/// formats `file:line:col: severity: message` for `span`, followed by the source line and a
+/// caret underlining the span
+fn render_snippet(
+    file_name: &str,
+    source: &Source,
+    span: Span,
+    severity: &str,
+    message: &str,
+) -> String {
+    let (line, column) = source.location(span.start);
+    let text = source.line(line);
+
+    // a span over multiple lines is underlined up to the end of the first line
+    let end_column = match source.location(span.end) {
+        (end_line, end_column) if end_line == line => end_column,
+        _ => text.chars().count() + 1,
+    };
+    let width = end_column.saturating_sub(column).max(1);
+    let (file, presumed_line) = source.presumed_line(line);
+
+    // tabs are kept so that the caret lines up with the source line
+    let indent: String = text
+        .chars()
+        .take(column - 1)
+        .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    format!(
+        "{}:{}:{}: {}: {}\n{}\n{}^{}",
+        file.unwrap_or(file_name),
+        presumed_line,
+        column,
+        severity,
+        message,
+        text,
+        indent,
+        "~".repeat(width - 1)
+    )
+}
+
+/// formats `text` as a JSON string literal
+pub fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\t' => json.push_str("\\t"),
+            '\r' => json.push_str("\\r"),
+            ch if (ch as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// a syntax error found by the parser
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub span: Span,
+    pub kind: Box<DiagnosticKind>,
+
+    /// the edit that fixes the error, if it is obvious
+    pub fixit: Option<FixIt>,
+}
+
+impl ParseError {
+    pub fn new(span: Span, kind: DiagnosticKind) -> ParseError {
+        ParseError {
+            span,
+            kind: Box::new(kind),
+            fixit: None,
+        }
+    }
+
+    pub fn with_fixit(self, fixit: FixIt) -> ParseError {
+        ParseError {
+            fixit: Some(fixit),
+            ..self
+        }
+    }
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(error: ParseError) -> Diagnostic {
+        Diagnostic {
+            fixits: error.fixit.into_iter().collect(),
+            ..Diagnostic::new(error.span, *error.kind)
+        }
+    }
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// the result of the semantic analysis of a declaration, a statement or an expression
+pub type CheckResult<T> = Result<T, Diagnostic>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use message::DiagnosticKind::*;
+
+    #[test]
+    fn render() {
+        let source = Source::new("int main(void) {\n\treturn x + 1;\n}\n".to_owned());
+        let diagnostic = Diagnostic::new(Span::new(25, 30), UndeclaredIdentifier("x".to_owned()));
+        assert_eq!(
+            diagnostic.render("a.c", &source, Language::English),
+            "a.c:2:9: error[E0401]: use of undeclared identifier `x`\n\treturn x + 1;\n\t       ^~~~~"
+        );
+    }
+
+    #[test]
+    fn render_included() {
+        let text = "# 1 \"a.c\"\n# 1 \"b.h\" 1\nint y;\nint z = x;\n# 3 \"a.c\" 2\n";
+        let source = Source::new(text.to_owned());
+        let diagnostic = Diagnostic::new(Span::new(37, 38), UndeclaredIdentifier("x".to_owned()));
+        assert_eq!(
+            diagnostic.render("a.c", &source, Language::English),
+            "b.h:2:9: error[E0401]: use of undeclared identifier `x`\nint z = x;\n        ^"
+        );
+    }
+
+    #[test]
+    fn render_warning() {
+        let source = Source::new("int x;\n".to_owned());
+        let warning = Diagnostic::new(Span::new(4, 5), UnusedVariable("x".to_owned()));
+        assert_eq!(
+            warning.render("a.c", &source, Language::English),
+            "a.c:1:5: warning[W0002]: unused variable `x` [-Wunused-variable]\nint x;\n    ^"
+        );
+    }
+
+    #[test]
+    fn render_note() {
+        let source = Source::new("int x;\nint x;\n".to_owned());
+        let diagnostic = Diagnostic::new(Span::new(11, 12), Redefinition("x".to_owned()))
+            .with_label(Span::new(4, 5), Note::PreviousDefinition);
+        assert_eq!(
+            diagnostic.render("a.c", &source, Language::English),
+            "a.c:2:5: error[E0212]: redefinition of `x`\nint x;\n    ^\n\
+             a.c:1:5: note: previous definition is here\nint x;\n    ^"
+        );
+        assert_eq!(
+            diagnostic.render("a.c", &source, Language::Japanese),
+            "a.c:2:5: error[E0212]: `x` の再定義です\nint x;\n    ^\n\
+             a.c:1:5: note: 以前の定義はここです\nint x;\n    ^"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let source = Source::new("int x;\n".to_owned());
+        let warning = Diagnostic::new(Span::new(4, 5), UnusedVariable("x".to_owned()));
+        assert_eq!(
+            warning.to_json("dir/\"a\".c", &source, Language::English),
+            r#"{"severity":"warning","code":"W0002","option":"unused-variable","message":"unused variable `x`","primary":{"file":"dir/\"a\".c","line":1,"column":5,"end_line":1,"end_column":6},"secondary":[],"fixits":[]}"#
+        );
+    }
+
+    #[test]
+    fn fixits() {
+        let source = Source::new("return 1\n}\nf(x;\n".to_owned());
+        let semicolon = FixIt::insertion(8, ";");
+        let paren = FixIt::insertion(14, ")");
+        let overlapping = FixIt {
+            span: Span::new(13, 15),
+            replacement: "y)".to_owned(),
+        };
+        assert_eq!(
+            apply_fixits(&source, &[&paren, &semicolon]),
+            "return 1;\n}\nf(x);\n"
+        );
+        assert_eq!(
+            apply_fixits(&source, &[&paren, &overlapping, &semicolon]),
+            "return 1;\n}\nf(y)\n"
+        );
+    }
+}
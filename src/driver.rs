@@ -0,0 +1,340 @@
+//! the compiler driver.  a C file is compiled to LLVM IR in memory, and the rest is done by
+//! external tools as cc does: the preprocessor, `llc`, the assembler and the linker.
+
+use std::env;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{self, prelude::*};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread;
+
+use args::{Args, Emit, Input};
+use cerussite::ast::env::Types;
+use cerussite::diagnostic::{self, Diagnostic, Format};
+use cerussite::message::Language;
+use cerussite::{Options, Source};
+
+pub type Result<T> = ::std::result::Result<T, Box<dyn Error>>;
+
+/// the external tools, which can be replaced with the environment variables
+pub struct Toolchain {
+    pub cpp: String,
+    pub llc: String,
+    pub assembler: String,
+    pub linker: String,
+}
+
+impl Toolchain {
+    pub fn from_env() -> Toolchain {
+        let tool = |var, default: &str| env::var(var).unwrap_or_else(|_| default.to_owned());
+        Toolchain {
+            cpp: tool("CERUSSITE_CPP", "cpp"),
+            llc: tool("CERUSSITE_LLC", "llc"),
+            assembler: tool("CERUSSITE_AS", "as"),
+            linker: tool("CERUSSITE_LD", "cc"),
+        }
+    }
+
+    /// preprocesses `text` of `input`.  the line markers are kept, by which `Source` maps the
+    /// diagnostics back to the lines of the files included.
+    fn preprocess(&self, args: &Args, input: &Input, text: &str) -> Result<String> {
+        let mut cpp_args = args.cpp_args.clone();
+        let output = match input {
+            Input::Stdin => {
+                cpp_args.push("-".to_owned());
+                run_tool(&self.cpp, &cpp_args, Some(text.as_bytes().to_vec()))?
+            }
+            // the file is read by cpp so that `#include "..."` is relative to it
+            Input::File(name) => {
+                cpp_args.push(name.clone());
+                run_tool(&self.cpp, &cpp_args, None)?
+            }
+        };
+        Ok(String::from_utf8(output)?)
+    }
+
+    /// compiles LLVM IR into assembly
+    fn compile_ir(&self, ir: &str, opt_level: u32) -> Result<Vec<u8>> {
+        let llc_args = [format!("-O{}", opt_level), "-o".to_owned(), "-".to_owned()];
+        run_tool(&self.llc, &llc_args, Some(ir.as_bytes().to_vec()))
+    }
+
+    fn assemble(&self, asm: &Path, object: &Path) -> Result<()> {
+        let as_args = [asm.as_os_str(), OsStr::new("-o"), object.as_os_str()];
+        run_tool(&self.assembler, &as_args, None).map(|_| ())
+    }
+
+    fn link(&self, objects: &[PathBuf], link_args: &[String], output: &Path) -> Result<()> {
+        let mut ld_args: Vec<&OsStr> = objects.iter().map(|object| object.as_os_str()).collect();
+        ld_args.extend(link_args.iter().map(OsStr::new));
+        ld_args.push(OsStr::new("-o"));
+        ld_args.push(output.as_os_str());
+        run_tool(&self.linker, &ld_args, None).map(|_| ())
+    }
+}
+
+/// runs `program`, writing `stdin` to it, and returns its stdout
+fn run_tool<S: AsRef<OsStr>>(program: &str, args: &[S], stdin: Option<Vec<u8>>) -> Result<Vec<u8>> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to run `{}`: {}", program, error))?;
+    // written from another thread so that the pipes do not fill up
+    let writer = stdin.map(|bytes| {
+        let mut pipe = child.stdin.take().unwrap();
+        thread::spawn(move || pipe.write_all(&bytes))
+    });
+    let output = child.wait_with_output()?;
+    if let Some(writer) = writer {
+        writer.join().unwrap()?;
+    }
+    if !output.status.success() {
+        return Err(format!("`{}` failed ({})", program, output.status).into());
+    }
+    Ok(output.stdout)
+}
+
+/// temporary files, which are removed on drop
+#[derive(Default)]
+struct TempFiles {
+    paths: Vec<PathBuf>,
+}
+
+impl TempFiles {
+    fn create(&mut self, extension: &str) -> PathBuf {
+        let name = format!(
+            "cerussite-{}-{}.{}",
+            process::id(),
+            self.paths.len(),
+            extension
+        );
+        let path = env::temp_dir().join(name);
+        self.paths.push(path.clone());
+        path
+    }
+}
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// the file of `-o`, or None for stdout (`-o -`) or without `-o`
+fn output_file(args: &Args) -> Option<&Path> {
+    args.output
+        .as_deref()
+        .filter(|output| *output != "-")
+        .map(Path::new)
+}
+
+/// where the output from `input` goes: the file of `-o`, the file named after the input for `-S`
+/// and `-c` (or with several inputs), or stdout (`None`)
+fn output_path(args: &Args, input: &Input, extension: &str) -> Option<PathBuf> {
+    if args.output.is_some() {
+        return output_file(args).map(Path::to_path_buf);
+    }
+    let named = args.cc_outputs || args.inputs.len() > 1;
+    if !named || (extension != "s" && extension != "o") {
+        return None;
+    }
+    let stem = match input {
+        Input::Stdin => OsStr::new("stdin"),
+        Input::File(name) => Path::new(name).file_stem().unwrap_or_default(),
+    };
+    Some(Path::new(stem).with_extension(extension))
+}
+
+fn write_output(path: Option<&Path>, bytes: &[u8]) -> Result<()> {
+    match path {
+        Some(path) => fs::write(path, bytes)?,
+        None => io::stdout().write_all(bytes)?,
+    }
+    Ok(())
+}
+
+/// whether `text` has a preprocessing directive
+fn has_directives(text: &str) -> bool {
+    text.lines().any(|line| line.trim_start().starts_with('#'))
+}
+
+/// processes all the inputs as `args` tells.  returns false if some C file has errors, which
+/// are printed.
+pub fn run(args: &Args, language: Language) -> Result<bool> {
+    let tools = Toolchain::from_env();
+    let emit = args.emit();
+    let mut temps = TempFiles::default();
+    let mut objects = Vec::new();
+    let mut succeeded = true;
+    for input in &args.inputs {
+        match input.extension() {
+            Some("c") => {}
+            Some("s") if emit == Emit::Obj || emit == Emit::Exe => {
+                let object = match output_path(args, input, "o") {
+                    Some(ref path) if emit == Emit::Obj => path.clone(),
+                    _ => temps.create("o"),
+                };
+                tools.assemble(Path::new(input.name()), &object)?;
+                if emit == Emit::Obj && output_path(args, input, "o").is_none() {
+                    write_output(None, &fs::read(&object)?)?;
+                }
+                objects.push(object);
+                continue;
+            }
+            _ if emit == Emit::Exe => {
+                objects.push(PathBuf::from(input.name()));
+                continue;
+            }
+            _ => {
+                eprintln!(
+                    "warning: `{}`: input file unused because linking is not done",
+                    input.name()
+                );
+                continue;
+            }
+        }
+
+        let mut text = String::new();
+        match input {
+            Input::Stdin => io::stdin().read_to_string(&mut text)?,
+            Input::File(name) => File::open(name)
+                .and_then(|mut file| file.read_to_string(&mut text))
+                .map_err(|error| format!("cannot read `{}`: {}", name, error))?,
+        };
+        let defines = args
+            .cpp_args
+            .iter()
+            .any(|arg| arg.starts_with("-D") || arg.starts_with("-U"));
+        let preprocessed = emit == Emit::Preprocessed || defines || has_directives(&text);
+        if preprocessed {
+            text = tools.preprocess(args, input, &text)?;
+        }
+        if emit == Emit::Preprocessed {
+            write_output(output_file(args), text.as_bytes())?;
+            continue;
+        }
+
+        let stage = match emit {
+            Emit::Tokens | Emit::Ast | Emit::AstJson => emit,
+            _ => Emit::Ir,
+        };
+        let output = match compile(args, input, text, preprocessed, stage, language)? {
+            Some(output) => output,
+            None => {
+                succeeded = false;
+                continue;
+            }
+        };
+        if stage == emit {
+            write_output(output_path(args, input, "ll").as_deref(), output.as_bytes())?;
+            continue;
+        }
+
+        let asm = tools.compile_ir(&output, args.opt_level)?;
+        if emit == Emit::Asm {
+            write_output(output_path(args, input, "s").as_deref(), &asm)?;
+            continue;
+        }
+        let asm_path = temps.create("s");
+        fs::write(&asm_path, asm)?;
+        let object = match output_path(args, input, "o") {
+            Some(ref path) if emit == Emit::Obj => path.clone(),
+            _ => temps.create("o"),
+        };
+        tools.assemble(&asm_path, &object)?;
+        if emit == Emit::Obj && output_path(args, input, "o").is_none() {
+            write_output(None, &fs::read(&object)?)?;
+        }
+        objects.push(object);
+    }
+
+    if succeeded && emit == Emit::Exe {
+        let output = args.output.as_ref().map_or("a.out", String::as_str);
+        tools.link(&objects, &args.link_args, Path::new(output))?;
+    }
+    Ok(succeeded)
+}
+
+/// compiles the C source `text` of `input` in memory to `emit` (tokens, ast or ir), and prints
+/// the diagnostics.  returns `None` if there are errors.
+fn compile(
+    args: &Args,
+    input: &Input,
+    text: String,
+    preprocessed: bool,
+    emit: Emit,
+    language: Language,
+) -> Result<Option<String>> {
+    let source = Source::new(text);
+    let result = match emit {
+        Emit::Tokens => cerussite::tokenize(&source).map(|tokens| {
+            let dump = tokens.iter().map(|(token, span)| {
+                let (line, column) = source.location(span.start);
+                let (_, line) = source.presumed_line(line);
+                format!("{}:{}: {:?}\n", line, column, token)
+            });
+            (dump.collect(), Vec::new())
+        }),
+        Emit::Ast => cerussite::parse(&source).map(|ast| {
+            let sexp = ast.dump(&Types::new()).to_sexp();
+            (sexp + "\n", Vec::new())
+        }),
+        Emit::AstJson => {
+            let options = Options {
+                warnings: args.warnings.clone(),
+                verify_ir: args.verify_ir,
+            };
+            cerussite::check(&source, &options).map(|checked| {
+                let json = checked.ast.dump(&checked.types).to_json(&source);
+                (json + "\n", checked.warnings)
+            })
+        }
+        _ => {
+            let options = Options {
+                warnings: args.warnings.clone(),
+                verify_ir: args.verify_ir,
+            };
+            cerussite::compile_source(&source, &options)
+                .map(|module| (module.ir.to_string(), module.warnings))
+        }
+    };
+
+    let (output, diagnostics) = match result {
+        Ok((output, warnings)) => (Some(output), warnings),
+        Err(diagnostics) => (None, diagnostics),
+    };
+    for diagnostic in &diagnostics {
+        match args.format {
+            Format::Text => eprintln!("{}", diagnostic.render(input.name(), &source, language)),
+            Format::Json => eprintln!("{}", diagnostic.to_json(input.name(), &source, language)),
+        }
+    }
+    if args.apply_fixits {
+        let fixits: Vec<_> = diagnostics
+            .iter()
+            .flat_map(|diagnostic| &diagnostic.fixits)
+            .collect();
+        if !fixits.is_empty() && preprocessed {
+            eprintln!(
+                "warning: fix-its are not applied to `{}`, which is preprocessed",
+                input.name()
+            );
+        } else if !fixits.is_empty() {
+            fs::write(input.name(), diagnostic::apply_fixits(&source, &fixits))?;
+        }
+    }
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        return Ok(None);
+    }
+    Ok(output)
+}
@@ -0,0 +1,230 @@
+use super::function::{BlockId, Definition, Function, ValueId};
+use super::instruction::{CastOp, FloatPredicate, Instruction, IntPredicate};
+use super::types::Type;
+use super::value::Value;
+
+/// the builder method of a binary operator, such as `Builder::add`
+pub type BinaryBuilder<'a> = fn(&mut Builder<'a>, Value, Value) -> Value;
+
+/// appends instructions to a block of a function
+pub struct Builder<'a> {
+    function: &'a mut Function,
+    block: BlockId,
+}
+impl<'a> Builder<'a> {
+    pub fn new(function: &'a mut Function, block: BlockId) -> Builder<'a> {
+        Builder { function, block }
+    }
+    /// appends `inst`.  panics if the types of its operands do not match.
+    fn push(&mut self, inst: Instruction) -> ValueId {
+        if let Err(message) = inst.check_types() {
+            panic!("ICE: {}", message);
+        }
+        self.function.append(self.block, inst)
+    }
+    /// pushes an instruction having a result, and returns the result
+    fn define(&mut self, inst: Instruction) -> Value {
+        let id = self.push(inst);
+        let ty = self.function.definition(id).and_then(Definition::ty);
+        Value::Register(ty.expect("ICE: the instruction has no result"), id)
+    }
+    pub fn block(&self) -> BlockId {
+        self.block
+    }
+    pub fn label(&self) -> Value {
+        Value::Label(self.block)
+    }
+    pub fn ret(&mut self, val: Value) {
+        let ret = Instruction::Ret(val);
+        self.push(ret);
+    }
+    pub fn ret_void(&mut self) {
+        self.push(Instruction::RetVoid);
+    }
+    /// whether the last instruction is a terminator, after which nothing can be appended
+    pub fn is_terminated(&self) -> bool {
+        let insts = self.function.instructions(self.block);
+        let last = insts.last().and_then(|id| self.function.instruction(*id));
+        last.is_some_and(Instruction::is_terminator)
+    }
+    pub fn br(&mut self, dest: Value) {
+        let br = Instruction::Br(dest);
+        self.push(br);
+    }
+    pub fn cond_br(&mut self, cond: Value, then: Value, otherwise: Value) {
+        let br = Instruction::CondBr(cond, then, otherwise);
+        self.push(br);
+    }
+    /// a switch on the integer `val`.  `cases` are pairs of a constant and a label.
+    pub fn switch(&mut self, val: Value, default: Value, cases: Vec<(Value, Value)>) {
+        let switch = Instruction::Switch(val, default, cases);
+        self.push(switch);
+    }
+    pub fn unreachable(&mut self) {
+        self.push(Instruction::Unreachable);
+    }
+    pub fn add(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::Add(lhs, rhs))
+    }
+    pub fn sub(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::Sub(lhs, rhs))
+    }
+    pub fn mul(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::Mul(lhs, rhs))
+    }
+    pub fn sdiv(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::SDiv(lhs, rhs))
+    }
+    pub fn udiv(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::UDiv(lhs, rhs))
+    }
+    pub fn srem(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::SRem(lhs, rhs))
+    }
+    pub fn urem(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::URem(lhs, rhs))
+    }
+    pub fn fadd(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::FAdd(lhs, rhs))
+    }
+    pub fn fsub(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::FSub(lhs, rhs))
+    }
+    pub fn fmul(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::FMul(lhs, rhs))
+    }
+    pub fn fdiv(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::FDiv(lhs, rhs))
+    }
+    pub fn and(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::And(lhs, rhs))
+    }
+    pub fn or(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::Or(lhs, rhs))
+    }
+    pub fn xor(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::Xor(lhs, rhs))
+    }
+    pub fn shl(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::Shl(lhs, rhs))
+    }
+    pub fn lshr(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::LShr(lhs, rhs))
+    }
+    pub fn ashr(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::AShr(lhs, rhs))
+    }
+    pub fn fneg(&mut self, val: Value) -> Value {
+        self.define(Instruction::FNeg(val))
+    }
+    /// compares integers or pointers, into an `i1`
+    pub fn icmp(&mut self, pred: IntPredicate, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::ICmp(pred, lhs, rhs))
+    }
+    pub fn fcmp(&mut self, pred: FloatPredicate, lhs: Value, rhs: Value) -> Value {
+        self.define(Instruction::FCmp(pred, lhs, rhs))
+    }
+    pub fn cast(&mut self, op: CastOp, val: Value, to: Type) -> Value {
+        self.define(Instruction::Cast(op, val, to))
+    }
+    pub fn alloca(&mut self, ty: Type) -> Value {
+        self.define(Instruction::Alloca(ty))
+    }
+    pub fn load(&mut self, ptr: Value, volatile: bool) -> Value {
+        self.define(Instruction::Load(ptr, volatile))
+    }
+    pub fn store(&mut self, val: Value, ptr: Value, volatile: bool) {
+        let store = Instruction::Store(val, ptr, volatile);
+        self.push(store);
+    }
+    /// the address of an element of `ptr`.  `ty` is the pointer type of the result, which is
+    /// not computed here since the members of the structures are not known.
+    pub fn getelementptr(&mut self, ty: Type, ptr: Value, indices: Vec<Value>) -> Value {
+        self.define(Instruction::GetElementPtr(ty, ptr, indices))
+    }
+    /// a call of a function returning a value.  `callee` is a pointer to the function.
+    pub fn call(&mut self, callee: Value, args: Vec<Value>) -> Value {
+        self.define(Instruction::Call(callee, args))
+    }
+    pub fn call_void(&mut self, callee: Value, args: Vec<Value>) {
+        let call = Instruction::Call(callee, args);
+        self.push(call);
+    }
+    /// `incoming` are pairs of a value of type `ty` and the label of the predecessor it comes
+    /// from
+    pub fn phi(&mut self, ty: Type, incoming: Vec<(Value, Value)>) -> Value {
+        self.define(Instruction::Phi(ty, incoming))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let mut func = Function::new("f", Type::I32);
+        let block = func.add_block();
+        let mut bb = func.builder(block);
+        let lhs = Value::Constant(Type::I32, 0);
+        let rhs = Value::Constant(Type::I32, 1);
+        let add = bb.add(lhs, rhs);
+        assert_eq!(add.ty(), Type::I32);
+        bb.ret(add);
+        assert_eq!(bb.label(), Value::Label(block));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut func = Function::new("f", Type::Double);
+        func.add_block();
+        let mut bb = func.last_block();
+        let ptr = bb.alloca(Type::Double);
+        let val = Value::Literal(Type::Double, "0x3FF8000000000000".to_owned());
+        bb.store(val, ptr.clone(), false);
+        let val = bb.load(ptr, false);
+        let val = bb.fneg(val);
+        bb.ret(val);
+        assert_eq!(
+            func.to_string(),
+            "define double @f() #0 {\n\
+             0:\n  \
+             %1 = alloca double\n  \
+             store double 0x3FF8000000000000, double* %1\n  \
+             %2 = load double, double* %1\n  \
+             %3 = fneg double %2\n  \
+             ret double %3\n\
+             }"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ICE: invalid operand types of add: i32 and i64")]
+    fn test_mismatched_operands() {
+        let mut func = Function::new("f", Type::Void);
+        func.add_block();
+        func.last_block()
+            .add(Value::Constant(Type::I32, 0), Value::Constant(Type::I64, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "ICE: store of i8 to i32*")]
+    fn test_mismatched_store() {
+        let mut func = Function::new("f", Type::Void);
+        func.add_block();
+        let ptr = func.last_block().alloca(Type::I32);
+        func.last_block()
+            .store(Value::Constant(Type::I8, 0), ptr, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "ICE: call of i32 (i32)* with (double)")]
+    fn test_mismatched_arguments() {
+        let mut func = Function::new("f", Type::Void);
+        func.add_block();
+        let function = Type::Function(Box::new(Type::I32), vec![Type::I32], false);
+        let callee = Value::Global(function.pointer(), "f".to_owned());
+        func.last_block()
+            .call(callee, vec![Value::Literal(Type::Double, "0.0".to_owned())]);
+    }
+}
@@ -1,26 +1,367 @@
 use std::vec::Vec;
-use super::block::BasicBlock;
+use std::fmt;
+use super::builder::Builder;
+use super::instruction::Instruction;
+use super::types::Type;
+use super::value::Value;
 
-pub struct Function(String, Vec<BasicBlock>);
+/// a parameter or an instruction of a function, by its index in the function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ValueId(usize);
+impl ValueId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// a block of a function, by its index in the function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlockId(usize);
+impl BlockId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// what a `ValueId` refers to
+#[derive(Debug)]
+pub enum Definition {
+    /// a parameter of the type
+    Param(Type),
+
+    /// an instruction of the block, which has a result unless it is a terminator, a store or a
+    /// call of a void function
+    Instruction(BlockId, Instruction),
+}
+impl Definition {
+    /// the type of the parameter or of the result.  None if the instruction has no result.
+    pub fn ty(&self) -> Option<Type> {
+        match self {
+            Definition::Param(ty) => Some(ty.clone()),
+            Definition::Instruction(_, inst) => inst.result_type(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Function {
+    name: String,
+
+    /// the return type
+    ret: Type,
+
+    /// whether it has internal linkage (`static`)
+    internal: bool,
+
+    /// the number of the attribute group of the module (`#N`)
+    attributes: usize,
+
+    /// the parameters and the instructions, indexed by `ValueId`
+    values: Vec<Definition>,
+
+    params: Vec<ValueId>,
+
+    /// the instructions of each block in order, indexed by `BlockId`.  the blocks are printed
+    /// in this order, and the first one is the entry block.
+    blocks: Vec<Vec<ValueId>>,
+}
 impl Function {
-    pub fn new() -> Function {
-        let name = String::from("main");
+    pub fn new(name: &str, ret: Type) -> Function {
+        let name = String::from(name);
         let vec = Vec::new();
-        Function(name, vec)
+        Function {
+            name,
+            ret,
+            internal: false,
+            attributes: 0,
+            values: Vec::new(),
+            params: Vec::new(),
+            blocks: vec,
+        }
+    }
+    pub fn set_internal(&mut self, internal: bool) {
+        self.internal = internal
+    }
+    /// gives the attribute group `group` of the module instead of `#0`
+    pub fn set_attributes(&mut self, group: usize) {
+        self.attributes = group
+    }
+    /// adds a parameter of type `ty`, and returns its value
+    pub fn add_param(&mut self, ty: Type) -> Value {
+        let id = ValueId(self.values.len());
+        self.values.push(Definition::Param(ty.clone()));
+        self.params.push(id);
+        Value::Register(ty, id)
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn ret(&self) -> &Type {
+        &self.ret
+    }
+    pub fn params(&self) -> &[ValueId] {
+        &self.params
+    }
+    /// the function type, which the address of this function points to
+    pub fn ty(&self) -> Type {
+        let params = self
+            .params
+            .iter()
+            .map(|id| {
+                self.values[id.0]
+                    .ty()
+                    .expect("ICE: a parameter has no type")
+            })
+            .collect();
+        Type::Function(Box::new(self.ret.clone()), params, false)
+    }
+    /// the blocks in order
+    pub fn blocks(&self) -> Vec<BlockId> {
+        (0..self.blocks.len()).map(BlockId).collect()
     }
-    pub fn push(&mut self, block: BasicBlock) {
-        self.1.push(block)
+    /// the instructions of `block` in order
+    pub fn instructions(&self, block: BlockId) -> &[ValueId] {
+        &self.blocks[block.0]
+    }
+    /// the parameter or the instruction `id` refers to.  None if it is not of this function.
+    pub fn definition(&self, id: ValueId) -> Option<&Definition> {
+        self.values.get(id.0)
+    }
+    /// the instruction `id` refers to.  None if it is a parameter.
+    pub fn instruction(&self, id: ValueId) -> Option<&Instruction> {
+        match self.definition(id) {
+            Some(Definition::Instruction(_, inst)) => Some(inst),
+            _ => None,
+        }
+    }
+    /// the instructions having `id` as an operand, in order
+    pub fn uses(&self, id: ValueId) -> Vec<ValueId> {
+        let uses = |user: &&ValueId| {
+            let inst = self
+                .instruction(**user)
+                .expect("ICE: a block has a parameter");
+            inst.operands().into_iter().any(|operand| match operand {
+                Value::Register(_, used) => *used == id,
+                _ => false,
+            })
+        };
+        self.blocks.iter().flatten().filter(uses).cloned().collect()
+    }
+    /// appends an empty block, and returns it
+    pub fn add_block(&mut self) -> BlockId {
+        self.blocks.push(Vec::new());
+        BlockId(self.blocks.len() - 1)
+    }
+    /// appends `inst` to `block` as is, and returns its id.  the builders check the types of
+    /// the operands first.
+    pub fn append(&mut self, block: BlockId, inst: Instruction) -> ValueId {
+        let id = ValueId(self.values.len());
+        self.values.push(Definition::Instruction(block, inst));
+        self.blocks[block.0].push(id);
+        id
+    }
+    /// the builder appending to `block`
+    pub fn builder(&mut self, block: BlockId) -> Builder<'_> {
+        Builder::new(self, block)
+    }
+    /// the builder appending to the block added last, which the code is appended to
+    pub fn last_block(&mut self) -> Builder<'_> {
+        let block = match self.blocks.len() {
+            0 => panic!("ICE: the function has no block"),
+            len => BlockId(len - 1),
+        };
+        self.builder(block)
+    }
+    /// numbers the parameters, the labels and the results sequentially from `%0` in order, as
+    /// LLVM requires
+    pub fn number(&self) -> Numbering {
+        let mut numbering = Numbering {
+            values: vec![None; self.values.len()],
+            blocks: Vec::new(),
+        };
+        let mut n = 0;
+        for id in &self.params {
+            numbering.values[id.0] = Some(n);
+            n += 1;
+        }
+        for block in &self.blocks {
+            numbering.blocks.push(n);
+            n += 1;
+            for id in block {
+                if self.values[id.0].ty().is_some() {
+                    numbering.values[id.0] = Some(n);
+                    n += 1;
+                }
+            }
+        }
+        numbering
+    }
+}
+
+/// the numbers of the values and the blocks of a function, given by `Function::number`
+pub struct Numbering {
+    values: Vec<Option<usize>>,
+    blocks: Vec<usize>,
+}
+impl Numbering {
+    pub fn value(&self, id: ValueId) -> usize {
+        match self.values.get(id.0) {
+            Some(Some(n)) => *n,
+            _ => panic!("ICE: the value {:?} is not numbered", id),
+        }
+    }
+    pub fn block(&self, id: BlockId) -> usize {
+        self.blocks[id.0]
+    }
+}
+
+/// a value or an instruction printed with the numbers of its function
+pub struct Numbered<'a, T: 'a>(pub &'a T, pub &'a Numbering);
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let numbering = self.number();
+        let linkage = if self.internal { "internal " } else { "" };
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|id| {
+                let ty = self.values[id.0]
+                    .ty()
+                    .expect("ICE: a parameter has no type");
+                format!("{} %{}", ty, numbering.value(*id))
+            })
+            .collect();
+        writeln!(
+            f,
+            "define {}{} @{}({}) #{} {{",
+            linkage,
+            self.ret,
+            self.name,
+            params.join(", "),
+            self.attributes
+        )?;
+        for (idx, block) in self.blocks.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}:", numbering.blocks[idx])?;
+            for id in block {
+                let inst = Numbered(
+                    self.instruction(*id).expect("ICE: no instruction"),
+                    &numbering,
+                );
+                match numbering.values[id.0] {
+                    Some(n) => writeln!(f, "  %{} = {}", n, inst)?,
+                    None => writeln!(f, "  {}", inst)?,
+                }
+            }
+        }
+        write!(f, "}}")
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::instruction::IntPredicate;
     use super::*;
 
     #[test]
     fn test_function() {
-        let mut func = Function::new();
-        let block = BasicBlock::new();
-        func.push(block);
+        let mut func = Function::new("main", Type::I32);
+        let block = func.add_block();
+        assert_eq!(func.blocks(), vec![block]);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut func = Function::new("f", Type::I32);
+        func.set_internal(true);
+        let x = func.add_param(Type::I32);
+        func.add_block();
+        let y = func.last_block().add(x, Value::Constant(Type::I32, 1));
+        func.last_block().ret(y);
+        // the code after `ret` is in a new block, which takes a number
+        func.add_block();
+        let zero = || Value::Constant(Type::I32, 0);
+        let z = func.last_block().add(zero(), zero());
+        func.last_block().ret(z);
+        assert_eq!(
+            func.to_string(),
+            "define internal i32 @f(i32 %0) #0 {\n\
+             1:\n  \
+             %2 = add i32 %0, 1\n  \
+             ret i32 %2\n\
+             \n\
+             3:\n  \
+             %4 = add i32 0, 0\n  \
+             ret i32 %4\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn test_branches() {
+        // int f(int x) { return x ? 1 : 2; }
+        let mut func = Function::new("f", Type::I32);
+        let x = func.add_param(Type::I32);
+        let (entry, then, otherwise, end) = (
+            func.add_block(),
+            func.add_block(),
+            func.add_block(),
+            func.add_block(),
+        );
+        let cond = func
+            .builder(entry)
+            .icmp(IntPredicate::Ne, x, Value::Constant(Type::I32, 0));
+        func.builder(entry)
+            .cond_br(cond, Value::Label(then), Value::Label(otherwise));
+        func.builder(then).br(Value::Label(end));
+        func.builder(otherwise).br(Value::Label(end));
+        let incoming = vec![
+            (Value::Constant(Type::I32, 1), Value::Label(then)),
+            (Value::Constant(Type::I32, 2), Value::Label(otherwise)),
+        ];
+        let result = func.builder(end).phi(Type::I32, incoming);
+        func.builder(end).ret(result);
+        assert_eq!(
+            func.to_string(),
+            "define i32 @f(i32 %0) #0 {\n\
+             1:\n  \
+             %2 = icmp ne i32 %0, 0\n  \
+             br i1 %2, label %3, label %4\n\
+             \n\
+             3:\n  \
+             br label %5\n\
+             \n\
+             4:\n  \
+             br label %5\n\
+             \n\
+             5:\n  \
+             %6 = phi i32 [ 1, %3 ], [ 2, %4 ]\n  \
+             ret i32 %6\n\
+             }"
+        );
+        let empty = func.add_block();
+        assert!(func.builder(entry).is_terminated() && !func.builder(empty).is_terminated());
+    }
+
+    #[test]
+    fn test_uses() {
+        let mut func = Function::new("f", Type::I32);
+        let x = func.add_param(Type::I32);
+        let id = match x {
+            Value::Register(_, id) => id,
+            _ => unreachable!(),
+        };
+        func.add_block();
+        let y = func.last_block().add(x.clone(), x.clone());
+        let z = func.last_block().mul(y.clone(), x);
+        func.last_block().ret(z);
+        let ids: Vec<ValueId> = func.instructions(func.blocks()[0]).to_vec();
+        assert_eq!(func.uses(id), vec![ids[0], ids[1]]);
+        assert_eq!(func.uses(ids[0]), vec![ids[1]]);
+        assert_eq!(func.uses(ids[1]), vec![ids[2]]);
+        assert!(func.uses(ids[2]).is_empty());
     }
 }
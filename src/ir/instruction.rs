@@ -1,25 +1,576 @@
-use super::value::Register;
+use std::fmt;
+use std::iter;
+
+use super::function::{Numbered, Numbering};
+use super::types::{is_valid_cast, Type};
 use super::value::Value;
 
+/// the predicate of `icmp`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntPredicate {
+    Eq,
+    Ne,
+    Ugt,
+    Uge,
+    Ult,
+    Ule,
+    Sgt,
+    Sge,
+    Slt,
+    Sle,
+}
+
+/// the predicate of `fcmp`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatPredicate {
+    Oeq,
+    One,
+    Ogt,
+    Oge,
+    Olt,
+    Ole,
+    Une,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastOp {
+    Trunc,
+    ZExt,
+    SExt,
+    FPTrunc,
+    FPExt,
+    FPToUI,
+    FPToSI,
+    UIToFP,
+    SIToFP,
+    PtrToInt,
+    IntToPtr,
+    BitCast,
+}
+
+/// an instruction.  the types of the operands are those of the values.  the result is
+/// referred to by the `ValueId` of the instruction in its function.
+#[derive(Debug)]
 pub enum Instruction {
     Ret(Value),
-    Add(Register, Value, Value),
+    RetVoid,
+
+    /// `br label %dest`
+    Br(Value),
+
+    /// `br i1 %cond, label %then, label %else`
+    CondBr(Value, Value, Value),
+
+    /// a switch on a value to the default label, or to the label of the matching case
+    Switch(Value, Value, Vec<(Value, Value)>),
+    Unreachable,
+    Add(Value, Value),
+    Sub(Value, Value),
+    Mul(Value, Value),
+    SDiv(Value, Value),
+    UDiv(Value, Value),
+    SRem(Value, Value),
+    URem(Value, Value),
+    FAdd(Value, Value),
+    FSub(Value, Value),
+    FMul(Value, Value),
+    FDiv(Value, Value),
+    And(Value, Value),
+    Or(Value, Value),
+    Xor(Value, Value),
+    Shl(Value, Value),
+    LShr(Value, Value),
+    AShr(Value, Value),
+    FNeg(Value),
+    ICmp(IntPredicate, Value, Value),
+    FCmp(FloatPredicate, Value, Value),
+
+    /// a conversion of the value to the type
+    Cast(CastOp, Value, Type),
+    Alloca(Type),
+
+    /// the last field tells whether the access is volatile
+    Load(Value, bool),
+    Store(Value, Value, bool),
+
+    /// `getelementptr inbounds` from the pointer with the indices.  the type is the pointer
+    /// type of the result, which is given since the members of the structures are not known.
+    GetElementPtr(Type, Value, Vec<Value>),
+
+    /// a call of the callee, which is a pointer to a function.  a void function has no result.
+    Call(Value, Vec<Value>),
+
+    /// the value of the type coming from each of the predecessors, as (value, label)
+    Phi(Type, Vec<(Value, Value)>),
+}
+
+impl Instruction {
+    /// whether this instruction ends a block
+    pub fn is_terminator(&self) -> bool {
+        matches!(
+            self,
+            Instruction::Ret(..)
+                | Instruction::RetVoid
+                | Instruction::Br(..)
+                | Instruction::CondBr(..)
+                | Instruction::Switch(..)
+                | Instruction::Unreachable
+        )
+    }
+
+    /// the operands, including the labels of the destinations
+    pub fn operands(&self) -> Vec<&Value> {
+        if let Some((_, lhs, rhs)) = self.binary() {
+            return vec![lhs, rhs];
+        }
+        match self {
+            Instruction::Ret(value) | Instruction::Br(value) => vec![value],
+            Instruction::RetVoid | Instruction::Unreachable | Instruction::Alloca(..) => vec![],
+            Instruction::CondBr(cond, then, otherwise) => vec![cond, then, otherwise],
+            Instruction::Switch(value, default, cases) => {
+                let cases = cases.iter().flat_map(|(case, dest)| vec![case, dest]);
+                vec![value, default].into_iter().chain(cases).collect()
+            }
+            Instruction::FNeg(value)
+            | Instruction::Cast(_, value, _)
+            | Instruction::Load(value, _) => vec![value],
+            Instruction::ICmp(_, lhs, rhs)
+            | Instruction::FCmp(_, lhs, rhs)
+            | Instruction::Store(lhs, rhs, _) => vec![lhs, rhs],
+            Instruction::GetElementPtr(_, value, args) | Instruction::Call(value, args) => {
+                iter::once(value).chain(args).collect()
+            }
+            Instruction::Phi(_, incoming) => incoming
+                .iter()
+                .flat_map(|(value, label)| vec![value, label])
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// the type of the result.  the operands must have been checked by `check_types`.
+    pub fn result_type(&self) -> Option<Type> {
+        if let Some((_, lhs, _)) = self.binary() {
+            return Some(lhs.ty());
+        }
+        match self {
+            Instruction::FNeg(value) => Some(value.ty()),
+            Instruction::ICmp(..) | Instruction::FCmp(..) => Some(Type::I1),
+            Instruction::Cast(_, _, ty)
+            | Instruction::GetElementPtr(ty, ..)
+            | Instruction::Phi(ty, _) => Some(ty.clone()),
+            Instruction::Alloca(ty) => Some(ty.clone().pointer()),
+            Instruction::Load(pointer, _) => Some(pointee(pointer)),
+            Instruction::Call(callee, _) => match pointee(callee) {
+                Type::Function(ref ret, ..) if **ret == Type::Void => None,
+                Type::Function(ret, ..) => Some(*ret),
+                ty => panic!("ICE: call of {}", ty),
+            },
+            _ => None,
+        }
+    }
+
+    /// checks the types of the operands against each other, and returns the description of the
+    /// mismatch if any.  the type of a returned value is checked by `verify`, which knows the
+    /// function.
+    pub fn check_types(&self) -> Result<(), String> {
+        if let Some((opcode, lhs, rhs)) = self.binary() {
+            let is = if opcode.starts_with('f') {
+                Type::is_float
+            } else {
+                Type::is_int
+            };
+            return check_operands(opcode, lhs, rhs, is);
+        }
+        match self {
+            Instruction::Ret(_) | Instruction::RetVoid | Instruction::Unreachable => Ok(()),
+            Instruction::Br(dest) => check_label("br", dest),
+            Instruction::CondBr(cond, then, otherwise) => {
+                if cond.ty() != Type::I1 {
+                    return Err(format!("the condition of br is not i1: {}", cond.ty()));
+                }
+                check_label("br", then)?;
+                check_label("br", otherwise)
+            }
+            Instruction::Switch(value, default, cases) => {
+                check_label("switch", default)?;
+                for (case, dest) in cases {
+                    check_operands("switch", value, case, Type::is_int)?;
+                    check_label("switch", dest)?;
+                }
+                Ok(())
+            }
+            Instruction::FNeg(value) => check_operands("fneg", value, value, Type::is_float),
+            Instruction::ICmp(_, lhs, rhs) => {
+                check_operands("icmp", lhs, rhs, |ty| ty.is_int() || ty.is_pointer())
+            }
+            Instruction::FCmp(_, lhs, rhs) => check_operands("fcmp", lhs, rhs, Type::is_float),
+            Instruction::Cast(op, value, to) => {
+                if !is_valid_cast(*op, &value.ty(), to) {
+                    return Err(format!("invalid {} from {} to {}", op, value.ty(), to));
+                }
+                Ok(())
+            }
+            Instruction::Alloca(ty) => {
+                if !ty.is_first_class() {
+                    return Err(format!("alloca of {}", ty));
+                }
+                Ok(())
+            }
+            Instruction::Load(pointer, _) => match pointer.ty().pointee() {
+                Some(ty) if ty.is_first_class() => Ok(()),
+                _ => Err(format!("load from {}", pointer.ty())),
+            },
+            Instruction::Store(value, pointer, _) => {
+                if pointer.ty().pointee() != Some(&value.ty()) {
+                    return Err(format!("store of {} to {}", value.ty(), pointer.ty()));
+                }
+                Ok(())
+            }
+            Instruction::GetElementPtr(ty, pointer, indices) => {
+                let valid = pointer.ty().is_pointer()
+                    && ty.is_pointer()
+                    && indices.iter().all(|index| index.ty().is_int());
+                if !valid {
+                    let message = format!("invalid getelementptr from {} to {}", pointer.ty(), ty);
+                    return Err(message);
+                }
+                Ok(())
+            }
+            Instruction::Call(callee, args) => check_call(callee, args),
+            Instruction::Phi(ty, incoming) => {
+                for (value, label) in incoming {
+                    if value.ty() != *ty {
+                        let message =
+                            format!("phi of {} has an incoming value of {}", ty, value.ty());
+                        return Err(message);
+                    }
+                    check_label("phi", label)?;
+                }
+                Ok(())
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// the opcode and the operands of a binary operator
+    fn binary(&self) -> Option<(&'static str, &Value, &Value)> {
+        let (opcode, lhs, rhs) = match self {
+            Instruction::Add(lhs, rhs) => ("add", lhs, rhs),
+            Instruction::Sub(lhs, rhs) => ("sub", lhs, rhs),
+            Instruction::Mul(lhs, rhs) => ("mul", lhs, rhs),
+            Instruction::SDiv(lhs, rhs) => ("sdiv", lhs, rhs),
+            Instruction::UDiv(lhs, rhs) => ("udiv", lhs, rhs),
+            Instruction::SRem(lhs, rhs) => ("srem", lhs, rhs),
+            Instruction::URem(lhs, rhs) => ("urem", lhs, rhs),
+            Instruction::FAdd(lhs, rhs) => ("fadd", lhs, rhs),
+            Instruction::FSub(lhs, rhs) => ("fsub", lhs, rhs),
+            Instruction::FMul(lhs, rhs) => ("fmul", lhs, rhs),
+            Instruction::FDiv(lhs, rhs) => ("fdiv", lhs, rhs),
+            Instruction::And(lhs, rhs) => ("and", lhs, rhs),
+            Instruction::Or(lhs, rhs) => ("or", lhs, rhs),
+            Instruction::Xor(lhs, rhs) => ("xor", lhs, rhs),
+            Instruction::Shl(lhs, rhs) => ("shl", lhs, rhs),
+            Instruction::LShr(lhs, rhs) => ("lshr", lhs, rhs),
+            Instruction::AShr(lhs, rhs) => ("ashr", lhs, rhs),
+            _ => return None,
+        };
+        Some((opcode, lhs, rhs))
+    }
+}
+
+impl fmt::Display for IntPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            IntPredicate::Eq => "eq",
+            IntPredicate::Ne => "ne",
+            IntPredicate::Ugt => "ugt",
+            IntPredicate::Uge => "uge",
+            IntPredicate::Ult => "ult",
+            IntPredicate::Ule => "ule",
+            IntPredicate::Sgt => "sgt",
+            IntPredicate::Sge => "sge",
+            IntPredicate::Slt => "slt",
+            IntPredicate::Sle => "sle",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for FloatPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FloatPredicate::Oeq => "oeq",
+            FloatPredicate::One => "one",
+            FloatPredicate::Ogt => "ogt",
+            FloatPredicate::Oge => "oge",
+            FloatPredicate::Olt => "olt",
+            FloatPredicate::Ole => "ole",
+            FloatPredicate::Une => "une",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for CastOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CastOp::Trunc => "trunc",
+            CastOp::ZExt => "zext",
+            CastOp::SExt => "sext",
+            CastOp::FPTrunc => "fptrunc",
+            CastOp::FPExt => "fpext",
+            CastOp::FPToUI => "fptoui",
+            CastOp::FPToSI => "fptosi",
+            CastOp::UIToFP => "uitofp",
+            CastOp::SIToFP => "sitofp",
+            CastOp::PtrToInt => "ptrtoint",
+            CastOp::IntToPtr => "inttoptr",
+            CastOp::BitCast => "bitcast",
+        };
+        f.write_str(name)
+    }
+}
+
+fn volatile(is_volatile: bool) -> &'static str {
+    if is_volatile {
+        "volatile "
+    } else {
+        ""
+    }
+}
+
+/// checks that the operands of `opcode` are of the same type, which satisfies `is`
+fn check_operands(
+    opcode: &str,
+    lhs: &Value,
+    rhs: &Value,
+    is: fn(&Type) -> bool,
+) -> Result<(), String> {
+    let (lhs, rhs) = (lhs.ty(), rhs.ty());
+    if lhs != rhs || !is(&lhs) {
+        return Err(format!(
+            "invalid operand types of {}: {} and {}",
+            opcode, lhs, rhs
+        ));
+    }
+    Ok(())
+}
+
+fn check_label(opcode: &str, dest: &Value) -> Result<(), String> {
+    if dest.ty() != Type::Label {
+        return Err(format!(
+            "the destination of {} is not a label: {}",
+            opcode,
+            dest.ty()
+        ));
+    }
+    Ok(())
+}
+
+/// checks the arguments against the type of the callee
+fn check_call(callee: &Value, args: &[Value]) -> Result<(), String> {
+    let (params, variadic) = match callee.ty().pointee() {
+        Some(Type::Function(_, params, variadic)) => (params.clone(), *variadic),
+        _ => return Err(format!("call of {}", callee.ty())),
+    };
+    let count = if variadic {
+        args.len() >= params.len()
+    } else {
+        args.len() == params.len()
+    };
+    if !count
+        || params
+            .iter()
+            .zip(args)
+            .any(|(param, arg)| *param != arg.ty())
+    {
+        let args: Vec<String> = args.iter().map(|arg| arg.ty().to_string()).collect();
+        return Err(format!(
+            "call of {} with ({})",
+            callee.ty(),
+            args.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// a value printed with its type, as `i32 %1`
+struct Typed<'a>(&'a Value, &'a Numbering);
+
+impl<'a> fmt::Display for Typed<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.0.ty(), Numbered(self.0, self.1))
+    }
+}
+
+/// the type pointed to by `pointer`
+fn pointee(pointer: &Value) -> Type {
+    match pointer.ty() {
+        Type::Pointer(pointee) => *pointee,
+        ty => panic!("ICE: {} is not a pointer", ty),
+    }
+}
+
+/// the instruction without its result, as `add i32 %1, 2`
+impl<'a> fmt::Display for Numbered<'a, Instruction> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let numbering = self.1;
+        let typed = |value| Typed(value, numbering);
+        let value = |value| Numbered(value, numbering);
+        if let Some((opcode, lhs, rhs)) = self.0.binary() {
+            return write!(f, "{} {}, {}", opcode, typed(lhs), value(rhs));
+        }
+        match self.0 {
+            Instruction::Ret(ret) => write!(f, "ret {}", typed(ret)),
+            Instruction::RetVoid => write!(f, "ret void"),
+            Instruction::Br(dest) => write!(f, "br {}", typed(dest)),
+            Instruction::CondBr(cond, then, otherwise) => write!(
+                f,
+                "br {}, {}, {}",
+                typed(cond),
+                typed(then),
+                typed(otherwise)
+            ),
+            Instruction::Switch(operand, default, cases) => {
+                write!(f, "switch {}, {} [", typed(operand), typed(default))?;
+                for (case, dest) in cases {
+                    write!(f, " {}, {}", typed(case), typed(dest))?;
+                }
+                write!(f, " ]")
+            }
+            Instruction::Unreachable => write!(f, "unreachable"),
+            Instruction::FNeg(operand) => write!(f, "fneg {}", typed(operand)),
+            Instruction::ICmp(pred, lhs, rhs) => {
+                write!(f, "icmp {} {}, {}", pred, typed(lhs), value(rhs))
+            }
+            Instruction::FCmp(pred, lhs, rhs) => {
+                write!(f, "fcmp {} {}, {}", pred, typed(lhs), value(rhs))
+            }
+            Instruction::Cast(op, operand, to) => write!(f, "{} {} to {}", op, typed(operand), to),
+            Instruction::Alloca(ty) => write!(f, "alloca {}", ty),
+            Instruction::Load(pointer, is_volatile) => write!(
+                f,
+                "load {}{}, {}",
+                volatile(*is_volatile),
+                pointee(pointer),
+                typed(pointer)
+            ),
+            Instruction::Store(stored, pointer, is_volatile) => write!(
+                f,
+                "store {}{}, {}",
+                volatile(*is_volatile),
+                typed(stored),
+                typed(pointer)
+            ),
+            Instruction::GetElementPtr(_, pointer, indices) => {
+                write!(
+                    f,
+                    "getelementptr inbounds {}, {}",
+                    pointee(pointer),
+                    typed(pointer)
+                )?;
+                for index in indices {
+                    write!(f, ", {}", typed(index))?;
+                }
+                Ok(())
+            }
+            Instruction::Call(callee, args) => {
+                write!(f, "call {} {}(", pointee(callee), value(callee))?;
+                for (idx, arg) in args.iter().enumerate() {
+                    let separator = if idx == 0 { "" } else { ", " };
+                    write!(f, "{}{}", separator, typed(arg))?;
+                }
+                write!(f, ")")
+            }
+            Instruction::Phi(ty, incoming) => {
+                write!(f, "phi {} ", ty)?;
+                for (idx, (incoming, label)) in incoming.iter().enumerate() {
+                    let separator = if idx == 0 { "" } else { ", " };
+                    write!(f, "{}[ {}, {} ]", separator, value(incoming), value(label))?;
+                }
+                Ok(())
+            }
+            _ => unreachable!(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::function::Function;
     use super::*;
 
     #[test]
     fn test_instruction() {
-        let reg = Register::new();
-        let reg1 = Register::new();
-        let reg2 = Register::new();
-        let reg3 = Register::new();
-        let val1 = Value::Register(reg1.make_ref());
-        let val2 = Value::Register(reg2.make_ref());
-        let val3 = Value::Register(reg3.make_ref());
-        let _add = Instruction::Add(reg, val1, val2);
-        let _ret = Instruction::Ret(val3);
+        let mut func = Function::new("f", Type::I32);
+        let val1 = func.add_param(Type::I32);
+        let val2 = func.add_param(Type::I32);
+        let add = Instruction::Add(val1, val2);
+        assert_eq!(add.result_type(), Some(Type::I32));
+        assert!(!add.is_terminator());
+        let ret = Instruction::Ret(Value::Constant(Type::I32, 0));
+        assert_eq!(ret.result_type(), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut func = Function::new("f", Type::Void);
+        func.add_param(Type::I32);
+        let pointer = func.add_param(Type::I32.pointer());
+        let numbering = func.number();
+        let load = Instruction::Load(pointer, true);
+        assert_eq!(
+            Numbered(&load, &numbering).to_string(),
+            "load volatile i32, i32* %1"
+        );
+        let function = Type::Function(
+            Box::new(Type::Void),
+            vec![Type::I32, Type::I8.pointer()],
+            false,
+        );
+        let call = Instruction::Call(
+            Value::Global(function.pointer(), "f".to_owned()),
+            vec![
+                Value::Constant(Type::I32, 1),
+                Value::Literal(Type::I8.pointer(), "null".to_owned()),
+            ],
+        );
+        assert_eq!(call.result_type(), None);
+        assert_eq!(
+            Numbered(&call, &numbering).to_string(),
+            "call void (i32, i8*) @f(i32 1, i8* null)"
+        );
+    }
+
+    #[test]
+    fn test_terminators() {
+        let mut func = Function::new("f", Type::Void);
+        func.add_block();
+        let (then, otherwise) = (func.add_block(), func.add_block());
+        let numbering = func.number();
+        let display = |inst: &Instruction| Numbered(inst, &numbering).to_string();
+        let cond = Value::Literal(Type::I1, "true".to_owned());
+        let br = Instruction::CondBr(cond, Value::Label(then), Value::Label(otherwise));
+        assert!(br.is_terminator());
+        assert_eq!(display(&br), "br i1 true, label %1, label %2");
+        let switch = Instruction::Switch(
+            Value::Constant(Type::I32, 0),
+            Value::Label(otherwise),
+            vec![(Value::Constant(Type::I32, 1), Value::Label(then))],
+        );
+        assert_eq!(
+            display(&switch),
+            "switch i32 0, label %2 [ i32 1, label %1 ]"
+        );
+        let phi = Instruction::Phi(
+            Type::I32,
+            vec![
+                (Value::Constant(Type::I32, 1), Value::Label(then)),
+                (Value::Constant(Type::I32, 2), Value::Label(otherwise)),
+            ],
+        );
+        assert!(!phi.is_terminator());
+        assert_eq!(display(&phi), "phi i32 [ 1, %1 ], [ 2, %2 ]");
     }
 }
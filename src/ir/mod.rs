@@ -1,28 +1,42 @@
-#![allow(dead_code)]
+//! the intermediate representation which the syntax tree is lowered into, and which is printed
+//! as LLVM IR.  a function owns its parameters, instructions and blocks, and the operands refer
+//! to them by `ValueId` and `BlockId`.
 
-mod block;
+mod builder;
 mod function;
 mod instruction;
+mod module;
+mod types;
 mod value;
+mod verify;
+
+pub use self::builder::{BinaryBuilder, Builder};
+pub use self::function::{BlockId, Definition, Function, Numbered, Numbering, ValueId};
+pub use self::instruction::{CastOp, FloatPredicate, Instruction, IntPredicate};
+pub use self::module::{first_element, llvm_string, Global, Module};
+pub use self::types::{is_valid_cast, Type};
+pub use self::value::Value;
+pub use self::verify::{verify, verify_module};
 
 #[cfg(test)]
 mod tests {
-    use super::block::*;
     use super::function::*;
+    use super::types::*;
     use super::value::*;
 
     #[test]
     fn test() {
-        let mut f = Function::new();
-        let mut b = BasicBlock::new();
-        let v0 = Value::Constant(0);
-        let v1 = Value::Constant(1);
-        let v2 = Value::Constant(2);
-        let v3 = Value::Constant(3);
+        let mut f = Function::new("main", Type::I32);
+        f.add_block();
+        let mut b = f.last_block();
+        let v0 = Value::Constant(Type::I32, 0);
+        let v1 = Value::Constant(Type::I32, 1);
+        let v2 = Value::Constant(Type::I32, 2);
+        let v3 = Value::Constant(Type::I32, 3);
         let a0 = b.add(v0, v1);
         let a1 = b.add(v2, v3);
         let a2 = b.add(a0, a1);
         b.ret(a2);
-        f.push(b);
+        assert!(f.to_string().contains("%3 = add i32 %1, %2"));
     }
 }
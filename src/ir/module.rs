@@ -0,0 +1,333 @@
+use std::fmt;
+
+use super::function::Function;
+use super::types::Type;
+use super::value::Value;
+
+/// the attributes of the functions which do not ask for others, as clang gives them at `-O0`
+const DEFAULT_ATTRIBUTES: &str = "noinline nounwind optnone";
+
+/// a global variable
+#[derive(Debug, Clone)]
+pub struct Global {
+    pub name: String,
+    pub ty: Type,
+
+    /// the initializer as an LLVM constant.  None for a variable defined in another module.
+    pub init: Option<String>,
+
+    /// whether it has internal linkage (`static`)
+    pub internal: bool,
+
+    pub constant: bool,
+}
+
+/// a translation unit, printed as a complete `.ll` file
+#[derive(Debug)]
+pub struct Module {
+    triple: Option<String>,
+    datalayout: Option<String>,
+
+    /// the named structure types in the order of definition, with None for an opaque one
+    structs: Vec<(String, Option<Vec<Type>>)>,
+
+    globals: Vec<Global>,
+
+    /// contents of string literals, without the terminating null character.  the index is
+    /// the suffix of the global name (`@.str.N`).
+    strings: Vec<Vec<u8>>,
+
+    functions: Vec<Function>,
+
+    /// the functions defined elsewhere, with their function types
+    declarations: Vec<(String, Type)>,
+
+    /// the attribute groups.  the index is the number of the group (`#N`).
+    attributes: Vec<String>,
+}
+impl Module {
+    /// an empty module, which has the attribute group `#0` of the functions
+    pub fn new() -> Module {
+        Module {
+            triple: None,
+            datalayout: None,
+            structs: Vec::new(),
+            globals: Vec::new(),
+            strings: Vec::new(),
+            functions: Vec::new(),
+            declarations: Vec::new(),
+            attributes: vec![DEFAULT_ATTRIBUTES.to_owned()],
+        }
+    }
+    pub fn set_target(&mut self, triple: &str, datalayout: &str) {
+        self.triple = Some(triple.to_owned());
+        self.datalayout = Some(datalayout.to_owned());
+    }
+    /// defines the structure type `%struct.NAME` with the types of the members, or as an opaque
+    /// type if `members` is None
+    pub fn define_struct(&mut self, name: &str, members: Option<Vec<Type>>) {
+        self.structs.push((name.to_owned(), members))
+    }
+    pub fn add_global(&mut self, global: Global) {
+        self.globals.push(global)
+    }
+    /// adds a string literal and returns its global, which points to the array of the
+    /// characters
+    pub fn add_string(&mut self, bytes: Vec<u8>) -> Value {
+        let ty = Type::Array(Box::new(Type::I8), bytes.len() + 1);
+        let name = format!(".str.{}", self.strings.len());
+        self.strings.push(bytes);
+        Value::Global(ty.pointer(), name)
+    }
+    pub fn add_function(&mut self, function: Function) {
+        self.functions.push(function)
+    }
+    pub fn functions(&self) -> &[Function] {
+        &self.functions
+    }
+    /// the names of the global variables, the string literals, the functions and the
+    /// declarations, with the pointer types of their addresses
+    pub fn symbols(&self) -> Vec<(String, Type)> {
+        let globals = self
+            .globals
+            .iter()
+            .map(|global| (global.name.clone(), global.ty.clone().pointer()));
+        let strings = self.strings.iter().enumerate().map(|(idx, bytes)| {
+            let ty = Type::Array(Box::new(Type::I8), bytes.len() + 1);
+            (format!(".str.{}", idx), ty.pointer())
+        });
+        let functions = self
+            .functions
+            .iter()
+            .map(|function| (function.name().to_owned(), function.ty().pointer()));
+        let declarations = self
+            .declarations
+            .iter()
+            .map(|(name, ty)| (name.clone(), ty.clone().pointer()));
+        globals
+            .chain(strings)
+            .chain(functions)
+            .chain(declarations)
+            .collect()
+    }
+    /// declares the function `name` of the function type `ty`, which is defined elsewhere.  a
+    /// function declared before is not declared again.
+    pub fn declare(&mut self, name: &str, ty: Type) {
+        if self
+            .declarations
+            .iter()
+            .all(|(declared, _)| declared != name)
+        {
+            self.declarations.push((name.to_owned(), ty))
+        }
+    }
+    /// adds an attribute group, such as `"nounwind"`, and returns its number
+    pub fn add_attributes(&mut self, attributes: &str) -> usize {
+        self.attributes.push(attributes.to_owned());
+        self.attributes.len() - 1
+    }
+}
+impl Default for Module {
+    fn default() -> Module {
+        Module::new()
+    }
+}
+
+/// formats `bytes` as an LLVM string constant (`c"..."`)
+pub fn llvm_string(bytes: &[u8]) -> String {
+    let escaped: String = bytes
+        .iter()
+        .map(|&byte| match byte {
+            b'"' | b'\\' => format!("\\{:02X}", byte),
+            0x20..=0x7e => (byte as char).to_string(),
+            _ => format!("\\{:02X}", byte),
+        })
+        .collect();
+    format!("c\"{}\"", escaped)
+}
+
+/// the constant address of the first element of the array which `array`, a global, points to
+pub fn first_element(array: &Value) -> Value {
+    let (ty, name) = match array {
+        Value::Global(ty, name) => (ty, name),
+        _ => panic!("ICE: {:?} is not a global", array),
+    };
+    match ty.pointee() {
+        Some(Type::Array(element, _)) => {
+            let address = format!(
+                "getelementptr inbounds ({}, {} @{}, i64 0, i64 0)",
+                ty.pointee().unwrap(),
+                ty,
+                name
+            );
+            Value::Literal(element.clone().pointer(), address)
+        }
+        _ => panic!("ICE: {} does not point to an array", ty),
+    }
+}
+
+impl fmt::Display for Global {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = if self.constant { "constant" } else { "global" };
+        match self.init {
+            Some(ref init) => {
+                let linkage = if self.internal { "internal " } else { "" };
+                write!(
+                    f,
+                    "@{} = {}{} {} {}",
+                    self.name, linkage, kind, self.ty, init
+                )
+            }
+            None => write!(f, "@{} = external {} {}", self.name, kind, self.ty),
+        }
+    }
+}
+
+/// the sections of the module are separated by blank lines, and the empty ones are omitted
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sections: Vec<String> = Vec::new();
+
+        let mut target = String::new();
+        if let Some(ref datalayout) = self.datalayout {
+            target += &format!("target datalayout = \"{}\"\n", datalayout);
+        }
+        if let Some(ref triple) = self.triple {
+            target += &format!("target triple = \"{}\"\n", triple);
+        }
+        sections.push(target);
+
+        let structs = self.structs.iter().map(|(name, members)| match members {
+            Some(members) => {
+                let members: Vec<String> = members.iter().map(Type::to_string).collect();
+                format!("%struct.{} = type {{ {} }}\n", name, members.join(", "))
+            }
+            None => format!("%struct.{} = type opaque\n", name),
+        });
+        sections.push(structs.collect());
+
+        let globals = self.globals.iter().map(|global| format!("{}\n", global));
+        let strings = self.strings.iter().enumerate().map(|(idx, bytes)| {
+            let mut bytes = bytes.clone();
+            bytes.push(0);
+            format!(
+                "@.str.{} = private unnamed_addr constant [{} x i8] {}\n",
+                idx,
+                bytes.len(),
+                llvm_string(&bytes)
+            )
+        });
+        sections.push(globals.chain(strings).collect());
+
+        sections.extend(
+            self.functions
+                .iter()
+                .map(|function| format!("{}\n", function)),
+        );
+
+        let declarations = self.declarations.iter().map(|(name, ty)| match ty {
+            Type::Function(ret, params, variadic) => {
+                let mut params: Vec<String> = params.iter().map(Type::to_string).collect();
+                if *variadic {
+                    params.push("...".to_owned());
+                }
+                format!("declare {} @{}({})\n", ret, name, params.join(", "))
+            }
+            _ => panic!("ICE: the declaration of {} is not a function: {}", name, ty),
+        });
+        sections.push(declarations.collect());
+
+        let attributes = self.attributes.iter().enumerate();
+        let attributes =
+            attributes.map(|(idx, group)| format!("attributes #{} = {{ {} }}\n", idx, group));
+        sections.push(attributes.collect());
+
+        let sections: Vec<String> = sections.into_iter().filter(|s| !s.is_empty()).collect();
+        write!(f, "{}", sections.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut module = Module::new();
+        module.set_target("x86_64-pc-linux-gnu", "e-m:e-i64:64-n8:16:32:64-S128");
+        module.define_struct("s", Some(vec![Type::I32, Type::Double]));
+        module.define_struct("t", None);
+        module.add_global(Global {
+            name: "g".to_owned(),
+            ty: Type::I32,
+            init: Some("1".to_owned()),
+            internal: true,
+            constant: false,
+        });
+        module.add_global(Global {
+            name: "e".to_owned(),
+            ty: Type::I8,
+            init: None,
+            internal: false,
+            constant: true,
+        });
+        let string = module.add_string(b"hi".to_vec());
+        let puts = Type::Function(Box::new(Type::I32), vec![Type::I8.pointer()], false);
+        module.declare("puts", puts.clone());
+        let callee = Value::Global(puts.pointer(), "puts".to_owned());
+
+        let mut main = Function::new("main", Type::I32);
+        main.add_block();
+        let zero = || Value::Constant(Type::I64, 0);
+        let s = main
+            .last_block()
+            .getelementptr(Type::I8.pointer(), string, vec![zero(), zero()]);
+        let result = main.last_block().call(callee, vec![s]);
+        main.last_block().ret(result);
+        module.add_function(main);
+
+        assert_eq!(
+            module.to_string(),
+            "target datalayout = \"e-m:e-i64:64-n8:16:32:64-S128\"\n\
+             target triple = \"x86_64-pc-linux-gnu\"\n\
+             \n\
+             %struct.s = type { i32, double }\n\
+             %struct.t = type opaque\n\
+             \n\
+             @g = internal global i32 1\n\
+             @e = external constant i8\n\
+             @.str.0 = private unnamed_addr constant [3 x i8] c\"hi\\00\"\n\
+             \n\
+             define i32 @main() #0 {\n\
+             0:\n  \
+             %1 = getelementptr inbounds [3 x i8], [3 x i8]* @.str.0, i64 0, i64 0\n  \
+             %2 = call i32 (i8*) @puts(i8* %1)\n  \
+             ret i32 %2\n\
+             }\n\
+             \n\
+             declare i32 @puts(i8*)\n\
+             \n\
+             attributes #0 = { noinline nounwind optnone }\n"
+        );
+    }
+
+    #[test]
+    fn test_llvm_string() {
+        assert_eq!(llvm_string(b"a\"\n\0"), r#"c"a\22\0A\00""#);
+    }
+
+    #[test]
+    fn test_first_element() {
+        let mut module = Module::new();
+        let string = module.add_string(b"hi".to_vec());
+        let element = first_element(&string);
+        assert_eq!(element.ty(), Type::I8.pointer());
+        assert_eq!(
+            element,
+            Value::Literal(
+                Type::I8.pointer(),
+                "getelementptr inbounds ([3 x i8], [3 x i8]* @.str.0, i64 0, i64 0)".to_owned()
+            )
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+
+use super::instruction::CastOp;
+
+/// the type of an IR value
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    Void,
+
+    /// an integer of the number of bits: `i1`, `i8`, `i16`, `i32` or `i64`
+    Int(u32),
+
+    Float,
+    Double,
+    Pointer(Box<Type>),
+    Array(Box<Type>, usize),
+
+    /// a named structure (`%struct.NAME`), whose members are defined in the module
+    Struct(String),
+
+    /// the return type, the parameter types and whether it is variadic
+    Function(Box<Type>, Vec<Type>, bool),
+
+    /// the type of the labels of blocks
+    Label,
+}
+
+impl Type {
+    pub const I1: Type = Type::Int(1);
+    pub const I8: Type = Type::Int(8);
+    pub const I32: Type = Type::Int(32);
+    pub const I64: Type = Type::Int(64);
+
+    /// the pointer to this type
+    pub fn pointer(self) -> Type {
+        Type::Pointer(Box::new(self))
+    }
+
+    /// the pointed-to type, if this is a pointer
+    pub fn pointee(&self) -> Option<&Type> {
+        match self {
+            Type::Pointer(pointee) => Some(pointee),
+            _ => None,
+        }
+    }
+
+    pub fn is_int(&self) -> bool {
+        matches!(self, Type::Int(_))
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Type::Float | Type::Double)
+    }
+
+    pub fn is_pointer(&self) -> bool {
+        matches!(self, Type::Pointer(_))
+    }
+
+    /// whether values of this type can be loaded, stored and allocated
+    pub fn is_first_class(&self) -> bool {
+        !matches!(self, Type::Void | Type::Function(..) | Type::Label)
+    }
+
+    /// the size in bits of a scalar type, for the casts.  None for the other types.
+    fn scalar_bits(&self) -> Option<u32> {
+        match self {
+            Type::Int(bits) => Some(*bits),
+            Type::Float => Some(32),
+            Type::Double => Some(64),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Void => write!(f, "void"),
+            Type::Int(bits) => write!(f, "i{}", bits),
+            Type::Float => write!(f, "float"),
+            Type::Double => write!(f, "double"),
+            Type::Pointer(pointee) => write!(f, "{}*", pointee),
+            Type::Array(element, len) => write!(f, "[{} x {}]", len, element),
+            Type::Struct(name) => write!(f, "%struct.{}", name),
+            Type::Function(ret, params, variadic) => {
+                let mut params: Vec<String> = params.iter().map(Type::to_string).collect();
+                if *variadic {
+                    params.push("...".to_owned());
+                }
+                write!(f, "{} ({})", ret, params.join(", "))
+            }
+            Type::Label => write!(f, "label"),
+        }
+    }
+}
+
+/// whether the cast `op` converts `from` to `to`
+pub fn is_valid_cast(op: CastOp, from: &Type, to: &Type) -> bool {
+    let bits = (from.scalar_bits(), to.scalar_bits());
+    match op {
+        CastOp::Trunc => from.is_int() && to.is_int() && bits.0 > bits.1,
+        CastOp::ZExt | CastOp::SExt => from.is_int() && to.is_int() && bits.0 < bits.1,
+        CastOp::FPTrunc => from.is_float() && to.is_float() && bits.0 > bits.1,
+        CastOp::FPExt => from.is_float() && to.is_float() && bits.0 < bits.1,
+        CastOp::FPToUI | CastOp::FPToSI => from.is_float() && to.is_int(),
+        CastOp::UIToFP | CastOp::SIToFP => from.is_int() && to.is_float(),
+        CastOp::PtrToInt => from.is_pointer() && to.is_int(),
+        CastOp::IntToPtr => from.is_int() && to.is_pointer(),
+        CastOp::BitCast => {
+            (from.is_pointer() && to.is_pointer()) || (bits.0.is_some() && bits.0 == bits.1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let function = Type::Function(Box::new(Type::I32), vec![Type::I8.pointer()], true);
+        assert_eq!(function.to_string(), "i32 (i8*, ...)");
+        assert_eq!(function.pointer().to_string(), "i32 (i8*, ...)*");
+        let matrix = Type::Array(Box::new(Type::Array(Box::new(Type::Double), 3)), 2);
+        assert_eq!(matrix.to_string(), "[2 x [3 x double]]");
+        assert_eq!(Type::Struct("s".to_owned()).to_string(), "%struct.s");
+    }
+
+    #[test]
+    fn casts() {
+        assert!(is_valid_cast(CastOp::SExt, &Type::I8, &Type::I32));
+        assert!(!is_valid_cast(CastOp::SExt, &Type::I32, &Type::I8));
+        assert!(is_valid_cast(CastOp::FPExt, &Type::Float, &Type::Double));
+        assert!(is_valid_cast(
+            CastOp::BitCast,
+            &Type::I32.pointer(),
+            &Type::I8.pointer()
+        ));
+        assert!(!is_valid_cast(CastOp::BitCast, &Type::I32, &Type::I64));
+    }
+}
@@ -1,64 +1,69 @@
-use std::cell::Cell;
-use std::rc::Rc;
-use std::rc::Weak;
+use std::fmt;
 
-enum RegisterName {
-    Unnamed(),
-    Numbering(i32),
+use super::function::{BlockId, Numbered, ValueId};
+use super::types::Type;
+
+/// an operand, with its type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// an integer constant
+    Constant(Type, i64),
+
+    /// a parameter or the result of an instruction of the function
+    Register(Type, ValueId),
+
+    /// the label of a block of the function
+    Label(BlockId),
+
+    /// a constant in the LLVM syntax, such as `0x3FF8000000000000`, `zeroinitializer` or `c"..."`
+    Literal(Type, String),
+
+    /// the address of a global variable or a function, without `@`.  the type is a pointer.
+    Global(Type, String),
 }
 
-pub struct Register(Rc<Cell<RegisterName>>);
-impl Register {
-    pub fn new() -> Register {
-        let name = RegisterName::Unnamed();
-        Register(Rc::new(Cell::new(name)))
-    }
-    pub fn set(&self, n: i32) {
-        let name = RegisterName::Numbering(n);
-        self.0.set(name)
-    }
-    pub fn make_ref(&self) -> WeakRegister {
-        let name = Rc::downgrade(&self.0);
-        WeakRegister(name)
+impl Value {
+    pub fn ty(&self) -> Type {
+        match self {
+            Value::Constant(ty, _)
+            | Value::Register(ty, _)
+            | Value::Literal(ty, _)
+            | Value::Global(ty, _) => ty.clone(),
+            Value::Label(_) => Type::Label,
+        }
     }
 }
 
-pub struct WeakRegister(Weak<Cell<RegisterName>>);
-
-pub enum Value {
-    Constant(i32),
-    Register(WeakRegister),
-    Label(WeakRegister),
+impl<'a> fmt::Display for Numbered<'a, Value> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Value::Constant(_, n) => write!(f, "{}", n),
+            Value::Register(_, id) => write!(f, "%{}", self.1.value(*id)),
+            Value::Label(block) => write!(f, "%{}", self.1.block(*block)),
+            Value::Literal(_, literal) => f.write_str(literal),
+            Value::Global(_, name) => write!(f, "@{}", name),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::function::Function;
     use super::*;
 
     #[test]
-    fn test_register_name() {
-        let _a = RegisterName::Unnamed();
-        let _b = RegisterName::Numbering(0);
-    }
-
-    #[test]
-    fn test_register() {
-        let a = Register::new();
-        let _b = a.make_ref();
-        a.set(0);
-    }
-
-    #[test]
-    fn test_weak_register() {
-        let a = Weak::new();
-        let _b = WeakRegister(a);
-    }
-
-    #[test]
-    fn test_value() {
-        let a = Register::new();
-        let _b = Value::Constant(0);
-        let _c = Value::Register(a.make_ref());
-        let _c = Value::Label(a.make_ref());
+    fn test_display() {
+        let mut func = Function::new("f", Type::Void);
+        func.add_param(Type::I32);
+        let x = func.add_param(Type::I32);
+        let block = func.add_block();
+        let numbering = func.number();
+        let display = |value: &Value| Numbered(value, &numbering).to_string();
+        assert_eq!(display(&x), "%1");
+        assert_eq!(display(&Value::Label(block)), "%2");
+        assert_eq!(display(&Value::Constant(Type::I64, -1)), "-1");
+        let string = Value::Global(Type::I8.pointer(), ".str.0".to_owned());
+        assert_eq!(display(&string), "@.str.0");
+        assert_eq!(string.ty().to_string(), "i8*");
     }
 }
@@ -0,0 +1,436 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::iter;
+
+use super::function::{Definition, Function, Numbered, Numbering};
+use super::instruction::Instruction;
+use super::module::Module;
+use super::types::Type;
+use super::value::Value;
+
+/// where a value is defined
+#[derive(Debug, Clone, Copy)]
+enum Def {
+    Param,
+
+    /// the instruction at (the index of the block, the index in the block)
+    Result(usize, usize),
+}
+
+/// checks that `function` is well-formed: every block ends in exactly one terminator, every
+/// operand is a value of the function having the type of the operand and dominating its use,
+/// and the phis have an incoming value from each predecessor.  returns the description of the
+/// first problem found.
+pub fn verify(function: &Function) -> Result<(), String> {
+    let numbering = function.number();
+    let blocks = function.blocks();
+    if blocks.is_empty() {
+        return Err("the function has no block".to_owned());
+    }
+
+    let mut positions = HashMap::new();
+    for block in &blocks {
+        for (idx, id) in function.instructions(*block).iter().enumerate() {
+            positions.insert(*id, (block.index(), idx));
+        }
+    }
+    // where the operand is defined.  None if it is not a register.
+    let def = |operand: &Value| match operand {
+        Value::Register(_, id) => Some(match positions.get(id) {
+            Some((block, idx)) => Def::Result(*block, *idx),
+            None => Def::Param,
+        }),
+        _ => None,
+    };
+
+    let mut successors = Vec::new();
+    for block in &blocks {
+        let at = |message: &str| format!("block %{}: {}", numbering.block(*block), message);
+        let insts: Vec<&Instruction> = function
+            .instructions(*block)
+            .iter()
+            .map(|id| {
+                function
+                    .instruction(*id)
+                    .expect("ICE: a block has a parameter")
+            })
+            .collect();
+        for inst in &insts {
+            inst.check_types().map_err(|message| at(&message))?;
+        }
+        match insts.iter().position(|inst| inst.is_terminator()) {
+            Some(idx) if idx + 1 == insts.len() => {}
+            Some(_) => return Err(at("an instruction after the terminator")),
+            None => return Err(at("no terminator")),
+        }
+        if let Some(idx) = insts.iter().position(|inst| !is_phi(inst)) {
+            if insts[idx..].iter().any(|inst| is_phi(inst)) {
+                return Err(at("a phi after another instruction"));
+            }
+        }
+
+        for inst in &insts {
+            for operand in inst.operands() {
+                check_use(function, &numbering, operand).map_err(|message| at(&message))?;
+            }
+            match inst {
+                Instruction::Ret(value) if value.ty() != *function.ret() => {
+                    return Err(at(&format!(
+                        "ret of {} in a function returning {}",
+                        value.ty(),
+                        function.ret()
+                    )));
+                }
+                Instruction::RetVoid if *function.ret() != Type::Void => {
+                    return Err(at(&format!(
+                        "ret void in a function returning {}",
+                        function.ret()
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        let terminator = insts.last().expect("ICE: the block is empty");
+        let labels = terminator
+            .operands()
+            .into_iter()
+            .filter_map(|operand| match operand {
+                Value::Label(dest) => Some(dest.index()),
+                _ => None,
+            });
+        successors.push(labels.collect::<BTreeSet<usize>>());
+    }
+
+    let predecessors: Vec<BTreeSet<usize>> = (0..blocks.len())
+        .map(|idx| {
+            (0..blocks.len())
+                .filter(|pred| successors[*pred].contains(&idx))
+                .collect()
+        })
+        .collect();
+    let dominators = dominators(&successors, &predecessors);
+    // whether the definition is available at the instruction at `idx` of the block `block`.
+    // everything is available in unreachable blocks, as LLVM does not check them.
+    let dominates = |def: Def, block: usize, idx: usize| match def {
+        Def::Param => true,
+        Def::Result(def_block, def_idx) => match dominators[block] {
+            _ if def_block == block => def_idx < idx,
+            Some(ref dominators) => dominators.contains(&def_block),
+            None => true,
+        },
+    };
+
+    for block in &blocks {
+        let idx = block.index();
+        let at = |message: String| format!("block %{}: {}", numbering.block(*block), message);
+        let value = |value| Numbered(value, &numbering);
+        for (inst_idx, id) in function.instructions(*block).iter().enumerate() {
+            let inst = function
+                .instruction(*id)
+                .expect("ICE: a block has a parameter");
+            if let Instruction::Phi(_, incoming) = inst {
+                let mut froms = BTreeSet::new();
+                for (incoming, label) in incoming {
+                    let from = match label {
+                        Value::Label(from) => from.index(),
+                        _ => unreachable!(),
+                    };
+                    if !predecessors[idx].contains(&from) {
+                        return Err(at(format!(
+                            "a phi from {}, which is not a predecessor",
+                            value(label)
+                        )));
+                    }
+                    if let Some(def) = def(incoming) {
+                        if !dominates(def, from, usize::MAX) {
+                            let message = format!("{} does not dominate its use", value(incoming));
+                            return Err(at(message));
+                        }
+                    }
+                    froms.insert(from);
+                }
+                if let Some(pred) = predecessors[idx].difference(&froms).next() {
+                    let pred = numbering.block(blocks[*pred]);
+                    return Err(at(format!("a phi without a value from %{}", pred)));
+                }
+                continue;
+            }
+            for operand in inst.operands() {
+                if let Some(def) = def(operand) {
+                    if !dominates(def, idx, inst_idx) {
+                        let message = format!("{} does not dominate its use", value(operand));
+                        return Err(at(message));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_phi(inst: &Instruction) -> bool {
+    matches!(inst, Instruction::Phi(..))
+}
+
+/// checks that `value` refers to a value or a block of `function`, and that a register has
+/// the type of its definition
+fn check_use(function: &Function, numbering: &Numbering, value: &Value) -> Result<(), String> {
+    match value {
+        Value::Register(ty, id) => match function.definition(*id).map(Definition::ty) {
+            Some(Some(ref def)) if def == ty => Ok(()),
+            Some(Some(def)) => Err(format!(
+                "{} of type {} is used as {}",
+                Numbered(value, numbering),
+                def,
+                ty
+            )),
+            Some(None) => Err("a use of an instruction without a result".to_owned()),
+            None => Err("a use of a value defined outside of the function".to_owned()),
+        },
+        Value::Label(block) if block.index() >= function.blocks().len() => {
+            Err("a use of a block outside of the function".to_owned())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// checks that every reference to a global of `module` in its functions has the type of the
+/// address of the global, which LLVM requires of the references to the same global.  returns
+/// the description of the first mismatch found.
+pub fn verify_module(module: &Module) -> Result<(), String> {
+    let mut types: HashMap<String, Type> = module.symbols().into_iter().collect();
+    for function in module.functions() {
+        for block in function.blocks() {
+            for id in function.instructions(block) {
+                let inst = function
+                    .instruction(*id)
+                    .expect("ICE: a block has a parameter");
+                for operand in inst.operands() {
+                    let (ty, name) = match operand {
+                        Value::Global(ty, name) => (ty, name),
+                        _ => continue,
+                    };
+                    match types.entry(name.clone()) {
+                        Entry::Occupied(entry) if entry.get() != ty => {
+                            return Err(format!(
+                                "@{} of type {} is referred to as {} in `{}`",
+                                name,
+                                entry.get(),
+                                ty,
+                                function.name()
+                            ));
+                        }
+                        Entry::Occupied(_) => {}
+                        Entry::Vacant(entry) => {
+                            entry.insert(ty.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// the blocks dominating each block, including itself.  None for the blocks unreachable from
+/// the entry block.
+fn dominators(
+    successors: &[BTreeSet<usize>],
+    predecessors: &[BTreeSet<usize>],
+) -> Vec<Option<BTreeSet<usize>>> {
+    let mut reachable = BTreeSet::new();
+    let mut stack = vec![0];
+    while let Some(block) = stack.pop() {
+        if reachable.insert(block) {
+            stack.extend(successors[block].iter().cloned());
+        }
+    }
+
+    let mut dominators: Vec<Option<BTreeSet<usize>>> = (0..successors.len())
+        .map(|block| match block {
+            0 => Some(iter::once(0).collect()),
+            _ if reachable.contains(&block) => Some(reachable.clone()),
+            _ => None,
+        })
+        .collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for block in 1..successors.len() {
+            if dominators[block].is_none() {
+                continue;
+            }
+            let mut preds = predecessors[block]
+                .iter()
+                .filter_map(|pred| dominators[*pred].as_ref());
+            let first = preds.next().cloned().unwrap_or_default();
+            let mut new: BTreeSet<usize> = preds.fold(first, |acc, dominators| &acc & dominators);
+            new.insert(block);
+            if dominators[block].as_ref() != Some(&new) {
+                dominators[block] = Some(new);
+                changed = true;
+            }
+        }
+    }
+    dominators
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::instruction::IntPredicate;
+    use super::*;
+
+    /// `int f(int x) { return x ? 1 : 2; }` with a phi
+    fn diamond(incoming_from_otherwise: bool) -> Function {
+        let mut func = Function::new("f", Type::I32);
+        let x = func.add_param(Type::I32);
+        let (entry, then, otherwise, end) = (
+            func.add_block(),
+            func.add_block(),
+            func.add_block(),
+            func.add_block(),
+        );
+        let cond = func
+            .builder(entry)
+            .icmp(IntPredicate::Ne, x, Value::Constant(Type::I32, 0));
+        func.builder(entry)
+            .cond_br(cond, Value::Label(then), Value::Label(otherwise));
+        func.builder(then).br(Value::Label(end));
+        func.builder(otherwise).br(Value::Label(end));
+        let mut incoming = vec![(Value::Constant(Type::I32, 1), Value::Label(then))];
+        if incoming_from_otherwise {
+            incoming.push((Value::Constant(Type::I32, 2), Value::Label(otherwise)));
+        }
+        let result = func.builder(end).phi(Type::I32, incoming);
+        func.builder(end).ret(result);
+        func
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(verify(&diamond(true)), Ok(()));
+    }
+
+    #[test]
+    fn phi_predecessors() {
+        assert_eq!(
+            verify(&diamond(false)),
+            Err("block %5: a phi without a value from %4".to_owned())
+        );
+    }
+
+    #[test]
+    fn terminators() {
+        let mut func = Function::new("f", Type::Void);
+        func.add_block();
+        func.last_block().ret_void();
+        func.last_block().ret_void();
+        assert_eq!(
+            verify(&func),
+            Err("block %0: an instruction after the terminator".to_owned())
+        );
+
+        let mut func = Function::new("f", Type::I32);
+        func.add_block();
+        func.last_block().alloca(Type::I32);
+        assert_eq!(verify(&func), Err("block %0: no terminator".to_owned()));
+
+        let mut func = Function::new("f", Type::I32);
+        func.add_block();
+        func.last_block().ret_void();
+        assert_eq!(
+            verify(&func),
+            Err("block %0: ret void in a function returning i32".to_owned())
+        );
+    }
+
+    #[test]
+    fn dominance() {
+        // the value defined in `then` is used in `end`, which `otherwise` also reaches
+        let mut func = Function::new("f", Type::I32);
+        let x = func.add_param(Type::I1);
+        let (entry, then, otherwise, end) = (
+            func.add_block(),
+            func.add_block(),
+            func.add_block(),
+            func.add_block(),
+        );
+        func.builder(entry)
+            .cond_br(x, Value::Label(then), Value::Label(otherwise));
+        let one = Value::Constant(Type::I32, 1);
+        let y = func.builder(then).add(one.clone(), one);
+        func.builder(then).br(Value::Label(end));
+        func.builder(otherwise).br(Value::Label(end));
+        func.builder(end).ret(y);
+        assert_eq!(
+            verify(&func),
+            Err("block %5: %3 does not dominate its use".to_owned())
+        );
+    }
+
+    #[test]
+    fn foreign_values() {
+        // a value of another function, whose index is not in this one
+        let mut other = Function::new("g", Type::Void);
+        other.add_block();
+        for _ in 0..4 {
+            other.last_block().alloca(Type::I32);
+        }
+        let value = other.last_block().alloca(Type::I32);
+        let mut func = Function::new("f", Type::I32);
+        func.add_block();
+        func.last_block().load(value, false);
+        func.last_block().ret(Value::Constant(Type::I32, 0));
+        assert_eq!(
+            verify(&func),
+            Err("block %0: a use of a value defined outside of the function".to_owned())
+        );
+
+        let mut func = Function::new("f", Type::Void);
+        func.add_block();
+        other.add_block();
+        let label = other.last_block().label();
+        assert_eq!(label, Value::Label(other.blocks()[1]));
+        func.last_block().br(label);
+        assert_eq!(
+            verify(&func),
+            Err("block %0: a use of a block outside of the function".to_owned())
+        );
+    }
+
+    #[test]
+    fn global_references() {
+        let mut module = Module::new();
+        let mut f = Function::new("f", Type::I32);
+        let x = f.add_param(Type::I32);
+        f.add_block();
+        f.last_block().ret(x);
+        module.add_function(f);
+
+        // `f` is called as declared by `int f();` before its definition
+        let mut main = Function::new("main", Type::I32);
+        main.add_block();
+        let unprototyped = Type::Function(Box::new(Type::I32), vec![], true);
+        let callee = Value::Global(unprototyped.clone().pointer(), "f".to_owned());
+        let result = main
+            .last_block()
+            .call(callee, vec![Value::Constant(Type::I32, 1)]);
+        main.last_block().ret(result);
+        module.add_function(main);
+        assert_eq!(
+            verify_module(&module),
+            Err("@f of type i32 (i32)* is referred to as i32 (...)* in `main`".to_owned())
+        );
+
+        let mut module = Module::new();
+        module.declare("g", unprototyped.clone());
+        let mut main = Function::new("main", Type::I32);
+        main.add_block();
+        let callee = Value::Global(unprototyped.pointer(), "g".to_owned());
+        let result = main.last_block().call(callee, vec![]);
+        main.last_block().ret(result);
+        module.add_function(main);
+        assert_eq!(verify_module(&module), Ok(()));
+    }
+}
@@ -1,79 +1,281 @@
-use itertools::Itertools;
-
-use std::iter::Peekable;
+use std::mem;
 
+use diagnostic::Diagnostic;
+use message::DiagnosticKind;
+use source::{Source, Span};
 use token::Token;
 
+/// the longest punctuator, in bytes (`%:%:`)
+const MAX_PUNCTUATOR_LEN: usize = 4;
+
 /// split the source code into tokens
 pub struct Lexer<'a> {
-    /// entire source code
-    source: &'a str,
+    /// entire source code (after line splicing)
+    source: &'a Source,
 
-    /// (starting, ending, character)
-    /// this is Peekable to use peeking_take_while().
-    chars: Peekable<Box<dyn Iterator<Item = (usize, usize, char)> + 'a>>,
+    /// byte offset of the next character to be read
+    pos: usize,
+
+    /// the lexical errors found so far.  the lexer skips the erroneous characters and goes on.
+    errors: Vec<Diagnostic>,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn from_source(source: &'a str) -> Lexer {
+    pub fn from_source(source: &'a Source) -> Lexer<'a> {
         Lexer {
-            source: source,
-            chars: gen_bid_char_indices(source),
+            source,
+            pos: 0,
+            errors: Vec::new(),
         }
     }
-}
 
-/// generate char_indices that having (starting, ending, char)
-fn gen_bid_char_indices<'a>(
-    source: &'a str,
-) -> Peekable<Box<dyn Iterator<Item = (usize, usize, char)> + 'a>> {
-    let ending_indices = source
-        .char_indices()
-        .map(|(pos, _)| pos)
-        .skip(1)
-        .chain(Some(source.len()).into_iter());
-
-    let bid_char_indices = source
-        .char_indices()
-        .zip(ending_indices)
-        .map(|((starting, ch), ending)| (starting, ending, ch));
-
-    let bid_char_indices: Box<dyn Iterator<Item = (usize, usize, char)>> =
-        Box::new(bid_char_indices);
-
-    bid_char_indices.peekable()
+    /// takes the lexical errors found so far
+    pub fn take_errors(&mut self) -> Vec<Diagnostic> {
+        mem::take(&mut self.errors)
+    }
+
+    /// records an error at `len` bytes from the current position, and skips them
+    fn skip_error(&mut self, len: usize, kind: DiagnosticKind) {
+        let span = Span::new(self.pos, self.pos + len);
+        self.errors.push(Diagnostic::new(span, kind));
+        self.pos += len;
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source.text()[self.pos..]
+    }
+
+    /// skips whitespace and comments.  each comment is equivalent to one space character, so it
+    /// is simply skipped as well.
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with("//") {
+                // the new-line itself is not part of the comment
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                match comment.find("*/") {
+                    Some(end) => self.pos += 2 + end + 2,
+                    None => {
+                        // the rest of the file is in the comment
+                        self.errors.push(Diagnostic::new(
+                            Span::new(self.pos, self.pos + 2),
+                            DiagnosticKind::UnterminatedComment,
+                        ));
+                        self.pos = self.source.text().len();
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// the length of the preprocessing number at the start of `rest`.  this takes letters and
+    /// signs after exponents too, so `0x1f` or `1e+5` are read as one token and validated as a
+    /// whole.
+    fn pp_number_len(rest: &str) -> usize {
+        let mut prev = None;
+        rest.char_indices()
+            .find(|&(_, ch)| {
+                let is_sign = (ch == '+' || ch == '-')
+                    && matches!(prev, Some('e') | Some('E') | Some('p') | Some('P'));
+                prev = Some(ch);
+                !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.' || is_sign)
+            })
+            .map(|(pos, _)| pos)
+            .unwrap_or_else(|| rest.len())
+    }
+
+    fn identifier_len(rest: &str) -> usize {
+        rest.find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+            .unwrap_or(rest.len())
+    }
+
+    /// the length of the string literal at the start of `rest`, including both quotes.  returns
+    /// None if the literal is not terminated on the same line.
+    fn string_literal_len(rest: &str) -> Option<usize> {
+        let mut escaped = false;
+        for (pos, ch) in rest.char_indices().skip(1) {
+            match ch {
+                '\n' => return None,
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return Some(pos + 1),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// finds the longest punctuator at the start of `rest`
+    fn punctuator(rest: &'a str) -> Option<(usize, Token<'a>)> {
+        (1..MAX_PUNCTUATOR_LEN + 1)
+            .rev()
+            .filter(|&len| rest.is_char_boundary(len))
+            .filter_map(|len| Token::from_str(&rest[..len]).map(|token| (len, token)))
+            .next()
+    }
 }
 
 impl<'a> Iterator for Lexer<'a> {
-    type Item = Token<'a>;
+    type Item = (Token<'a>, Span);
 
-    /// find a next token starting from current position (is it whitespace, skips them and from
-    /// a next non-whitespace character).  if entire source code consumed, this returns None.
+    /// find a next token starting from current position (is it whitespace or a comment, skips
+    /// them and from a next non-whitespace character).  if entire source code consumed, this
+    /// returns None.  characters that do not form a token are recorded as errors and skipped.
     fn next(&mut self) -> Option<Self::Item> {
-        // skip whitespace
-        self.chars
-            .peeking_take_while(|&(_, _, ch)| ch.is_whitespace())
-            .for_each(drop);
-
-        // get first char position. if there isn't, returns None.
-        let first = self.chars.peek().map(|&(first, _, _)| first)?;
-
-        // to avoid borrowing self in closure
-        let source = self.source;
-
-        // find longest token
-        let mut token = None;
-        self.chars
-            .peeking_take_while(|&(_, pos, _)| {
-                let maybe_token = Token::from_str(&source[first..pos]);
-                let is_valid = maybe_token.is_some();
-                if is_valid {
-                    token = maybe_token;
+        loop {
+            self.skip_whitespace_and_comments();
+
+            let rest = self.rest();
+            let mut chars = rest.chars();
+            let first = chars.next()?;
+            let second = chars.next();
+
+            let is_number = first.is_ascii_digit()
+                || (first == '.' && second.is_some_and(|ch| ch.is_ascii_digit()));
+
+            let token = if is_number {
+                let len = Lexer::pp_number_len(rest);
+                match Token::from_str(&rest[..len]) {
+                    Some(token) => Some((len, token)),
+                    None => {
+                        self.skip_error(len, DiagnosticKind::InvalidNumber(rest[..len].to_owned()));
+                        continue;
+                    }
                 }
-                is_valid
-            })
-            .for_each(drop);
+            } else if first.is_ascii_alphabetic() || first == '_' {
+                let len = Lexer::identifier_len(rest);
+                Token::from_str(&rest[..len]).map(|token| (len, token))
+            } else if first == '"' {
+                match Lexer::string_literal_len(rest) {
+                    Some(len) => Token::from_str(&rest[..len]).map(|token| (len, token)),
+                    None => {
+                        // the rest of the line is skipped
+                        let len = rest.find('\n').unwrap_or(rest.len());
+                        self.skip_error(len, DiagnosticKind::UnterminatedString);
+                        continue;
+                    }
+                }
+            } else {
+                Lexer::punctuator(rest)
+            };
+
+            match token {
+                Some((len, token)) => {
+                    let span = Span::new(self.pos, self.pos + len);
+                    self.pos += len;
+                    return Some((token, span));
+                }
+                None => self.skip_error(first.len_utf8(), DiagnosticKind::InvalidCharacter(first)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_tokens(source: &str, expected: &[Token]) {
+        let source = Source::new(source.to_owned());
+        let tokens: Vec<Token> = Lexer::from_source(&source)
+            .map(|(token, _)| token)
+            .collect();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn skip_comments() {
+        assert_tokens(
+            "return /* 1 + */ 42; // comment\n",
+            &[Token::KwReturn, Token::Literal("42"), Token::SySemicolon],
+        );
+        assert_tokens(
+            "4/**/2//*\n/",
+            &[Token::Literal("4"), Token::Literal("2"), Token::OpDiv],
+        );
+    }
+
+    #[test]
+    fn splice_lines() {
+        assert_tokens(
+            "re\\\nturn // comment \\\n continued\n;",
+            &[Token::KwReturn, Token::SySemicolon],
+        );
+    }
+
+    #[test]
+    fn digraphs() {
+        assert_tokens(
+            "<% %> <: :> %: %:%:",
+            &[
+                Token::SyLBrace,
+                Token::SyRBrace,
+                Token::SyLBracket,
+                Token::SyRBracket,
+                Token::SyHash,
+                Token::SyHashHash,
+            ],
+        );
+    }
+
+    #[test]
+    fn numbers_and_strings() {
+        assert_tokens(
+            r#"1.5f .5 1e+3 0x1p-2 0x1f "a\"b" <="#,
+            &[
+                Token::FloatLiteral("1.5f"),
+                Token::FloatLiteral(".5"),
+                Token::FloatLiteral("1e+3"),
+                Token::FloatLiteral("0x1p-2"),
+                Token::Literal("0x1f"),
+                Token::StringLiteral(r#""a\"b""#),
+                Token::OpLe,
+            ],
+        );
+    }
+
+    #[test]
+    fn spans() {
+        let source = Source::new("int x;".to_owned());
+        let spans: Vec<Span> = Lexer::from_source(&source).map(|(_, span)| span).collect();
+        assert_eq!(spans, [Span::new(0, 3), Span::new(4, 5), Span::new(5, 6)]);
+    }
+
+    #[test]
+    fn unterminated_comment() {
+        let source = Source::new("42\n  /* 42".to_owned());
+        let mut lexer = Lexer::from_source(&source);
+        assert_eq!(lexer.by_ref().count(), 1);
+        let errors = lexer.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message(), "unterminated comment");
+        assert_eq!(source.location(errors[0].span.start), (2, 3));
+    }
+
+    #[test]
+    fn invalid_characters() {
+        let source = Source::new("a @ b $\n\"c 1z".to_owned());
+        let mut lexer = Lexer::from_source(&source);
+        let tokens: Vec<Token> = lexer.by_ref().map(|(token, _)| token).collect();
+        assert_eq!(tokens, [Token::Ident("a"), Token::Ident("b")]);
 
-        token
+        let errors = lexer.take_errors();
+        let messages: Vec<String> = errors.iter().map(Diagnostic::message).collect();
+        assert_eq!(
+            messages,
+            [
+                "invalid character `@` in source",
+                "invalid character `$` in source",
+                "missing terminating `\"` character",
+            ]
+        );
+        assert_eq!(errors[0].span, Span::new(2, 3));
+        assert_eq!(source.location(errors[2].span.start), (2, 1));
     }
 }
@@ -0,0 +1,214 @@
+//! cerussite, a C compiler generating LLVM IR.  `compile` compiles a translation unit in
+//! memory, and the stages of it are exposed as the public modules.
+
+extern crate itertools;
+#[macro_use]
+extern crate lazy_static;
+extern crate regex;
+
+pub mod ast;
+pub mod diagnostic;
+pub mod ir;
+pub mod lexer;
+pub mod message;
+pub mod source;
+pub mod token;
+pub mod types;
+pub mod warning;
+
+use ast::env::{Env, Types};
+use ast::Ast;
+pub use diagnostic::Diagnostic;
+use lexer::Lexer;
+pub use source::{Source, Span};
+use token::{Token, Tokens};
+
+/// the target of the generated IR.  the sizes of the types assume LP64.
+const TARGET_TRIPLE: &str = "x86_64-pc-linux-gnu";
+const DATALAYOUT: &str = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128";
+
+/// the options of `compile`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub warnings: warning::Options,
+
+    /// whether the IR is checked by `ir::verify`, which is always done in debug builds
+    pub verify_ir: bool,
+}
+
+/// a compiled translation unit
+#[derive(Debug)]
+pub struct Module {
+    /// the LLVM IR, printed as a `.ll` file by `to_string`
+    pub ir: ir::Module,
+
+    /// the warnings, which did not stop the compilation
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// a translation unit which has passed the semantic analysis
+#[derive(Debug)]
+pub struct Checked {
+    pub ast: Ast,
+
+    /// the types of the expressions and the declarators
+    pub types: Types,
+
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// splits `source` into tokens with their spans
+pub fn tokenize(source: &Source) -> Result<Vec<(Token<'_>, Span)>, Vec<Diagnostic>> {
+    let mut lexer = Lexer::from_source(source);
+    let tokens: Vec<_> = lexer.by_ref().collect();
+    let errors = lexer.take_errors();
+    if errors.is_empty() {
+        Ok(tokens)
+    } else {
+        Err(errors)
+    }
+}
+
+/// parses `source` into the syntax tree.  the lexical and syntax errors are reported together.
+pub fn parse(source: &Source) -> Result<Ast, Vec<Diagnostic>> {
+    let mut lexer = Lexer::from_source(source);
+    let (tokens, spans): (Vec<Token>, Vec<Span>) = lexer.by_ref().unzip();
+    let mut diagnostics = lexer.take_errors();
+
+    // parse even after lexical errors so that syntax errors are reported together
+    match Ast::parse(Tokens::with_spans(&tokens, &spans)) {
+        Ok(ast) if diagnostics.is_empty() => Ok(ast),
+        Ok(_) => Err(diagnostics),
+        Err(errors) => {
+            diagnostics.extend(errors.into_iter().map(Diagnostic::from));
+            diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+            Err(diagnostics)
+        }
+    }
+}
+
+/// parses and checks `source`.  on failure, all the diagnostics are returned, including the
+/// warnings.
+pub fn check(source: &Source, options: &Options) -> Result<Checked, Vec<Diagnostic>> {
+    let ast = parse(source)?;
+    let (diagnostics, types) = ast.analyze();
+    let warnings = options.warnings.apply(diagnostics);
+    if warnings.iter().any(Diagnostic::is_error) {
+        return Err(warnings);
+    }
+    Ok(Checked {
+        ast,
+        types,
+        warnings,
+    })
+}
+
+/// compiles a translation unit to LLVM IR.  on failure, all the diagnostics are returned,
+/// including the warnings.
+pub fn compile(source: &str, options: &Options) -> Result<Module, Vec<Diagnostic>> {
+    compile_source(&Source::new(source.to_owned()), options)
+}
+
+/// `compile` for a `Source`, whose positions the diagnostics refer to
+pub fn compile_source(source: &Source, options: &Options) -> Result<Module, Vec<Diagnostic>> {
+    let checked = check(source, options)?;
+    let warnings = checked.warnings;
+    let ast = checked.ast;
+    let mut env = Env::new();
+    ast.gen_code(&mut env);
+    let mut module = env.into_module();
+    if options.verify_ir || cfg!(debug_assertions) {
+        for function in module.functions() {
+            if let Err(message) = ir::verify(function) {
+                panic!("ICE: invalid IR of `{}`: {}", function.name(), message);
+            }
+        }
+        if let Err(message) = ir::verify_module(&module) {
+            panic!("ICE: invalid IR: {}", message);
+        }
+    }
+    module.set_target(TARGET_TRIPLE, DATALAYOUT);
+    Ok(Module {
+        ir: module,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn compile_module() {
+        let module = compile("int main(void) { int x; return 42; }", &Options::default()).unwrap();
+        assert!(module.ir.to_string().contains("define i32 @main()"));
+        assert_eq!(module.warnings.len(), 1);
+        assert_eq!(module.warnings[0].message(), "unused variable `x`");
+
+        let mut options = Options::default();
+        options.warnings.parse_flag("-Werror");
+        let errors = compile("int main(void) { int x; return 42; }", &options).unwrap_err();
+        assert!(errors.iter().all(Diagnostic::is_error));
+
+        let errors = compile("int main(void) { return y $ 1 }", &Options::default()).unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+
+    /// the IR, with code after `return` in unnamed blocks, is accepted by `llvm-as` if it is
+    /// installed
+    #[test]
+    fn valid_ir() {
+        let text = r#"
+            struct P { int x; double y; char s[4]; };
+            static int counter;
+            static int *counted = &counter;
+            char *name = "bump";
+            void bump(void) { counter = counter + 1; return; }
+            int add(int a, long b) { return a + b; }
+            int later();
+            int main(void) {
+                volatile int v = 3;
+                struct P p = {4, 5.5, "xy"};
+                int arr[3] = {1, 2};
+                int (*f)(int, long) = add;
+                unsigned u = 7;
+                bump();
+                p.x = arr[1] + v;
+                (*&p).y = (arr[2]) = 6;
+                p.x = p.x + (arr + 2 - 1 == &arr[1]) + (0 != arr) + (arr < 1 + arr);
+                p.x = later(p.x, 1);
+                { return f(p.x, arr[0]) + u / 2u + (p.y > 1.0) + -v + (int)-p.y; }
+                return 1;
+            }
+            int later(int a, int b) { return a - b; }
+        "#;
+        let ir = compile(text, &Options::default()).unwrap().ir.to_string();
+        assert!(ir.contains("  ret void\n\n4:\n  ret void\n}"));
+        assert!(ir.starts_with("target datalayout = "));
+        assert!(ir.contains("\nattributes #0 = { "));
+        // the aggregates are zero-filled by the intrinsic declared once
+        assert!(ir.contains("@llvm.memset.p0i8.i64(i8* %4, i8 0, i64 24, i1 0)"));
+        assert!(ir.contains(", i8 0, i64 12, i1 0)"));
+        assert_eq!(ir.matches("declare void @llvm.memset").count(), 1);
+        let llvm_as = Command::new("llvm-as")
+            .args(["-o", "/dev/null", "-"])
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut llvm_as = match llvm_as {
+            Ok(llvm_as) => llvm_as,
+            Err(_) => return,
+        };
+        let mut stdin = llvm_as.stdin.take().unwrap();
+        stdin.write_all(ir.as_bytes()).unwrap();
+        drop(stdin);
+        let output = llvm_as.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
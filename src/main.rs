@@ -1,46 +1,63 @@
-extern crate itertools;
-#[macro_use]
-extern crate lazy_static;
-extern crate regex;
+extern crate cerussite;
 
 use std::env;
 use std::error::Error;
-use std::fs::File;
-use std::io::prelude::*;
+use std::process;
 
-mod ast;
-mod ir;
-mod lexer;
-mod token;
+mod args;
+mod driver;
 
-use ast::Ast;
-use lexer::Lexer;
-use token::{Token, Tokens};
+use args::Args;
+use cerussite::message::{self, Language};
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let file_name = env::args().nth(1).expect("no file name supplied.");
-
-    let mut source = String::new();
-    File::open(&*file_name)?.read_to_string(&mut source)?;
-
-    let tokens: Vec<Token> = Lexer::from_source(&source).collect();
-
-    eprintln!("{:?}", tokens);
-
-    assert!(tokens.len() >= 9);
-
-    let tokens = match &tokens[0..5] {
-        &[Token::TyInt, Token::Ident("main"), Token::SyLPar, Token::TyVoid, Token::SyRPar] => {
-            &tokens[5..]
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!("{}", args::USAGE);
+            process::exit(1);
         }
-        _ => panic!("compilation error"),
     };
-
-    let ast = Ast::parse(Tokens::new(tokens));
-
-    println!("define i32 @main() #0 {{");
-    let _ = ast.gen_code();
-    println!("}}");
-
-    Ok(())
+    if args.help {
+        println!("{}", args::USAGE);
+        return Ok(());
+    }
+    if args.version {
+        println!("cerussite {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+
+    let language = args.language.unwrap_or_else(|| {
+        env::var("LANG").map_or(Language::English, |locale| Language::from_locale(&locale))
+    });
+
+    if let Some(ref code) = args.explain {
+        match message::explain(code, language) {
+            Some(explanation) => println!("{}", explanation),
+            None => {
+                eprintln!("error: no extended information for `{}`", code);
+                process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    for option in &args.unknown {
+        eprintln!("warning: ignoring unknown option `{}`", option);
+    }
+    if args.inputs.is_empty() {
+        eprintln!("error: no input file");
+        eprintln!("{}", args::USAGE);
+        process::exit(1);
+    }
+
+    match driver::run(&args, language) {
+        Ok(true) => Ok(()),
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,1341 @@
+/// (code, English, Japanese)
+pub const EXPLANATIONS: &[(&str, &str, &str)] = &[
+    (
+        "E0001",
+        "\
+E0001: invalid character in source
+
+A character that cannot begin any token appeared outside a string or a comment.
+
+    int main(void) {
+        return 1 @ 2; // `@` is not an operator
+    }
+
+The character is skipped and the rest of the file is still checked.",
+        "\
+E0001: ソースコード中の不正な文字
+
+文字列やコメントの外に、どのトークンの始まりにもならない文字があります。
+
+    int main(void) {
+        return 1 @ 2; // `@` は演算子ではない
+    }
+
+その文字は読み飛ばされ、ファイルの残りも検査されます。",
+    ),
+    (
+        "E0002",
+        "\
+E0002: invalid number
+
+A token starting with a digit is not a valid integer or floating constant.
+
+    int x = 09; // `9` is not an octal digit
+    int y = 1e; // the exponent has no digits",
+        "\
+E0002: 不正な数値
+
+数字で始まるトークンが、整数定数としても浮動小数点定数としても正しくありません。
+
+    int x = 09; // `9` は 8 進数の数字ではない
+    int y = 1e; // 指数部に数字がない",
+    ),
+    (
+        "E0003",
+        "\
+E0003: unterminated string literal
+
+A string literal has no closing `\"` on the same line.
+
+    char *s = \"hello;
+
+String literals cannot span lines.  Close the literal, or write `\\n` for a newline.",
+        "\
+E0003: 閉じられていない文字列リテラル
+
+文字列リテラルの終わりの `\"` が同じ行にありません。
+
+    char *s = \"hello;
+
+文字列リテラルは複数行にまたがれません。リテラルを閉じるか、改行は `\\n` と書いてください。",
+    ),
+    (
+        "E0004",
+        "\
+E0004: unterminated comment
+
+A `/*` comment has no matching `*/` before the end of the file.
+
+    int main(void) {
+        /* the comment never ends
+        return 0;
+    }",
+        "\
+E0004: 閉じられていないコメント
+
+`/*` で始まるコメントに対応する `*/` がファイルの終わりまでにありません。
+
+    int main(void) {
+        /* コメントが終わらない
+        return 0;
+    }",
+    ),
+    (
+        "E0005",
+        "\
+E0005: unknown escape sequence
+
+A backslash in a character constant or a string literal is followed by a character that does
+not make an escape sequence.
+
+    char *s = \"100\\%\"; // write `%` without a backslash",
+        "\
+E0005: 不明なエスケープシーケンス
+
+文字定数や文字列リテラルの中のバックスラッシュの後に、エスケープシーケンスにならない文字があります。
+
+    char *s = \"100\\%\"; // `%` はバックスラッシュなしで書く",
+    ),
+    (
+        "E0006",
+        "\
+E0006: integer constant too large
+
+An integer constant does not fit in the largest integer type, or in the type its suffix asks
+for.
+
+    long x = 100000000000000000000;
+    int y = 3000000000u; // does not fit in `unsigned int`",
+        "\
+E0006: 大きすぎる整数定数
+
+整数定数が最も大きい整数型にも、接尾辞で指定された型にも収まりません。
+
+    long x = 100000000000000000000;
+    int y = 3000000000u; // `unsigned int` に収まらない",
+    ),
+    (
+        "E0101",
+        "\
+E0101: unexpected token
+
+The parser needed a particular construct, but found another token or the end of the file.
+
+    int main(void) {
+        return 0;
+    // the `}` closing the function is missing
+
+Parsing resumes at the next `;` or `}`, so several syntax errors are reported at once.",
+        "\
+E0101: 予期しないトークン
+
+構文解析器が特定の構文を必要としていたところに、別のトークンかファイルの終わりがありました。
+
+    int main(void) {
+        return 0;
+    // 関数を閉じる `}` がない
+
+構文解析は次の `;` か `}` から再開するので、複数の構文エラーが一度に報告されます。",
+    ),
+    (
+        "E0102",
+        "\
+E0102: unbalanced parenthesis, bracket or brace
+
+An opening `(`, `[` or `{` has no matching closing one.
+
+    int f(int x) {
+        return (x + 1; // `)` is missing
+    }
+
+For a missing `)` in an expression, the error comes with a fix-it that `--apply-fixits` applies.",
+        "\
+E0102: 対応しない括弧
+
+開き括弧 `(`、`[`、`{` に対応する閉じ括弧がありません。
+
+    int f(int x) {
+        return (x + 1; // `)` がない
+    }
+
+式の中の `)` が足りない場合は、`--apply-fixits` で適用できる修正の提案が付きます。",
+    ),
+    (
+        "E0103",
+        "\
+E0103: missing semicolon
+
+A statement is not terminated by `;`.
+
+    int main(void) {
+        return 0 // `;` is missing
+    }
+
+For a `return` statement, the error comes with a fix-it that `--apply-fixits` applies.",
+        "\
+E0103: セミコロンがない
+
+文が `;` で終わっていません。
+
+    int main(void) {
+        return 0 // `;` がない
+    }
+
+`return` 文の場合は、`--apply-fixits` で適用できる修正の提案が付きます。",
+    ),
+    (
+        "E0104",
+        "\
+E0104: missing semicolon in a member declaration
+
+A member declaration in a structure is not terminated by `;`.
+
+    struct point {
+        int x
+        int y;
+    };",
+        "\
+E0104: メンバ宣言のセミコロンがない
+
+構造体のメンバの宣言が `;` で終わっていません。
+
+    struct point {
+        int x
+        int y;
+    };",
+    ),
+    (
+        "E0105",
+        "\
+E0105: missing `=` after designator
+
+A designator in an initializer list must be followed by `=`.
+
+    int a[3] = { [1] 5 }; // write `[1] = 5`",
+        "\
+E0105: 指示子の後の `=` がない
+
+初期化子リストの中の指示子の後には `=` が必要です。
+
+    int a[3] = { [1] 5 }; // `[1] = 5` と書く",
+    ),
+    (
+        "E0106",
+        "\
+E0106: missing identifier in declarator
+
+A declaration that needs a name has none.
+
+    int *; // the pointer has no name",
+        "\
+E0106: 宣言子に識別子がない
+
+名前が必要な宣言に名前がありません。
+
+    int *; // ポインタに名前がない",
+    ),
+    (
+        "E0107",
+        "\
+E0107: missing type specifier
+
+A declaration has no type.  Implicit `int` is not supported.
+
+    static x = 1; // write `static int x = 1;`",
+        "\
+E0107: 型指定子がない
+
+宣言に型がありません。暗黙の `int` はサポートされていません。
+
+    static x = 1; // `static int x = 1;` と書く",
+    ),
+    (
+        "E0108",
+        "\
+E0108: `...` without a named parameter
+
+A variadic function needs at least one named parameter before `...`.
+
+    int printf(...); // write `int printf(const char *format, ...);`",
+        "\
+E0108: 名前付きの仮引数のない `...`
+
+可変長引数の関数には `...` の前に名前付きの仮引数が少なくとも 1 つ必要です。
+
+    int printf(...); // `int printf(const char *format, ...);` と書く",
+    ),
+    (
+        "E0109",
+        "\
+E0109: identifier in type name
+
+A type name, as in a cast, cannot declare a name.
+
+    int main(void) {
+        return (int x)1; // write `(int)1`
+    }",
+        "\
+E0109: 型名の中の識別子
+
+キャストの中の型名では名前を宣言できません。
+
+    int main(void) {
+        return (int x)1; // `(int)1` と書く
+    }",
+    ),
+    (
+        "E0201",
+        "\
+E0201: multiple storage classes
+
+A declaration has more than one of `static`, `extern`, `auto` and `register`.
+
+    static extern int x;",
+        "\
+E0201: 複数の記憶域クラス
+
+宣言に `static`、`extern`、`auto`、`register` のうち 2 つ以上があります。
+
+    static extern int x;",
+    ),
+    (
+        "E0202",
+        "\
+E0202: invalid combination of type specifiers
+
+The type specifiers of a declaration do not make a type.
+
+    long char c;
+    struct s int x;",
+        "\
+E0202: 型指定子の不正な組み合わせ
+
+宣言の型指定子を組み合わせても型になりません。
+
+    long char c;
+    struct s int x;",
+    ),
+    (
+        "E0203",
+        "\
+E0203: `long double` is not supported
+
+This compiler does not support `long double`.  Use `double` instead.
+
+    long double x = 1.0;",
+        "\
+E0203: `long double` はサポートされていない
+
+このコンパイラは `long double` をサポートしていません。代わりに `double` を使ってください。
+
+    long double x = 1.0;",
+    ),
+    (
+        "E0204",
+        "\
+E0204: storage class in type name or member
+
+A type name, as in a cast, or a member of a structure cannot have a storage class.
+
+    struct s {
+        static int x;
+    };",
+        "\
+E0204: 型名やメンバの記憶域クラス
+
+キャストの中の型名や構造体のメンバには記憶域クラスを付けられません。
+
+    struct s {
+        static int x;
+    };",
+    ),
+    (
+        "E0205",
+        "\
+E0205: invalid storage class for parameter
+
+The only storage class allowed for a parameter is `register`.
+
+    int f(static int x);",
+        "\
+E0205: 仮引数の不正な記憶域クラス
+
+仮引数に付けられる記憶域クラスは `register` だけです。
+
+    int f(static int x);",
+    ),
+    (
+        "E0206",
+        "\
+E0206: parameter of type `void`
+
+A parameter cannot have type `void`.  `(void)` alone means that the function takes no
+arguments.
+
+    int f(int x, void);",
+        "\
+E0206: `void` 型の仮引数
+
+仮引数は `void` 型を持てません。`(void)` だけを書いた場合は、関数が引数を取らないという意味になります。
+
+    int f(int x, void);",
+    ),
+    (
+        "E0207",
+        "\
+E0207: parameter name omitted
+
+Every parameter of a function definition needs a name.
+
+    int f(int) {
+        return 0;
+    }",
+        "\
+E0207: 仮引数の名前の省略
+
+関数定義の仮引数にはすべて名前が必要です。
+
+    int f(int) {
+        return 0;
+    }",
+    ),
+    (
+        "E0208",
+        "\
+E0208: invalid array size
+
+The size of an array must be a positive integer constant expression.
+
+    int n = 3;
+    int a[n];  // variable length arrays are not supported
+    int b[0];  // zero size arrays are not supported
+    int c[-1];",
+        "\
+E0208: 不正な配列の大きさ
+
+配列の大きさは正の整数定数式でなければなりません。
+
+    int n = 3;
+    int a[n];  // 可変長配列はサポートされていない
+    int b[0];  // 大きさ 0 の配列はサポートされていない
+    int c[-1];",
+    ),
+    (
+        "E0209",
+        "\
+E0209: invalid array element type
+
+The elements of an array must have a complete object type.
+
+    int f[3](void);   // an array of functions
+    struct s a[3];    // `struct s` is not defined yet
+
+Use an array of function pointers, `int (*f[3])(void)`, instead of an array of functions.",
+        "\
+E0209: 不正な配列の要素型
+
+配列の要素は完全なオブジェクト型でなければなりません。
+
+    int f[3](void);   // 関数の配列
+    struct s a[3];    // `struct s` はまだ定義されていない
+
+関数の配列の代わりに関数ポインタの配列 `int (*f[3])(void)` を使ってください。",
+    ),
+    (
+        "E0210",
+        "\
+E0210: invalid return type
+
+A function cannot return an array or a function.  Return a pointer instead.
+
+    int f(void)[3];
+    int g(void)(void);",
+        "\
+E0210: 不正な戻り値の型
+
+関数は配列や関数を返せません。代わりにポインタを返してください。
+
+    int f(void)[3];
+    int g(void)(void);",
+    ),
+    (
+        "E0211",
+        "\
+E0211: incomplete type
+
+A variable or a member has a type whose size is unknown, such as `void` or a structure that
+is declared but not defined.
+
+    struct s;
+    struct s x;
+    void v;",
+        "\
+E0211: 不完全型
+
+変数やメンバの型の大きさがわかりません。`void` や、宣言されただけで定義されていない構造体がこれにあたります。
+
+    struct s;
+    struct s x;
+    void v;",
+    ),
+    (
+        "E0212",
+        "\
+E0212: redefinition
+
+A name or a structure tag is defined twice in the same scope.  The previous definition is
+shown as a note.
+
+    int x = 1;
+    int x = 2;",
+        "\
+E0212: 再定義
+
+同じスコープで名前や構造体のタグが 2 回定義されています。以前の定義が `note:` として表示されます。
+
+    int x = 1;
+    int x = 2;",
+    ),
+    (
+        "E0213",
+        "\
+E0213: conflicting types
+
+A name is declared again with a type different from the previous declaration.
+
+    int f(int x);
+    long f(int x);",
+        "\
+E0213: 矛盾する型
+
+名前が以前の宣言とは異なる型で再び宣言されています。
+
+    int f(int x);
+    long f(int x);",
+    ),
+    (
+        "E0214",
+        "\
+E0214: redeclared as a different kind of symbol
+
+A name declared as a function is declared again as a variable, or the other way round.
+
+    int f(void);
+    int f;",
+        "\
+E0214: 別の種類のシンボルとしての再宣言
+
+関数として宣言された名前が変数として再び宣言されています (またはその逆です)。
+
+    int f(void);
+    int f;",
+    ),
+    (
+        "E0215",
+        "\
+E0215: duplicate member
+
+Two members of a structure have the same name.
+
+    struct point {
+        int x;
+        int x;
+    };",
+        "\
+E0215: メンバの重複
+
+構造体の 2 つのメンバが同じ名前を持っています。
+
+    struct point {
+        int x;
+        int x;
+    };",
+    ),
+    (
+        "E0216",
+        "\
+E0216: structure without members
+
+A structure must have at least one member.
+
+    struct empty {};",
+        "\
+E0216: メンバのない構造体
+
+構造体には少なくとも 1 つのメンバが必要です。
+
+    struct empty {};",
+    ),
+    (
+        "E0217",
+        "\
+E0217: declaration of an anonymous structure
+
+A structure without a tag cannot be referred to later, so it must be defined where it
+appears.
+
+    struct *p;",
+        "\
+E0217: 無名構造体の宣言
+
+タグのない構造体は後から参照できないので、現れた場所で定義しなければなりません。
+
+    struct *p;",
+    ),
+    (
+        "E0218",
+        "\
+E0218: structure defined in a function
+
+This compiler supports structure definitions only at file scope.
+
+    int main(void) {
+        struct point { int x, y; } p;
+        return 0;
+    }
+
+Move the definition out of the function.",
+        "\
+E0218: 関数内での構造体の定義
+
+このコンパイラはファイルスコープでの構造体の定義だけをサポートしています。
+
+    int main(void) {
+        struct point { int x, y; } p;
+        return 0;
+    }
+
+定義を関数の外に移してください。",
+    ),
+    (
+        "E0219",
+        "\
+E0219: static function declared in a block
+
+A function declared in a block cannot be `static`.
+
+    int main(void) {
+        static int f(void);
+        return f();
+    }
+
+Declare the function at file scope.",
+        "\
+E0219: ブロック内で宣言された static 関数
+
+ブロック内で宣言された関数は `static` にできません。
+
+    int main(void) {
+        static int f(void);
+        return f();
+    }
+
+関数はファイルスコープで宣言してください。",
+    ),
+    (
+        "E0220",
+        "\
+E0220: initialized `extern` declaration in a block
+
+An `extern` declaration in a block refers to a variable defined elsewhere, so it cannot have
+an initializer.
+
+    int main(void) {
+        extern int x = 1;
+        return x;
+    }",
+        "\
+E0220: 初期化子のあるブロック内の `extern` 宣言
+
+ブロック内の `extern` 宣言は他の場所で定義された変数を参照するので、初期化子を持てません。
+
+    int main(void) {
+        extern int x = 1;
+        return x;
+    }",
+    ),
+    (
+        "E0221",
+        "\
+E0221: initializer for a function
+
+Only variables can be initialized.
+
+    int f(void) = 0;",
+        "\
+E0221: 関数の初期化子
+
+初期化できるのは変数だけです。
+
+    int f(void) = 0;",
+    ),
+    (
+        "E0222",
+        "\
+E0222: conflicting linkage
+
+A variable is declared with internal linkage (`static`) and with external linkage (neither
+`static` nor `extern`) in the same file.  A declaration with `extern` takes the linkage of the
+previous one.
+
+    int x;
+    static int x;",
+        "\
+E0222: 矛盾するリンケージ
+
+同じファイルで、変数が内部リンケージ (`static`) と外部リンケージ (`static` も `extern` もなし) で宣言されています。`extern` を付けた宣言は以前の宣言のリンケージを引き継ぎます。
+
+    int x;
+    static int x;",
+    ),
+    (
+        "E0301",
+        "\
+E0301: invalid array designator
+
+The index of an array designator must be an integer constant expression within the bounds of
+the array.
+
+    int a[3] = { [3] = 1 };
+    int b[3] = { [-1] = 1 };",
+        "\
+E0301: 不正な配列指示子
+
+配列指示子の添字は、配列の範囲内の整数定数式でなければなりません。
+
+    int a[3] = { [3] = 1 };
+    int b[3] = { [-1] = 1 };",
+    ),
+    (
+        "E0302",
+        "\
+E0302: designator for a different type
+
+An array designator `[i]` initializes an array, and a field designator `.name` initializes a
+structure.  Neither can initialize a scalar.
+
+    struct point { int x, y; };
+    struct point p = { [0] = 1 };
+    int x = { .y = 1 };",
+        "\
+E0302: 型に合わない指示子
+
+配列指示子 `[i]` は配列を、フィールド指示子 `.name` は構造体を初期化します。どちらもスカラーは初期化できません。
+
+    struct point { int x, y; };
+    struct point p = { [0] = 1 };
+    int x = { .y = 1 };",
+    ),
+    (
+        "E0303",
+        "\
+E0303: unknown field in designator
+
+A field designator names a member that the structure does not have.
+
+    struct point { int x, y; };
+    struct point p = { .z = 1 };",
+        "\
+E0303: 指示子の中の不明なフィールド
+
+フィールド指示子が、構造体にないメンバを指定しています。
+
+    struct point { int x, y; };
+    struct point p = { .z = 1 };",
+    ),
+    (
+        "E0304",
+        "\
+E0304: empty scalar initializer
+
+A scalar initialized with braces needs a value in them.
+
+    int x = {};",
+        "\
+E0304: 空のスカラー初期化子
+
+波括弧で初期化するスカラーには、その中に値が必要です。
+
+    int x = {};",
+    ),
+    (
+        "E0305",
+        "\
+E0305: excess elements in initializer
+
+An initializer list has more elements than the object has.
+
+    int a[2] = { 1, 2, 3 };
+    int x = { 1, 2 };",
+        "\
+E0305: 初期化子の要素が多すぎる
+
+初期化子リストの要素がオブジェクトの要素より多くなっています。
+
+    int a[2] = { 1, 2, 3 };
+    int x = { 1, 2 };",
+    ),
+    (
+        "E0306",
+        "\
+E0306: string too long for array
+
+A string literal initializing a `char` array has more characters than the array, not counting
+the terminating null character.
+
+    char s[3] = \"abcd\";",
+        "\
+E0306: 配列に対して長すぎる文字列
+
+`char` 配列を初期化する文字列リテラルの文字数が、終端のヌル文字を除いても配列の大きさを超えています。
+
+    char s[3] = \"abcd\";",
+    ),
+    (
+        "E0307",
+        "\
+E0307: aggregate initialized without braces
+
+An array or a structure must be initialized with an initializer list in braces (or, for a
+`char` array, with a string literal), unless it is initialized with another structure of the
+same type.
+
+    int a[2] = 1; // write `{ 1 }`",
+        "\
+E0307: 波括弧のない集成体の初期化
+
+配列や構造体は、同じ型の構造体で初期化する場合を除いて、波括弧で囲んだ初期化子リスト (`char` 配列の場合は文字列リテラル) で初期化しなければなりません。
+
+    int a[2] = 1; // `{ 1 }` と書く",
+    ),
+    (
+        "E0308",
+        "\
+E0308: non-constant initializer
+
+A variable with static storage duration must be initialized with constant expressions.
+
+    int x = 1;
+    int y = x + 1;",
+        "\
+E0308: 定数でない初期化子
+
+静的記憶域期間を持つ変数は定数式で初期化しなければなりません。
+
+    int x = 1;
+    int y = x + 1;",
+    ),
+    (
+        "E0401",
+        "\
+E0401: use of undeclared identifier
+
+A name is used but not declared in any enclosing scope.
+
+    int main(void) {
+        return x; // `x` is not declared
+    }
+
+Declare the variable before using it.  A call to an undeclared function declares it
+implicitly as returning `int`.",
+        "\
+E0401: 宣言されていない識別子の使用
+
+名前が使われていますが、それを囲むどのスコープでも宣言されていません。
+
+    int main(void) {
+        return x; // `x` は宣言されていない
+    }
+
+変数は使う前に宣言してください。宣言されていない関数の呼び出しは、`int` を返す関数として暗黙に宣言されます。",
+    ),
+    (
+        "E0402",
+        "\
+E0402: incompatible types
+
+The value assigned, used to initialize, passed or returned cannot be converted to the type of
+the destination.  Arithmetic types convert to each other, and a pointer converts to a pointer
+to a compatible type or to `void` which keeps the `const` and `volatile` qualifiers of the
+pointed-to type.
+
+    struct point { int x, y; };
+    struct point p;
+    const int c = 1;
+
+    int main(void) {
+        int x = p;
+        long *q = &x;
+        int *r = &c;
+        return *q;
+    }",
+        "\
+E0402: 互換性のない型
+
+代入、初期化、引数、`return` の値が、代入先の型に変換できません。算術型どうしは相互に変換でき、ポインタは互換な型へのポインタか `void` へのポインタに変換できます。ただし、指す先の型の `const` と `volatile` 修飾子は外せません。
+
+    struct point { int x, y; };
+    struct point p;
+    const int c = 1;
+
+    int main(void) {
+        int x = p;
+        long *q = &x;
+        int *r = &c;
+        return *q;
+    }",
+    ),
+    (
+        "E0403",
+        "\
+E0403: expression is not assignable
+
+The left operand of an assignment must be a modifiable lvalue.  A whole array cannot be
+assigned.
+
+    int main(void) {
+        int a[2];
+        int b[2];
+        a = b;
+        1 = 2;
+        return 0;
+    }",
+        "\
+E0403: 代入できない式
+
+代入の左辺は変更可能な左辺値でなければなりません。配列全体には代入できません。
+
+    int main(void) {
+        int a[2];
+        int b[2];
+        a = b;
+        1 = 2;
+        return 0;
+    }",
+    ),
+    (
+        "E0404",
+        "\
+E0404: assignment to `const`
+
+An object declared `const` cannot be modified after it is initialized.
+Nor can a structure with a `const` member be assigned as a whole.
+
+    int main(void) {
+        const int x = 1;
+        x = 2;
+        return x;
+    }",
+        "\
+E0404: `const` への代入
+
+`const` として宣言されたオブジェクトは、初期化した後で変更できません。
+`const` のメンバを持つ構造体も、全体に代入することはできません。
+
+    int main(void) {
+        const int x = 1;
+        x = 2;
+        return x;
+    }",
+    ),
+    (
+        "E0405",
+        "\
+E0405: expression is not an lvalue
+
+The operation needs an object that has an address, but the operand is a value.
+
+    int main(void) {
+        int *p = &1;
+        return *p;
+    }",
+        "\
+E0405: 左辺値でない式
+
+アドレスを持つオブジェクトが必要な演算のオペランドが、値になっています。
+
+    int main(void) {
+        int *p = &1;
+        return *p;
+    }",
+    ),
+    (
+        "E0406",
+        "\
+E0406: invalid operands to binary operator
+
+The types of the operands are not allowed for the operator.  For example, `%` needs integers,
+and two pointers can only be subtracted or compared.
+
+    int main(void) {
+        double d = 1.5 % 2;
+        int *p;
+        int *q;
+        p + q;
+        return 0;
+    }",
+        "\
+E0406: 二項演算子の不正なオペランド
+
+オペランドの型がその演算子には使えません。たとえば `%` には整数が必要で、2 つのポインタどうしは引き算と比較しかできません。
+
+    int main(void) {
+        double d = 1.5 % 2;
+        int *p;
+        int *q;
+        p + q;
+        return 0;
+    }",
+    ),
+    (
+        "E0407",
+        "\
+E0407: invalid operand to unary operator
+
+The type of the operand is not allowed for the operator.
+
+    int main(void) {
+        int *p;
+        return -p;
+    }",
+        "\
+E0407: 単項演算子の不正なオペランド
+
+オペランドの型がその演算子には使えません。
+
+    int main(void) {
+        int *p;
+        return -p;
+    }",
+    ),
+    (
+        "E0408",
+        "\
+E0408: invalid indirection
+
+Only a pointer to an object can be dereferenced with `*`.
+
+    int main(void) {
+        void *p;
+        *p;
+        return *1;
+    }
+
+Cast a `void *` to a pointer to the type of the object first.",
+        "\
+E0408: 不正な間接参照
+
+`*` で間接参照できるのはオブジェクトへのポインタだけです。
+
+    int main(void) {
+        void *p;
+        *p;
+        return *1;
+    }
+
+`void *` は先にオブジェクトの型へのポインタにキャストしてください。",
+    ),
+    (
+        "E0409",
+        "\
+E0409: invalid cast
+
+The type cannot be converted to the other by a cast.  A structure cannot be cast, and a
+floating value cannot be cast to a pointer.
+
+    struct point { int x, y; } p;
+
+    int main(void) {
+        return (int)p;
+    }",
+        "\
+E0409: 不正なキャスト
+
+その型はキャストによって他方の型に変換できません。構造体はキャストできず、浮動小数点数はポインタにキャストできません。
+
+    struct point { int x, y; } p;
+
+    int main(void) {
+        return (int)p;
+    }",
+    ),
+    (
+        "E0410",
+        "\
+E0410: cast to a non-scalar type
+
+A cast can only convert to `void`, an arithmetic type or a pointer type.
+
+    struct point { int x, y; };
+
+    int main(void) {
+        (struct point)1;
+        return 0;
+    }",
+        "\
+E0410: スカラーでない型へのキャスト
+
+キャストで変換できるのは `void`、算術型、ポインタ型だけです。
+
+    struct point { int x, y; };
+
+    int main(void) {
+        (struct point)1;
+        return 0;
+    }",
+    ),
+    (
+        "E0411",
+        "\
+E0411: called object is not a function
+
+Only a function or a pointer to a function can be called.
+
+    int main(void) {
+        int x;
+        return x(1);
+    }",
+        "\
+E0411: 呼び出されたオブジェクトが関数ではない
+
+呼び出せるのは関数か関数ポインタだけです。
+
+    int main(void) {
+        int x;
+        return x(1);
+    }",
+    ),
+    (
+        "E0412",
+        "\
+E0412: wrong number of arguments
+
+A call passes a different number of arguments from the parameters of the prototype.  A
+variadic function takes at least as many arguments as its named parameters.
+
+    int add(int a, int b);
+
+    int main(void) {
+        return add(1);
+    }",
+        "\
+E0412: 引数の数の誤り
+
+呼び出しの引数の数が、プロトタイプの仮引数の数と異なります。可変長引数の関数は、名前付きの仮引数以上の数の引数を取ります。
+
+    int add(int a, int b);
+
+    int main(void) {
+        return add(1);
+    }",
+    ),
+    (
+        "E0413",
+        "\
+E0413: `void` argument
+
+An expression of type `void` has no value, so it cannot be passed as an argument.
+
+    void f(void);
+    int printf(const char *format, ...);
+
+    int main(void) {
+        printf(\"%d\", f());
+        return 0;
+    }",
+        "\
+E0413: `void` の引数
+
+`void` 型の式は値を持たないので、引数として渡せません。
+
+    void f(void);
+    int printf(const char *format, ...);
+
+    int main(void) {
+        printf(\"%d\", f());
+        return 0;
+    }",
+    ),
+    (
+        "E0414",
+        "\
+E0414: invalid subscript
+
+In `a[i]`, one operand must be an array or a pointer and the other an integer.
+
+    int main(void) {
+        int x;
+        int a[3];
+        return x[0] + a[1.0];
+    }",
+        "\
+E0414: 不正な添字
+
+`a[i]` では、一方のオペランドが配列かポインタで、もう一方が整数でなければなりません。
+
+    int main(void) {
+        int x;
+        int a[3];
+        return x[0] + a[1.0];
+    }",
+    ),
+    (
+        "E0415",
+        "\
+E0415: invalid member access
+
+The left operand of `.` must be a structure that has the member.
+
+    struct point { int x, y; } p;
+
+    int main(void) {
+        int n;
+        return p.z + n.x;
+    }",
+        "\
+E0415: 不正なメンバアクセス
+
+`.` の左辺はそのメンバを持つ構造体でなければなりません。
+
+    struct point { int x, y; } p;
+
+    int main(void) {
+        int n;
+        return p.z + n.x;
+    }",
+    ),
+    (
+        "E0501",
+        "\
+E0501: value returned from a `void` function
+
+A function returning `void` cannot return a value.
+
+    void f(void) {
+        return 1;
+    }",
+        "\
+E0501: `void` 関数からの値の返却
+
+`void` を返す関数は値を返せません。
+
+    void f(void) {
+        return 1;
+    }",
+    ),
+    (
+        "E0502",
+        "\
+E0502: missing return value
+
+A function with a non-void return type must return a value with `return`.
+
+    int f(void) {
+        return;
+    }",
+        "\
+E0502: 戻り値がない
+
+戻り値の型が `void` でない関数は、`return` で値を返さなければなりません。
+
+    int f(void) {
+        return;
+    }",
+    ),
+    (
+        "W0001",
+        "\
+W0001: unreachable code (-Wunreachable-code)
+
+A statement follows a `return` in the same block, so it is never executed.
+
+    int f(void) {
+        return 1;
+        f();
+    }",
+        "\
+W0001: 到達できないコード (-Wunreachable-code)
+
+同じブロックの `return` の後に文があるので、その文は決して実行されません。
+
+    int f(void) {
+        return 1;
+        f();
+    }",
+    ),
+    (
+        "W0002",
+        "\
+W0002: unused variable (-Wunused-variable)
+
+A local variable is declared but never used.
+
+    int main(void) {
+        int x = 1;
+        return 0;
+    }",
+        "\
+W0002: 使われていない変数 (-Wunused-variable)
+
+ローカル変数が宣言されていますが、一度も使われていません。
+
+    int main(void) {
+        int x = 1;
+        return 0;
+    }",
+    ),
+    (
+        "W0003",
+        "\
+W0003: division by zero (-Wdivision-by-zero)
+
+The right operand of an integer `/` or `%` is a constant zero.  The behavior is undefined.
+
+    int main(void) {
+        return 1 / 0;
+    }",
+        "\
+W0003: ゼロによる除算 (-Wdivision-by-zero)
+
+整数の `/` や `%` の右オペランドが定数のゼロです。動作は未定義です。
+
+    int main(void) {
+        return 1 / 0;
+    }",
+    ),
+    (
+        "W0004",
+        "\
+W0004: signed integer overflow (-Woverflow)
+
+A constant expression overflows its signed integer type.  The behavior is undefined.
+
+    int x = 2147483647 + 1;",
+        "\
+W0004: 符号付き整数のオーバーフロー (-Woverflow)
+
+定数式がその符号付き整数型の範囲を超えます。動作は未定義です。
+
+    int x = 2147483647 + 1;",
+    ),
+    (
+        "W0005",
+        "\
+W0005: missing return statement (-Wreturn-type)
+
+The end of a non-void function can be reached without `return`.  Using the value of such a
+call is undefined.  `main` is exempt, since reaching its end returns 0.
+
+    int f(int x) {
+        x = x + 1;
+    }",
+        "\
+W0005: return 文がない (-Wreturn-type)
+
+`void` でない関数の終わりに `return` なしで到達できます。そのような呼び出しの値を使うと動作は未定義です。`main` は終わりに到達すると 0 を返すので対象外です。
+
+    int f(int x) {
+        x = x + 1;
+    }",
+    ),
+];
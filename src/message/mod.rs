@@ -0,0 +1,1020 @@
+//! the catalogue of diagnostic messages.  every kind of diagnostic has a stable code (`E0401`
+//! for an error, `W0002` for a warning), an English and a Japanese message, and a longer
+//! explanation printed by `--explain`.
+//!
+//! in the templates of the messages, `{0}`, `{1}`, ... stand for the arguments of the kind.
+
+mod explanations;
+
+use types::Type;
+use warning::Warning;
+
+/// the language of diagnostics (`--lang=` or `LANG`)
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Language {
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub fn from_name(name: &str) -> Option<Language> {
+        match name {
+            "en" => Some(Language::English),
+            "ja" => Some(Language::Japanese),
+            _ => None,
+        }
+    }
+
+    /// the language of a locale name such as `ja_JP.UTF-8`
+    pub fn from_locale(locale: &str) -> Language {
+        if locale.starts_with("ja") {
+            Language::Japanese
+        } else {
+            Language::English
+        }
+    }
+}
+
+/// the words filled in the messages
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Term {
+    Parens,
+    Brackets,
+    Braces,
+    PrimaryExpression,
+    ParameterList,
+    FunctionCall,
+    Declarator,
+    Cast,
+    Designator,
+    ArraySubscript,
+    ArraySize,
+    InitializerList,
+    JumpStatement,
+    ExpressionStatement,
+    Expression,
+    FieldDesignator,
+    TypeSpecifier,
+    SemicolonAfterDeclaration,
+    CompoundStatement,
+    MemberName,
+    Division,
+    Remainder,
+
+    /// a token, quoted
+    Token(String),
+}
+
+impl Term {
+    fn name(&self, language: Language) -> String {
+        let (en, ja) = match self {
+            Term::Parens => ("parens", "括弧"),
+            Term::Brackets => ("brackets", "角括弧"),
+            Term::Braces => ("braces", "波括弧"),
+            Term::PrimaryExpression => ("primary expression", "一次式"),
+            Term::ParameterList => ("parameter list", "仮引数リスト"),
+            Term::FunctionCall => ("function call", "関数呼び出し"),
+            Term::Declarator => ("declarator", "宣言子"),
+            Term::Cast => ("cast", "キャスト"),
+            Term::Designator => ("designator", "指示子"),
+            Term::ArraySubscript => ("array subscript", "配列の添字"),
+            Term::ArraySize => ("array size", "配列の大きさ"),
+            Term::InitializerList => ("initializer list", "初期化子リスト"),
+            Term::JumpStatement => ("jump statement", "ジャンプ文"),
+            Term::ExpressionStatement => ("expression statement", "式文"),
+            Term::Expression => ("expression", "式"),
+            Term::FieldDesignator => ("a field designator", "フィールド指示子"),
+            Term::TypeSpecifier => ("type specifier", "型指定子"),
+            Term::SemicolonAfterDeclaration => ("`;` after declaration", "宣言の後の `;`"),
+            Term::CompoundStatement => ("compound statement (`{`)", "複合文 (`{`)"),
+            Term::MemberName => ("member name after `.`", "`.` の後のメンバ名"),
+            Term::Division => ("division", "除算"),
+            Term::Remainder => ("remainder", "剰余"),
+            Term::Token(token) => return format!("`{}`", token),
+        };
+        pick(language, en, ja).to_owned()
+    }
+}
+
+/// the conversion of a value to another type as if by assignment
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Conversion {
+    Assignment,
+    Initialization,
+    Return,
+
+    /// the argument at the position (from 1) of a call of the function
+    Argument(usize, String),
+}
+
+/// the kinds of diagnostics, with the parts of the messages that vary
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DiagnosticKind {
+    // lexical errors
+    InvalidCharacter(char),
+    InvalidNumber(String),
+    UnterminatedString,
+    UnterminatedComment,
+    UnknownEscape(char),
+    IntegerTooLarge(String),
+    IntegerTooLargeForType(String),
+
+    // syntax errors
+    /// what is expected and the token found instead, which is `None` at the end of the file
+    Expected(Term, Option<String>),
+
+    /// the delimiter and the construct it encloses
+    Unclosed(Term, Term),
+    MissingSemicolon(Term),
+    MissingSemicolonInStruct,
+    MissingEqualsAfterDesignator,
+    MissingIdentifier,
+    MissingTypeSpecifier,
+    UnnamedParameterBeforeEllipsis,
+    IdentifierInTypeName(String),
+
+    // declarations
+    MultipleStorageClasses,
+    InvalidTypeSpecifiers(String),
+    StructAfterStruct,
+    StructWithSpecifier(String),
+    LongDouble,
+    LongDoubleLiteral(String),
+    StorageClassInTypeName,
+    InvalidParameterStorageClass,
+    VoidParameter,
+    ParameterNameOmitted(String),
+    NegativeArraySize,
+    ZeroSizeArray,
+    NonConstantArraySize,
+    ArrayOfFunctions,
+    IncompleteElementType(Type),
+    FunctionReturningArray,
+    FunctionReturningFunction,
+    IncompleteVariable(String, Type),
+    IncompleteField(String, Type),
+    Redefinition(String),
+    ConflictingTypes(String),
+    DifferentKindOfSymbol(String),
+    DuplicateMember(String),
+    EmptyStruct,
+    AnonymousStructDeclaration,
+    StructInFunction,
+    StaticBlockFunction(String),
+    ExternWithInitializer(String),
+    NonVariableInitializer(String),
+    StaticFollowsNonStatic(String),
+    NonStaticFollowsStatic(String),
+
+    // initializers
+    NegativeDesignator,
+    DesignatorOutOfBounds(usize),
+    NonConstantDesignator,
+    ArrayDesignatorMismatch(Type),
+    FieldDesignatorMismatch(String, Type),
+    DesignatorForScalar(Type),
+    UnknownField(String),
+    EmptyScalarInitializer,
+    ExcessScalarElements,
+    ExcessElements(Type),
+    StringTooLong,
+    BracesRequired(Type),
+    NonConstantInitializer,
+
+    // expressions
+    UndeclaredIdentifier(String),
+
+    /// the conversion, the type converted to, and the type converted from
+    IncompatibleTypes(Conversion, Type, Type),
+    NotAssignable,
+    ArrayNotAssignable,
+    ConstVariable(String),
+    ConstExpression,
+
+    /// the const-qualified member of the assigned structure
+    ConstMember(String),
+    AddressOfRvalue,
+    MemberOfRvalue,
+    InvalidOperands(Type, Type),
+    InvalidRemainderOperands(Type),
+    InvalidUnaryOperand(Type),
+    NonPointerIndirection(Type),
+    VoidIndirection,
+    InvalidCast(Type, Type),
+    NonScalarCast(Type),
+    NotFunction(Type),
+    NotCallable(Type),
+    TooManyArguments(String),
+    TooFewArguments(String),
+    VoidArgument,
+    NonIntegerSubscript,
+    NotSubscriptable,
+
+    /// the member and the struct
+    NoMember(String, String),
+    NotStruct,
+
+    // statements
+    VoidReturnValue,
+    MissingReturnValue,
+
+    // warnings
+    UnreachableCode,
+    UnusedVariable(String),
+
+    /// `Term::Division` or `Term::Remainder`
+    DivisionByZero(Term),
+    Overflow,
+    MissingReturn(String),
+}
+
+impl DiagnosticKind {
+    /// the stable code identifying the kind, such as `E0401`
+    pub fn code(&self) -> &'static str {
+        use self::DiagnosticKind::*;
+        match self {
+            InvalidCharacter(_) => "E0001",
+            InvalidNumber(_) => "E0002",
+            UnterminatedString => "E0003",
+            UnterminatedComment => "E0004",
+            UnknownEscape(_) => "E0005",
+            IntegerTooLarge(_) | IntegerTooLargeForType(_) => "E0006",
+            Expected(Term::TypeSpecifier, _) | MissingTypeSpecifier => "E0107",
+            Expected(..) => "E0101",
+            Unclosed(..) => "E0102",
+            MissingSemicolon(_) => "E0103",
+            MissingSemicolonInStruct => "E0104",
+            MissingEqualsAfterDesignator => "E0105",
+            MissingIdentifier => "E0106",
+            UnnamedParameterBeforeEllipsis => "E0108",
+            IdentifierInTypeName(_) => "E0109",
+            MultipleStorageClasses => "E0201",
+            InvalidTypeSpecifiers(_) | StructAfterStruct | StructWithSpecifier(_) => "E0202",
+            LongDouble | LongDoubleLiteral(_) => "E0203",
+            StorageClassInTypeName => "E0204",
+            InvalidParameterStorageClass => "E0205",
+            VoidParameter => "E0206",
+            ParameterNameOmitted(_) => "E0207",
+            NegativeArraySize | ZeroSizeArray | NonConstantArraySize => "E0208",
+            ArrayOfFunctions | IncompleteElementType(_) => "E0209",
+            FunctionReturningArray | FunctionReturningFunction => "E0210",
+            IncompleteVariable(..) | IncompleteField(..) => "E0211",
+            Redefinition(_) => "E0212",
+            ConflictingTypes(_) => "E0213",
+            DifferentKindOfSymbol(_) => "E0214",
+            DuplicateMember(_) => "E0215",
+            EmptyStruct => "E0216",
+            AnonymousStructDeclaration => "E0217",
+            StructInFunction => "E0218",
+            StaticBlockFunction(_) => "E0219",
+            ExternWithInitializer(_) => "E0220",
+            NonVariableInitializer(_) => "E0221",
+            StaticFollowsNonStatic(_) | NonStaticFollowsStatic(_) => "E0222",
+            NegativeDesignator | DesignatorOutOfBounds(_) | NonConstantDesignator => "E0301",
+            ArrayDesignatorMismatch(_) | FieldDesignatorMismatch(..) | DesignatorForScalar(_) => {
+                "E0302"
+            }
+            UnknownField(_) => "E0303",
+            EmptyScalarInitializer => "E0304",
+            ExcessScalarElements | ExcessElements(_) => "E0305",
+            StringTooLong => "E0306",
+            BracesRequired(_) => "E0307",
+            NonConstantInitializer => "E0308",
+            UndeclaredIdentifier(_) => "E0401",
+            IncompatibleTypes(..) => "E0402",
+            NotAssignable | ArrayNotAssignable => "E0403",
+            ConstVariable(_) | ConstExpression | ConstMember(_) => "E0404",
+            AddressOfRvalue | MemberOfRvalue => "E0405",
+            InvalidOperands(..) | InvalidRemainderOperands(_) => "E0406",
+            InvalidUnaryOperand(_) => "E0407",
+            NonPointerIndirection(_) | VoidIndirection => "E0408",
+            InvalidCast(..) => "E0409",
+            NonScalarCast(_) => "E0410",
+            NotFunction(_) | NotCallable(_) => "E0411",
+            TooManyArguments(_) | TooFewArguments(_) => "E0412",
+            VoidArgument => "E0413",
+            NonIntegerSubscript | NotSubscriptable => "E0414",
+            NoMember(..) | NotStruct => "E0415",
+            VoidReturnValue => "E0501",
+            MissingReturnValue => "E0502",
+            UnreachableCode => "W0001",
+            UnusedVariable(_) => "W0002",
+            DivisionByZero(_) => "W0003",
+            Overflow => "W0004",
+            MissingReturn(_) => "W0005",
+        }
+    }
+
+    /// the warning this kind is, or `None` for an error
+    pub fn warning(&self) -> Option<Warning> {
+        match self {
+            DiagnosticKind::UnreachableCode => Some(Warning::UnreachableCode),
+            DiagnosticKind::UnusedVariable(_) => Some(Warning::UnusedVariable),
+            DiagnosticKind::DivisionByZero(_) => Some(Warning::DivisionByZero),
+            DiagnosticKind::Overflow => Some(Warning::Overflow),
+            DiagnosticKind::MissingReturn(_) => Some(Warning::ReturnType),
+            _ => None,
+        }
+    }
+
+    /// the message in `language`
+    pub fn message(&self, language: Language) -> String {
+        use self::DiagnosticKind::*;
+        let term = |term: &Term| term.name(language);
+        let (en, ja, args): (&str, &str, Vec<String>) = match self {
+            InvalidCharacter(ch) => (
+                "invalid character `{0}` in source",
+                "ソースコード中の不正な文字 `{0}`",
+                vec![ch.to_string()],
+            ),
+            InvalidNumber(number) => (
+                "invalid number `{0}`",
+                "不正な数値 `{0}`",
+                vec![number.clone()],
+            ),
+            UnterminatedString => (
+                "missing terminating `\"` character",
+                "文字列の終わりの `\"` がありません",
+                vec![],
+            ),
+            UnterminatedComment => (
+                "unterminated comment",
+                "コメントが閉じられていません",
+                vec![],
+            ),
+            UnknownEscape(ch) => (
+                "unknown escape sequence `\\{0}`",
+                "不明なエスケープシーケンス `\\{0}`",
+                vec![ch.to_string()],
+            ),
+            IntegerTooLarge(literal) => (
+                "integer constant `{0}` is too large",
+                "整数定数 `{0}` が大きすぎます",
+                vec![literal.clone()],
+            ),
+            IntegerTooLargeForType(literal) => (
+                "integer constant `{0}` is too large for its type",
+                "整数定数 `{0}` はその型で表すには大きすぎます",
+                vec![literal.clone()],
+            ),
+            Expected(what, Some(found)) => (
+                "expected {0}, found `{1}`",
+                "{0} が必要ですが `{1}` がありました",
+                vec![term(what), found.clone()],
+            ),
+            Expected(what, None) => (
+                "expected {0}, found end of file",
+                "{0} が必要ですがファイルが終わりました",
+                vec![term(what)],
+            ),
+            Unclosed(delimiter, what) => (
+                "no matching {0} for {1}.",
+                "{1}の{0}が閉じられていません。",
+                vec![term(delimiter), term(what)],
+            ),
+            MissingSemicolon(what) => (
+                "missing semicolon after {0}.",
+                "{0}の後にセミコロンがありません。",
+                vec![term(what)],
+            ),
+            MissingSemicolonInStruct => (
+                "expected `;` at end of declaration list.",
+                "宣言リストの終わりに `;` が必要です。",
+                vec![],
+            ),
+            MissingEqualsAfterDesignator => (
+                "expected `=` after designator.",
+                "指示子の後に `=` が必要です。",
+                vec![],
+            ),
+            MissingIdentifier => (
+                "expected identifier in declarator",
+                "宣言子に識別子が必要です",
+                vec![],
+            ),
+            MissingTypeSpecifier => ("expected type specifier", "型指定子が必要です", vec![]),
+            UnnamedParameterBeforeEllipsis => (
+                "ISO C requires a named parameter before `...`",
+                "ISO C では `...` の前に名前付きの仮引数が必要です",
+                vec![],
+            ),
+            IdentifierInTypeName(name) => (
+                "unexpected identifier `{0}` in type name",
+                "型名の中に予期しない識別子 `{0}` があります",
+                vec![name.clone()],
+            ),
+            MultipleStorageClasses => (
+                "multiple storage classes in declaration specifiers",
+                "宣言指定子に複数の記憶域クラスがあります",
+                vec![],
+            ),
+            InvalidTypeSpecifiers(specifiers) => (
+                "invalid combination of type specifiers: `{0}`",
+                "型指定子の組み合わせが不正です: `{0}`",
+                vec![specifiers.clone()],
+            ),
+            StructAfterStruct => (
+                "cannot combine `struct` with previous `struct`",
+                "`struct` を前の `struct` と組み合わせることはできません",
+                vec![],
+            ),
+            StructWithSpecifier(specifier) => (
+                "cannot combine `struct` with `{0}`",
+                "`struct` を `{0}` と組み合わせることはできません",
+                vec![specifier.clone()],
+            ),
+            LongDouble => (
+                "`long double` is not supported",
+                "`long double` はサポートされていません",
+                vec![],
+            ),
+            LongDoubleLiteral(literal) => (
+                "`long double` is not supported: `{0}`",
+                "`long double` はサポートされていません: `{0}`",
+                vec![literal.clone()],
+            ),
+            StorageClassInTypeName => (
+                "type name does not allow storage class to be specified",
+                "型名には記憶域クラスを指定できません",
+                vec![],
+            ),
+            InvalidParameterStorageClass => (
+                "invalid storage class for parameter",
+                "仮引数に不正な記憶域クラスが指定されています",
+                vec![],
+            ),
+            VoidParameter => (
+                "parameter has incomplete type `void`",
+                "仮引数が不完全型 `void` を持っています",
+                vec![],
+            ),
+            ParameterNameOmitted(function) => (
+                "parameter name omitted in definition of `{0}`",
+                "`{0}` の定義で仮引数の名前が省略されています",
+                vec![function.clone()],
+            ),
+            NegativeArraySize => ("array has negative size", "配列の大きさが負です", vec![]),
+            ZeroSizeArray => (
+                "zero size arrays are not supported",
+                "大きさ 0 の配列はサポートされていません",
+                vec![],
+            ),
+            NonConstantArraySize => (
+                "size of array is not an integer constant expression",
+                "配列の大きさが整数定数式ではありません",
+                vec![],
+            ),
+            ArrayOfFunctions => (
+                "array of functions is not allowed",
+                "関数の配列は使えません",
+                vec![],
+            ),
+            IncompleteElementType(ty) => (
+                "array has incomplete element type {0}",
+                "配列の要素型 {0} が不完全型です",
+                vec![ty.to_string()],
+            ),
+            FunctionReturningArray => (
+                "function cannot return array type",
+                "関数は配列型を返せません",
+                vec![],
+            ),
+            FunctionReturningFunction => (
+                "function cannot return function type",
+                "関数は関数型を返せません",
+                vec![],
+            ),
+            IncompleteVariable(name, ty) => (
+                "variable `{0}` has incomplete type {1}",
+                "変数 `{0}` が不完全型 {1} を持っています",
+                vec![name.clone(), ty.to_string()],
+            ),
+            IncompleteField(name, ty) => (
+                "field `{0}` has incomplete type {1}",
+                "フィールド `{0}` が不完全型 {1} を持っています",
+                vec![name.clone(), ty.to_string()],
+            ),
+            Redefinition(name) => (
+                "redefinition of `{0}`",
+                "`{0}` の再定義です",
+                vec![name.clone()],
+            ),
+            ConflictingTypes(name) => (
+                "conflicting types for `{0}`",
+                "`{0}` の型が以前の宣言と矛盾しています",
+                vec![name.clone()],
+            ),
+            DifferentKindOfSymbol(name) => (
+                "`{0}` redeclared as a different kind of symbol",
+                "`{0}` が別の種類のシンボルとして再宣言されています",
+                vec![name.clone()],
+            ),
+            DuplicateMember(name) => (
+                "duplicate member `{0}`",
+                "メンバ `{0}` が重複しています",
+                vec![name.clone()],
+            ),
+            EmptyStruct => (
+                "struct without members is not supported",
+                "メンバのない構造体はサポートされていません",
+                vec![],
+            ),
+            AnonymousStructDeclaration => (
+                "declaration of anonymous struct must be a definition",
+                "無名構造体の宣言は定義でなければなりません",
+                vec![],
+            ),
+            StructInFunction => (
+                "definition of a structure in a function is not supported",
+                "関数内での構造体の定義はサポートされていません",
+                vec![],
+            ),
+            StaticBlockFunction(name) => (
+                "function `{0}` declared in block scope cannot be static",
+                "ブロックスコープで宣言された関数 `{0}` は static にできません",
+                vec![name.clone()],
+            ),
+            ExternWithInitializer(name) => (
+                "`{0}` declared extern in block scope has an initializer",
+                "ブロックスコープで extern 宣言された `{0}` に初期化子があります",
+                vec![name.clone()],
+            ),
+            NonVariableInitializer(name) => (
+                "illegal initializer (only variables can be initialized): `{0}`",
+                "不正な初期化子です (初期化できるのは変数だけです): `{0}`",
+                vec![name.clone()],
+            ),
+            StaticFollowsNonStatic(name) => (
+                "static declaration of `{0}` follows non-static declaration",
+                "static でない宣言の後に `{0}` が static で宣言されています",
+                vec![name.clone()],
+            ),
+            NonStaticFollowsStatic(name) => (
+                "non-static declaration of `{0}` follows static declaration",
+                "static 宣言の後に `{0}` が static でなく宣言されています",
+                vec![name.clone()],
+            ),
+            NegativeDesignator => (
+                "array designator value is negative",
+                "配列指示子の値が負です",
+                vec![],
+            ),
+            DesignatorOutOfBounds(index) => (
+                "array designator index ({0}) exceeds array bounds",
+                "配列指示子の添字 ({0}) が配列の範囲を超えています",
+                vec![index.to_string()],
+            ),
+            NonConstantDesignator => (
+                "array designator is not an integer constant expression",
+                "配列指示子が整数定数式ではありません",
+                vec![],
+            ),
+            ArrayDesignatorMismatch(ty) => (
+                "array designator cannot initialize {0}",
+                "配列指示子で {0} は初期化できません",
+                vec![ty.to_string()],
+            ),
+            FieldDesignatorMismatch(name, ty) => (
+                "field designator `{0}` cannot initialize {1}",
+                "フィールド指示子 `{0}` で {1} は初期化できません",
+                vec![name.clone(), ty.to_string()],
+            ),
+            DesignatorForScalar(ty) => (
+                "designator in initializer for scalar type {0}",
+                "スカラー型 {0} の初期化子に指示子があります",
+                vec![ty.to_string()],
+            ),
+            UnknownField(name) => (
+                "field designator `{0}` does not refer to any field",
+                "フィールド指示子 `{0}` に対応するフィールドがありません",
+                vec![name.clone()],
+            ),
+            EmptyScalarInitializer => (
+                "scalar initializer cannot be empty",
+                "スカラーの初期化子は空にできません",
+                vec![],
+            ),
+            ExcessScalarElements => (
+                "excess elements in scalar initializer",
+                "スカラーの初期化子の要素が多すぎます",
+                vec![],
+            ),
+            ExcessElements(ty) => (
+                "excess elements in initializer of {0}",
+                "{0} の初期化子の要素が多すぎます",
+                vec![ty.to_string()],
+            ),
+            StringTooLong => (
+                "initializer-string for char array is too long",
+                "char 配列の初期化文字列が長すぎます",
+                vec![],
+            ),
+            BracesRequired(ty) => (
+                "initializing {0} requires a brace-enclosed initializer list",
+                "{0} の初期化には波括弧で囲まれた初期化子リストが必要です",
+                vec![ty.to_string()],
+            ),
+            NonConstantInitializer => (
+                "initializer element is not a compile-time constant",
+                "初期化子の要素がコンパイル時定数ではありません",
+                vec![],
+            ),
+            UndeclaredIdentifier(name) => (
+                "use of undeclared identifier `{0}`",
+                "宣言されていない識別子 `{0}` が使われています",
+                vec![name.clone()],
+            ),
+            IncompatibleTypes(Conversion::Argument(idx, function), to, from) => (
+                "incompatible type for argument {0} of `{1}` ({2} from {3})",
+                "`{1}` の引数 {0} の型に互換性がありません ({3} から {2})",
+                vec![
+                    idx.to_string(),
+                    function.clone(),
+                    to.to_string(),
+                    from.to_string(),
+                ],
+            ),
+            IncompatibleTypes(conversion, to, from) => {
+                let conversion = match conversion {
+                    Conversion::Assignment => pick(language, "assignment", "代入"),
+                    Conversion::Initialization => pick(language, "initialization", "初期化"),
+                    _ => pick(language, "return", "return 文"),
+                };
+                (
+                    "incompatible types in {0} ({1} from {2})",
+                    "{0}の型に互換性がありません ({2} から {1})",
+                    vec![conversion.to_owned(), to.to_string(), from.to_string()],
+                )
+            }
+            NotAssignable => ("expression is not assignable", "式に代入できません", vec![]),
+            ArrayNotAssignable => (
+                "array type is not assignable",
+                "配列型には代入できません",
+                vec![],
+            ),
+            ConstVariable(name) => (
+                "cannot assign to variable `{0}` with const-qualified type",
+                "const 修飾された型の変数 `{0}` には代入できません",
+                vec![name.clone()],
+            ),
+            ConstExpression => (
+                "cannot assign to expression with const-qualified type",
+                "const 修飾された型の式には代入できません",
+                vec![],
+            ),
+            ConstMember(name) => (
+                "cannot assign to structure with const-qualified member `{0}`",
+                "const 修飾されたメンバ `{0}` を持つ構造体には代入できません",
+                vec![name.clone()],
+            ),
+            AddressOfRvalue => (
+                "cannot take the address of an rvalue",
+                "右辺値のアドレスは取れません",
+                vec![],
+            ),
+            MemberOfRvalue => (
+                "member access of non-lvalue is not supported",
+                "左辺値でない式のメンバアクセスはサポートされていません",
+                vec![],
+            ),
+            InvalidOperands(lhs, rhs) => (
+                "invalid operands to binary expression ({0} and {1})",
+                "二項演算子のオペランドが不正です ({0} と {1})",
+                vec![lhs.to_string(), rhs.to_string()],
+            ),
+            InvalidRemainderOperands(ty) => (
+                "invalid operands to binary `%` ({0})",
+                "二項演算子 `%` のオペランドが不正です ({0})",
+                vec![ty.to_string()],
+            ),
+            InvalidUnaryOperand(ty) => (
+                "invalid argument type {0} to unary expression",
+                "単項演算子のオペランドの型 {0} が不正です",
+                vec![ty.to_string()],
+            ),
+            NonPointerIndirection(ty) => (
+                "indirection requires pointer operand ({0} invalid)",
+                "間接参照にはポインタのオペランドが必要です ({0} は不正)",
+                vec![ty.to_string()],
+            ),
+            VoidIndirection => (
+                "indirection of pointer to `void`",
+                "`void` へのポインタを間接参照しています",
+                vec![],
+            ),
+            InvalidCast(from, to) => (
+                "cannot cast {0} to {1}",
+                "{0} を {1} にキャストできません",
+                vec![from.to_string(), to.to_string()],
+            ),
+            NonScalarCast(ty) => (
+                "used type {0} where arithmetic or pointer type is required",
+                "算術型かポインタ型が必要な場所で型 {0} が使われています",
+                vec![ty.to_string()],
+            ),
+            NotFunction(ty) => (
+                "called object type {0} is not a function",
+                "呼び出されたオブジェクトの型 {0} は関数ではありません",
+                vec![ty.to_string()],
+            ),
+            NotCallable(ty) => (
+                "called object type {0} is not a function or function pointer",
+                "呼び出されたオブジェクトの型 {0} は関数でも関数ポインタでもありません",
+                vec![ty.to_string()],
+            ),
+            TooManyArguments(function) => (
+                "too many arguments to function call of `{0}`",
+                "`{0}` の呼び出しの引数が多すぎます",
+                vec![function.clone()],
+            ),
+            TooFewArguments(function) => (
+                "too few arguments to function call of `{0}`",
+                "`{0}` の呼び出しの引数が少なすぎます",
+                vec![function.clone()],
+            ),
+            VoidArgument => (
+                "argument may not have `void` type",
+                "引数は `void` 型を持てません",
+                vec![],
+            ),
+            NonIntegerSubscript => (
+                "array subscript is not an integer",
+                "配列の添字が整数ではありません",
+                vec![],
+            ),
+            NotSubscriptable => (
+                "subscripted value is not an array or pointer",
+                "添字を付けられた値が配列でもポインタでもありません",
+                vec![],
+            ),
+            NoMember(name, tag) => (
+                "no member named `{0}` in `{1}`",
+                "`{1}` に `{0}` という名前のメンバはありません",
+                vec![name.clone(), tag.clone()],
+            ),
+            NotStruct => (
+                "member reference base type is not a structure",
+                "メンバ参照の対象の型が構造体ではありません",
+                vec![],
+            ),
+            VoidReturnValue => (
+                "void function should not return a value",
+                "void 関数は値を返すべきではありません",
+                vec![],
+            ),
+            MissingReturnValue => (
+                "non-void function should return a value",
+                "void でない関数は値を返すべきです",
+                vec![],
+            ),
+            UnreachableCode => (
+                "code will never be executed",
+                "このコードは決して実行されません",
+                vec![],
+            ),
+            UnusedVariable(name) => (
+                "unused variable `{0}`",
+                "変数 `{0}` は使われていません",
+                vec![name.clone()],
+            ),
+            DivisionByZero(what) => (
+                "{0} by zero is undefined",
+                "ゼロによる{0}は未定義です",
+                vec![term(what)],
+            ),
+            Overflow => (
+                "signed integer overflow in constant expression",
+                "定数式で符号付き整数のオーバーフローが起きます",
+                vec![],
+            ),
+            MissingReturn(name) => (
+                "non-void function `{0}` does not return a value",
+                "void でない関数 `{0}` が値を返していません",
+                vec![name.clone()],
+            ),
+        };
+        fill(pick(language, en, ja), &args)
+    }
+}
+
+/// the notes attached to diagnostics, which have no code
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Note {
+    PreviousDefinition,
+    PreviousDeclaration,
+
+    /// a fix-it inserting the text
+    Insert(String),
+
+    /// a fix-it replacing the span with the text
+    Replace(String),
+}
+
+impl Note {
+    /// the message in `language`
+    pub fn message(&self, language: Language) -> String {
+        let (en, ja, args) = match self {
+            Note::PreviousDefinition => (
+                "previous definition is here",
+                "以前の定義はここです",
+                vec![],
+            ),
+            Note::PreviousDeclaration => (
+                "previous declaration is here",
+                "以前の宣言はここです",
+                vec![],
+            ),
+            Note::Insert(text) => (
+                "insert `{0}`",
+                "`{0}` を挿入してください",
+                vec![text.clone()],
+            ),
+            Note::Replace(text) => (
+                "replace with `{0}`",
+                "`{0}` に置き換えてください",
+                vec![text.clone()],
+            ),
+        };
+        fill(pick(language, en, ja), &args)
+    }
+}
+
+fn pick<'a>(language: Language, en: &'a str, ja: &'a str) -> &'a str {
+    match language {
+        Language::English => en,
+        Language::Japanese => ja,
+    }
+}
+
+/// fills `{0}`, `{1}`, ... in `template` with `args`.  the arguments themselves are not
+/// scanned, so they may contain braces.
+fn fill(template: &str, args: &[String]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let placeholder = rest[start + 1..].find('}').and_then(|end| {
+            let index = rest[start + 1..start + 1 + end].parse::<usize>().ok()?;
+            Some((index, start + 1 + end + 1))
+        });
+        match placeholder {
+            Some((index, next)) => {
+                filled.push_str(&args[index]);
+                rest = &rest[next..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// the long explanation of `code` with an example, for `--explain`
+pub fn explain(code: &str, language: Language) -> Option<&'static str> {
+    explanations::EXPLANATIONS
+        .iter()
+        .find(|&&(name, _, _)| name == code)
+        .map(|&(_, en, ja)| pick(language, en, ja))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiagnosticKind::*;
+    use super::*;
+
+    #[test]
+    fn codes() {
+        let name = || "x".to_owned();
+        // a kind of each code
+        let kinds = vec![
+            InvalidCharacter('@'),
+            InvalidNumber(name()),
+            UnterminatedString,
+            UnterminatedComment,
+            UnknownEscape('q'),
+            IntegerTooLarge(name()),
+            Expected(Term::Expression, None),
+            Unclosed(Term::Parens, Term::Cast),
+            MissingSemicolon(Term::JumpStatement),
+            MissingSemicolonInStruct,
+            MissingEqualsAfterDesignator,
+            MissingIdentifier,
+            Expected(Term::TypeSpecifier, None),
+            UnnamedParameterBeforeEllipsis,
+            IdentifierInTypeName(name()),
+            MultipleStorageClasses,
+            StructAfterStruct,
+            LongDouble,
+            StorageClassInTypeName,
+            InvalidParameterStorageClass,
+            VoidParameter,
+            ParameterNameOmitted(name()),
+            ZeroSizeArray,
+            ArrayOfFunctions,
+            FunctionReturningArray,
+            IncompleteVariable(name(), Type::Void),
+            Redefinition(name()),
+            ConflictingTypes(name()),
+            DifferentKindOfSymbol(name()),
+            DuplicateMember(name()),
+            EmptyStruct,
+            AnonymousStructDeclaration,
+            StructInFunction,
+            StaticBlockFunction(name()),
+            ExternWithInitializer(name()),
+            NonVariableInitializer(name()),
+            StaticFollowsNonStatic(name()),
+            NegativeDesignator,
+            DesignatorForScalar(Type::Void),
+            UnknownField(name()),
+            EmptyScalarInitializer,
+            ExcessScalarElements,
+            StringTooLong,
+            BracesRequired(Type::Void),
+            NonConstantInitializer,
+            UndeclaredIdentifier(name()),
+            IncompatibleTypes(Conversion::Return, Type::Void, Type::Void),
+            NotAssignable,
+            ConstExpression,
+            ConstMember(name()),
+            AddressOfRvalue,
+            InvalidOperands(Type::Void, Type::Void),
+            InvalidUnaryOperand(Type::Void),
+            VoidIndirection,
+            InvalidCast(Type::Void, Type::Void),
+            NonScalarCast(Type::Void),
+            NotCallable(Type::Void),
+            TooFewArguments(name()),
+            VoidArgument,
+            NotSubscriptable,
+            NotStruct,
+            VoidReturnValue,
+            MissingReturnValue,
+            UnreachableCode,
+            UnusedVariable(name()),
+            DivisionByZero(Term::Division),
+            Overflow,
+            MissingReturn(name()),
+        ];
+        assert_eq!(UndeclaredIdentifier(name()).code(), "E0401");
+        assert_eq!(Expected(Term::Expression, None).code(), "E0101");
+        assert_eq!(Expected(Term::TypeSpecifier, None).code(), "E0107");
+        assert_eq!(
+            UnusedVariable(name()).warning(),
+            Some(Warning::UnusedVariable)
+        );
+        assert_eq!(StringTooLong.warning(), None);
+
+        // every code is explained, and every explanation is of a code
+        for kind in &kinds {
+            let code = kind.code();
+            assert!(explain(code, Language::English).is_some(), "{}", code);
+            assert!(explain(code, Language::Japanese).is_some(), "{}", code);
+        }
+        for &(name, en, ja) in explanations::EXPLANATIONS {
+            assert!(kinds.iter().any(|kind| kind.code() == name), "{}", name);
+            assert!(en.starts_with(name) && ja.starts_with(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn translations() {
+        let (en, ja) = (Language::English, Language::Japanese);
+        let unclosed = Unclosed(Term::Parens, Term::PrimaryExpression);
+        assert_eq!(
+            unclosed.message(en),
+            "no matching parens for primary expression."
+        );
+        assert_eq!(unclosed.message(ja), "一次式の括弧が閉じられていません。");
+        let expected = Expected(Term::SemicolonAfterDeclaration, Some("}".to_owned()));
+        assert_eq!(
+            expected.message(en),
+            "expected `;` after declaration, found `}`"
+        );
+        assert_eq!(
+            expected.message(ja),
+            "宣言の後の `;` が必要ですが `}` がありました"
+        );
+        assert_eq!(
+            Expected(Term::CompoundStatement, Some("{0}".to_owned())).message(en),
+            "expected compound statement (`{`), found `{0}`"
+        );
+        assert_eq!(
+            NoMember("x".to_owned(), "s".to_owned()).message(ja),
+            "`s` に `x` という名前のメンバはありません"
+        );
+        assert_eq!(
+            IncompatibleTypes(Conversion::Return, Type::Void, Type::Void).message(ja),
+            "return 文の型に互換性がありません (void から void)"
+        );
+        assert_eq!(
+            Note::Insert(";".to_owned()).message(ja),
+            "`;` を挿入してください"
+        );
+        assert_eq!(Language::from_locale("ja_JP.UTF-8"), ja);
+        assert_eq!(Language::from_locale("C"), Language::English);
+    }
+}
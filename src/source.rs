@@ -0,0 +1,219 @@
+use regex::Regex;
+
+/// a range of bytes in `Source::text()`
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    /// the span from the start of this span to the end of `other`
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end.max(self.start))
+    }
+}
+
+/// a line marker (`# 12 "foo.h" 1`) left by the preprocessor, which tells that the next line is
+/// the line `line` of `file`
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct LineMarker {
+    /// the 1-origin line of the marker in the original file
+    at: usize,
+
+    line: usize,
+    file: String,
+}
+
+impl LineMarker {
+    fn parse(at: usize, text: &str) -> Option<LineMarker> {
+        lazy_static! {
+            static ref MARKER: Regex =
+                Regex::new(r#"^#\s*(\d+)\s+"((?:[^"\\]|\\.)*)"(?:\s+\d+)*\s*$"#).unwrap();
+        }
+        let captures = MARKER.captures(text)?;
+        let line = captures[1].parse().ok()?;
+        let mut file = String::new();
+        let mut chars = captures[2].chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => file.extend(chars.next()),
+                ch => file.push(ch),
+            }
+        }
+        Some(LineMarker { at, line, file })
+    }
+}
+
+/// a source file after translation phase 2 (line splicing).
+///
+/// the lexer works on `text`, from which every backslash immediately followed by a new-line has
+/// been deleted.  positions in `text` can be mapped back to the original file with `location()`.
+/// the line markers of preprocessed source are blanked out in `text`, and the lines after them
+/// are mapped to the files they came from with `presumed_line()`.
+pub struct Source {
+    /// the file as it was read
+    original: String,
+
+    /// the file after line splicing
+    text: String,
+
+    /// (offset in `text`, total bytes deleted up to and including this splice)
+    splices: Vec<(usize, usize)>,
+
+    /// the line markers in order
+    markers: Vec<LineMarker>,
+}
+
+impl Source {
+    pub fn new(original: String) -> Source {
+        // line markers are replaced with spaces so that the offsets stay the same
+        let mut markers = Vec::new();
+        let mut unmarked = String::with_capacity(original.len());
+        for (idx, line) in original.split_inclusive('\n').enumerate() {
+            let content = line.trim_end_matches(['\r', '\n']);
+            match LineMarker::parse(idx + 1, content) {
+                Some(marker) => {
+                    markers.push(marker);
+                    unmarked.push_str(&" ".repeat(content.len()));
+                    unmarked.push_str(&line[content.len()..]);
+                }
+                None => unmarked.push_str(line),
+            }
+        }
+
+        let mut text = String::with_capacity(original.len());
+        let mut splices = Vec::new();
+        let mut deleted = 0;
+        let mut rest = &unmarked[..];
+
+        while let Some(backslash) = rest.find('\\') {
+            text.push_str(&rest[..backslash]);
+            let after = &rest[backslash + 1..];
+            let newline_len = if after.starts_with('\n') {
+                1
+            } else if after.starts_with("\r\n") {
+                2
+            } else {
+                0
+            };
+
+            if newline_len == 0 {
+                text.push('\\');
+            } else {
+                deleted += 1 + newline_len;
+                splices.push((text.len(), deleted));
+            }
+            rest = &after[newline_len..];
+        }
+        text.push_str(rest);
+
+        Source {
+            original,
+            text,
+            splices,
+            markers,
+        }
+    }
+
+    /// the file as it was read
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// the text the lexer should see
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// converts an offset in `text()` into an offset in the original file
+    pub fn original_offset(&self, pos: usize) -> usize {
+        let deleted = self
+            .splices
+            .iter()
+            .take_while(|&&(at, _)| at <= pos)
+            .last()
+            .map(|&(_, deleted)| deleted)
+            .unwrap_or(0);
+        pos + deleted
+    }
+
+    /// returns the 1-origin (line, column) of an offset in `text()`.  columns count characters.
+    pub fn location(&self, pos: usize) -> (usize, usize) {
+        let pos = self.original_offset(pos);
+        let before = &self.original[..pos];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|nl| nl + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+
+    /// the file and the line that the `line`-th (1-origin) line of the original file came from,
+    /// as told by the last line marker before it.  the file is None without a marker.
+    pub fn presumed_line(&self, line: usize) -> (Option<&str>, usize) {
+        match self
+            .markers
+            .iter()
+            .take_while(|marker| marker.at < line)
+            .last()
+        {
+            Some(marker) => (Some(&marker.file), marker.line + line - marker.at - 1),
+            None => (None, line),
+        }
+    }
+
+    /// the `line`-th (1-origin) line of the original file, without the new-line
+    pub fn line(&self, line: usize) -> &str {
+        let text = self.original.split('\n').nth(line - 1).unwrap_or("");
+        text.strip_suffix('\r').unwrap_or(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splice_lines() {
+        let source = Source::new("ret\\\nurn 4\\\r\n2;\\ \n".to_owned());
+        assert_eq!(source.text(), "return 42;\\ \n");
+    }
+
+    #[test]
+    fn location_after_splice() {
+        let source = Source::new("int\\\n x;\ny".to_owned());
+        assert_eq!(source.text(), "int x;\ny");
+        assert_eq!(source.location(0), (1, 1));
+        assert_eq!(source.location(4), (2, 2));
+        assert_eq!(source.location(7), (3, 1));
+    }
+
+    #[test]
+    fn line_markers() {
+        let text = "# 1 \"a.c\"\nint x;\n# 7 \"dir\\\\b \\\"1\\\".h\" 1 3\n\ny;\n";
+        let source = Source::new(text.to_owned());
+        assert_eq!(
+            source.text(),
+            "         \nint x;\n                        \n\ny;\n"
+        );
+        assert_eq!(source.presumed_line(2), (Some("a.c"), 1));
+        assert_eq!(source.presumed_line(5), (Some("dir\\b \"1\".h"), 8));
+        assert_eq!(source.line(5), "y;");
+
+        let source = Source::new("#define X 1\nX;".to_owned());
+        assert_eq!(source.text(), "#define X 1\nX;");
+        assert_eq!(source.presumed_line(2), (None, 2));
+    }
+
+    #[test]
+    fn lines() {
+        let source = Source::new("int x;\r\nreturn x;\n".to_owned());
+        assert_eq!(source.line(1), "int x;");
+        assert_eq!(source.line(2), "return x;");
+        assert_eq!(source.line(3), "");
+    }
+}
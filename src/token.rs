@@ -1,7 +1,13 @@
+use std::fmt;
+
 use regex::Regex;
 
+use diagnostic::{FixIt, ParseError, ParseResult};
+use message::{DiagnosticKind, Term};
+use source::Span;
+
 macro_rules! define_tokens {
-    ($(literal $lname:ident: $lmatcher:expr;)* $(regex ($rregex_name:ident) $rname:ident: $rmatcher:expr;)*) => {
+    ($(literal $lname:ident: $lspelling:expr $(, $lmatcher:expr)*;)* $(regex ($rregex_name:ident) $rname:ident: $rmatcher:expr;)*) => {
         #[derive(Debug, Clone, Copy, Eq, PartialEq)]
         pub enum Token<'a> {
             $($lname,)*
@@ -9,10 +15,12 @@ macro_rules! define_tokens {
         }
 
         impl<'a> Token<'a> {
+            // not `FromStr`, whose error type would be meaningless here
+            #[allow(clippy::should_implement_trait)]
             pub fn from_str(token_str: &'a str) -> Option<Token<'a>> {
                 match token_str {
                     $(
-                        $lmatcher => return Some(Token::$lname),
+                        $lspelling $(| $lmatcher)* => return Some(Token::$lname),
                     )*
                     _ => {},
                 }
@@ -27,6 +35,17 @@ macro_rules! define_tokens {
             }
         }
 
+        /// the spelling of the token in the source.  the first one is used for a punctuator
+        /// with alternative spellings.
+        impl<'a> fmt::Display for Token<'a> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                match *self {
+                    $(Token::$lname => f.write_str($lspelling),)*
+                    $(Token::$rname(s) => f.write_str(s),)*
+                }
+            }
+        }
+
         lazy_static! {
             $(
                 static ref $rregex_name: Regex = Regex::new($rmatcher).unwrap();
@@ -36,45 +55,102 @@ macro_rules! define_tokens {
 }
 
 define_tokens! {
-    literal TyInt: "int";
     literal TyVoid: "void";
+    literal TyChar: "char";
+    literal TyShort: "short";
+    literal TyInt: "int";
+    literal TyLong: "long";
+    literal TyFloat: "float";
+    literal TyDouble: "double";
+    literal TySigned: "signed";
+    literal TyUnsigned: "unsigned";
 
     literal KwReturn: "return";
+    literal KwStatic: "static";
+    literal KwExtern: "extern";
+    literal KwConst: "const";
+    literal KwVolatile: "volatile";
+    literal KwStruct: "struct";
 
     literal OpAdd: "+";
     literal OpSub: "-";
     literal OpMul: "*";
     literal OpDiv: "/";
     literal OpRem: "%";
+    literal OpAnd: "&";
+
+    literal OpLt: "<";
+    literal OpGt: ">";
+    literal OpLe: "<=";
+    literal OpGe: ">=";
+    literal OpEq: "==";
+    literal OpNe: "!=";
 
     literal OpAssign: "=";
 
     literal SyLPar: "(";
     literal SyRPar: ")";
-    literal SyLBrace: "{";
-    literal SyRBrace: "}";
+    literal SyLBracket: "[", "<:";
+    literal SyRBracket: "]", ":>";
+    literal SyLBrace: "{", "<%";
+    literal SyRBrace: "}", "%>";
     literal SySemicolon: ";";
+    literal SyComma: ",";
+    literal SyDot: ".";
+    literal SyEllipsis: "...";
+    literal SyHash: "#", "%:";
+    literal SyHashHash: "##", "%:%:";
 
     regex (RE_IDENT) Ident: "^[a-zA-Z_][a-zA-Z0-9_]*$";
-    regex (RE_LITERAL) Literal: "^[0-9]+$";
+    regex (RE_LITERAL) Literal: concat!(
+        "^(0[xX][0-9a-fA-F]+|0[bB][01]+|0[0-7]*|[1-9][0-9]*)",
+        "([uU](ll|LL|[lL])?|(ll|LL|[lL])[uU]?)?$"
+    );
+    regex (RE_FLOAT_LITERAL) FloatLiteral: concat!(
+        "^((([0-9]*\\.[0-9]+|[0-9]+\\.)([eE][+-]?[0-9]+)?|[0-9]+[eE][+-]?[0-9]+)",
+        "|0[xX]([0-9a-fA-F]*\\.[0-9a-fA-F]+|[0-9a-fA-F]+\\.?)[pP][+-]?[0-9]+)[fFlL]?$"
+    );
+    regex (RE_STRING_LITERAL) StringLiteral: r#"^"([^"\\\n]|\\.)*"$"#;
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct Tokens<'a> {
     tokens: &'a [Token<'a>],
+
+    /// the span of each token.  empty if unknown.
+    spans: &'a [Span],
+
+    /// the span of the last token read
+    prev: Span,
 }
 
 impl<'a> Tokens<'a> {
+    /// tokens without location information
+    #[cfg(test)]
     pub fn new(tokens: &'a [Token<'a>]) -> Tokens<'a> {
-        Tokens { tokens }
+        Tokens::with_spans(tokens, &[])
+    }
+
+    pub fn with_spans(tokens: &'a [Token<'a>], spans: &'a [Span]) -> Tokens<'a> {
+        Tokens {
+            tokens,
+            spans,
+            prev: Span::default(),
+        }
     }
 }
 
 impl<'a> Tokens<'a> {
+    // the parser also looks back with `prev`, so this is not an `Iterator`
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<Token<'a>> {
         let res = self.peek();
         if res.is_some() {
+            self.prev = self.span();
             self.tokens = &self.tokens[1..];
+            if !self.spans.is_empty() {
+                self.spans = &self.spans[1..];
+            }
         }
         res
     }
@@ -83,18 +159,70 @@ impl<'a> Tokens<'a> {
         self.tokens.iter().next().cloned()
     }
 
-    pub fn eat(&mut self, expect: Token<'a>) {
-        self.eat_impl(expect, None);
+    /// the span of the next token, or the empty span just after the last token at the end
+    pub fn span(&self) -> Span {
+        match self.spans.first() {
+            Some(&span) => span,
+            None => Span::new(self.prev.end, self.prev.end),
+        }
+    }
+
+    /// the span of the last token read, which is the end of a node just parsed
+    pub fn prev_span(&self) -> Span {
+        self.prev
+    }
+
+    /// an error at the next token, which is not `what` expected
+    pub fn expected(&self, what: Term) -> ParseError {
+        let found = self.peek().map(|token| token.to_string());
+        ParseError::new(self.span(), DiagnosticKind::Expected(what, found))
     }
 
-    pub fn eat_err(&mut self, expect: Token<'a>, msg_if_fails: &str) {
-        self.eat_impl(expect, Some(msg_if_fails));
+    pub fn eat(&mut self, expect: Token<'a>) -> ParseResult<()> {
+        if self.peek() != Some(expect) {
+            return Err(self.expected(Term::Token(expect.to_string())));
+        }
+        self.next();
+        Ok(())
+    }
+
+    pub fn eat_err(&mut self, expect: Token<'a>, kind_if_fails: DiagnosticKind) -> ParseResult<()> {
+        if self.peek() != Some(expect) {
+            return Err(ParseError::new(self.span(), kind_if_fails));
+        }
+        self.next();
+        Ok(())
+    }
+
+    /// eats `expect`, or returns an error of `kind_if_fails` suggesting to insert it just after
+    /// the previous token
+    pub fn eat_or_insert(
+        &mut self,
+        expect: Token<'a>,
+        kind_if_fails: DiagnosticKind,
+    ) -> ParseResult<()> {
+        let insertion = FixIt::insertion(self.prev.end, expect.to_string());
+        self.eat_err(expect, kind_if_fails)
+            .map_err(|error| error.with_fixit(insertion))
     }
 
-    fn eat_impl(&mut self, expect: Token<'a>, msg_if_fails: Option<&str>) {
-        let msg_if_fails = msg_if_fails.unwrap_or("ICE: unexpected token");
-        let token = self.next();
-        assert_eq!(token, Some(expect), "{}", msg_if_fails);
+    /// skips the tokens after a syntax error to resume parsing (panic-mode recovery).  stops
+    /// after the next `;` or `}` outside of the braces skipped, or before a `}` that closes an
+    /// enclosing block.
+    pub fn synchronize(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::SyRBrace if depth == 0 => return,
+                Token::SyLBrace => depth += 1,
+                Token::SyRBrace => depth -= 1,
+                _ => {}
+            }
+            self.next();
+            if depth == 0 && (token == Token::SySemicolon || token == Token::SyRBrace) {
+                return;
+            }
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -112,7 +240,55 @@ mod tests {
         let mut tokens = Tokens::new(&[Token::TyVoid]);
         assert!(!tokens.is_empty());
         assert_eq!(Some(Token::TyVoid), tokens.peek());
-        tokens.eat(Token::TyVoid);
+        tokens.eat(Token::TyVoid).unwrap();
+        assert!(tokens.is_empty());
+    }
+
+    #[test]
+    fn eat_reports_found_token() {
+        let mut tokens = Tokens::new(&[Token::SyRBrace]);
+        let error = tokens.eat(Token::SySemicolon).unwrap_err();
+        assert_eq!(
+            *error.kind,
+            DiagnosticKind::Expected(Term::Token(";".to_owned()), Some("}".to_owned()))
+        );
+        assert_eq!(tokens.peek(), Some(Token::SyRBrace));
+    }
+
+    #[test]
+    fn suggest_insertion() {
+        let tokens = [Token::KwReturn, Token::Literal("1"), Token::SyRBrace];
+        let spans = [Span::new(0, 6), Span::new(7, 8), Span::new(10, 11)];
+        let mut tokens = Tokens::with_spans(&tokens, &spans);
+        tokens.next();
+        tokens.next();
+        let error = tokens
+            .eat_or_insert(
+                Token::SySemicolon,
+                DiagnosticKind::MissingSemicolon(Term::JumpStatement),
+            )
+            .unwrap_err();
+        assert_eq!(error.span, Span::new(10, 11));
+        assert_eq!(error.fixit, Some(FixIt::insertion(8, ";")));
+    }
+
+    #[test]
+    fn synchronize() {
+        let mut tokens = Tokens::new(&[
+            Token::Ident("x"),
+            Token::SyLBrace,
+            Token::SySemicolon,
+            Token::SyRBrace,
+            Token::SyRBrace,
+        ]);
+        tokens.synchronize();
+        assert_eq!(tokens.peek(), Some(Token::SyRBrace));
+        tokens.next();
+        tokens.synchronize();
         assert!(tokens.is_empty());
+
+        let mut tokens = Tokens::new(&[Token::Ident("x"), Token::SySemicolon, Token::TyInt]);
+        tokens.synchronize();
+        assert_eq!(tokens.peek(), Some(Token::TyInt));
     }
 }
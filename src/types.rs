@@ -0,0 +1,540 @@
+use std::fmt;
+
+use ir;
+
+/// integer conversion rank.  `long` and `long long` are both 64 bits wide (LP64), but they are
+/// still distinct types with distinct ranks.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum IntRank {
+    Char,
+    Short,
+    Int,
+    Long,
+    LongLong,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct IntType {
+    pub rank: IntRank,
+    pub signed: bool,
+}
+
+impl IntType {
+    pub const CHAR: IntType = IntType::new(IntRank::Char, true);
+    pub const SHORT: IntType = IntType::new(IntRank::Short, true);
+    pub const INT: IntType = IntType::new(IntRank::Int, true);
+    pub const UINT: IntType = IntType::new(IntRank::Int, false);
+    pub const LONG: IntType = IntType::new(IntRank::Long, true);
+    pub const ULONG: IntType = IntType::new(IntRank::Long, false);
+    pub const LLONG: IntType = IntType::new(IntRank::LongLong, true);
+    pub const ULLONG: IntType = IntType::new(IntRank::LongLong, false);
+
+    pub const fn new(rank: IntRank, signed: bool) -> IntType {
+        IntType { rank, signed }
+    }
+
+    pub fn bits(self) -> u32 {
+        match self.rank {
+            IntRank::Char => 8,
+            IntRank::Short => 16,
+            IntRank::Int => 32,
+            IntRank::Long | IntRank::LongLong => 64,
+        }
+    }
+
+    pub fn max_value(self) -> u64 {
+        let bits = if self.signed {
+            self.bits() - 1
+        } else {
+            self.bits()
+        };
+        u64::MAX >> (64 - bits)
+    }
+
+    pub fn to_unsigned(self) -> IntType {
+        IntType::new(self.rank, false)
+    }
+
+    /// the corresponding IR type
+    pub fn ir_type(self) -> ir::Type {
+        ir::Type::Int(self.bits())
+    }
+
+    /// the name of the corresponding LLVM type
+    pub fn llvm_type(self) -> String {
+        self.ir_type().to_string()
+    }
+
+    /// formats `value` (which must be representable in this type, or be the bit pattern of a
+    /// representable value) as an LLVM constant, which is always written in signed form.
+    pub fn llvm_constant(self, value: u64) -> String {
+        match self.bits() {
+            8 => format!("{}", value as u8 as i8),
+            16 => format!("{}", value as u16 as i16),
+            32 => format!("{}", value as u32 as i32),
+            _ => format!("{}", value as i64),
+        }
+    }
+
+    /// integer promotions (C11 6.3.1.1).  every type narrower than `int` fits in `int`.
+    pub fn promote(self) -> IntType {
+        if self.rank < IntRank::Int {
+            IntType::INT
+        } else {
+            self
+        }
+    }
+
+    /// the type of both operands after the usual arithmetic conversions (C11 6.3.1.8)
+    pub fn common(self, other: IntType) -> IntType {
+        let (this, other) = (self.promote(), other.promote());
+        if this == other {
+            return this;
+        }
+
+        if this.signed == other.signed {
+            return if this.rank >= other.rank { this } else { other };
+        }
+
+        let (signed, unsigned) = if this.signed {
+            (this, other)
+        } else {
+            (other, this)
+        };
+
+        if unsigned.rank >= signed.rank {
+            unsigned
+        } else if signed.bits() > unsigned.bits() {
+            signed
+        } else {
+            signed.to_unsigned()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum FloatType {
+    Float,
+    Double,
+}
+
+impl FloatType {
+    pub fn ir_type(self) -> ir::Type {
+        match self {
+            FloatType::Float => ir::Type::Float,
+            FloatType::Double => ir::Type::Double,
+        }
+    }
+
+    pub fn llvm_type(self) -> String {
+        self.ir_type().to_string()
+    }
+
+    /// formats `value` as an LLVM constant.  LLVM accepts the bit pattern of a double in hex for
+    /// both types, which is exact as long as a `float` value is representable as `float`.
+    pub fn llvm_constant(self, value: f64) -> String {
+        let value = match self {
+            FloatType::Float => f64::from(value as f32),
+            FloatType::Double => value,
+        };
+        format!("0x{:016X}", value.to_bits())
+    }
+}
+
+/// type qualifiers.  the qualifiers of an object itself are kept with the object; those of a
+/// pointed-to type are kept in `Type::Pointer`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Qualifiers {
+    pub is_const: bool,
+    pub is_volatile: bool,
+}
+
+impl Qualifiers {
+    /// whether these qualifiers include all of `other`
+    pub fn contains(self, other: Qualifiers) -> bool {
+        (self.is_const || !other.is_const) && (self.is_volatile || !other.is_volatile)
+    }
+
+    /// the qualifiers of a subobject (an element or a member) of an object
+    pub fn merge(self, other: Qualifiers) -> Qualifiers {
+        Qualifiers {
+            is_const: self.is_const || other.is_const,
+            is_volatile: self.is_volatile || other.is_volatile,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FunctionType {
+    pub ret: Type,
+
+    /// None if the function is declared without a prototype (`int f()`)
+    pub params: Option<Vec<Type>>,
+
+    pub variadic: bool,
+}
+
+impl FunctionType {
+    /// the IR function type.  functions without a prototype are treated as variadic functions
+    /// without fixed parameters, so that any call to them is well-typed.
+    pub fn ir_type(&self) -> ir::Type {
+        let params = self.params.iter().flatten().map(Type::ir_type).collect();
+        let variadic = self.variadic || self.params.is_none();
+        ir::Type::Function(Box::new(self.ret.ir_type()), params, variadic)
+    }
+
+    /// the LLVM function type
+    pub fn llvm_type(&self) -> String {
+        self.ir_type().to_string()
+    }
+
+    /// whether this function type is compatible with `other`.  a function type without a
+    /// prototype is compatible with any function type with a compatible return type.
+    pub fn is_compatible(&self, other: &FunctionType) -> bool {
+        let params_compatible = match (&self.params, &other.params) {
+            (Some(lhs), Some(rhs)) => {
+                self.variadic == other.variadic
+                    && lhs.len() == rhs.len()
+                    && lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs.is_compatible(rhs))
+            }
+            _ => true,
+        };
+        self.ret.is_compatible(&other.ret) && params_compatible
+    }
+
+    /// the parameter types in an LLVM function type, as in `ir_type`
+    pub fn llvm_params(&self) -> String {
+        let mut params: Vec<String> = self
+            .params
+            .iter()
+            .flatten()
+            .map(|param| param.llvm_type())
+            .collect();
+        if self.variadic || self.params.is_none() {
+            params.push("...".to_owned());
+        }
+        params.join(", ")
+    }
+}
+
+/// a member of a structure
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Member {
+    pub name: String,
+    pub ty: Type,
+    pub qualifiers: Qualifiers,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Type {
+    Void,
+    Int(IntType),
+    Float(FloatType),
+    Pointer(Box<Type>, Qualifiers),
+
+    /// None for an array of unknown size (`int a[]`)
+    Array(Box<Type>, Option<usize>),
+
+    /// a structure by its unique name, which is also the name of the LLVM type (`%struct.NAME`).
+    /// the members are kept in `Env`.
+    Struct(String),
+
+    Function(Box<FunctionType>),
+}
+
+impl Type {
+    pub const INT: Type = Type::Int(IntType::INT);
+    pub const DOUBLE: Type = Type::Float(FloatType::Double);
+
+    pub fn is_arithmetic(&self) -> bool {
+        match self {
+            Type::Int(_) | Type::Float(_) => true,
+            Type::Void
+            | Type::Pointer(..)
+            | Type::Array(..)
+            | Type::Struct(_)
+            | Type::Function(_) => false,
+        }
+    }
+
+    pub fn is_scalar(&self) -> bool {
+        matches!(self, Type::Int(_) | Type::Float(_) | Type::Pointer(..))
+    }
+
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self, Type::Array(..) | Type::Struct(_))
+    }
+
+    pub fn is_function_pointer(&self) -> bool {
+        match self {
+            Type::Pointer(pointee, _) => matches!(**pointee, Type::Function(_)),
+            _ => false,
+        }
+    }
+
+    /// whether this type is compatible with `other` (C11 6.2.7).  the qualifiers of pointed-to
+    /// types are not compared, and an array of unknown size is compatible with arrays of any
+    /// size.
+    pub fn is_compatible(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Pointer(lhs, _), Type::Pointer(rhs, _)) => lhs.is_compatible(rhs),
+            (Type::Array(lhs, lhs_len), Type::Array(rhs, rhs_len)) => {
+                lhs.is_compatible(rhs)
+                    && (lhs_len.is_none() || rhs_len.is_none() || lhs_len == rhs_len)
+            }
+            (Type::Function(lhs), Type::Function(rhs)) => lhs.is_compatible(rhs),
+            _ => self == other,
+        }
+    }
+
+    /// the type of an expression of this type after array-to-pointer conversion.  `qualifiers`
+    /// are those of the array object, which apply to its elements.
+    pub fn decay(&self, qualifiers: Qualifiers) -> Type {
+        match self {
+            Type::Array(element, _) => Type::Pointer(element.clone(), qualifiers),
+            other => other.clone(),
+        }
+    }
+
+    pub fn ir_type(&self) -> ir::Type {
+        match self {
+            Type::Void => ir::Type::Void,
+            Type::Int(ty) => ty.ir_type(),
+            Type::Float(ty) => ty.ir_type(),
+            // LLVM has no `void*`
+            Type::Pointer(pointee, _) if **pointee == Type::Void => ir::Type::I8.pointer(),
+            Type::Pointer(pointee, _) => pointee.ir_type().pointer(),
+            Type::Array(element, Some(len)) => ir::Type::Array(Box::new(element.ir_type()), *len),
+            Type::Array(_, None) => panic!("ICE: array has incomplete type {:?}", self),
+            Type::Struct(name) => ir::Type::Struct(name.clone()),
+            Type::Function(function) => function.ir_type(),
+        }
+    }
+
+    pub fn llvm_type(&self) -> String {
+        self.ir_type().to_string()
+    }
+
+    /// the zero value of this type, as an LLVM constant
+    pub fn llvm_zero(&self) -> String {
+        match self {
+            Type::Int(_) => "0".to_owned(),
+            Type::Float(_) => "0.0".to_owned(),
+            Type::Pointer(..) => "null".to_owned(),
+            Type::Array(..) | Type::Struct(_) => "zeroinitializer".to_owned(),
+            Type::Void | Type::Function(_) => panic!("ICE: {:?} has no values", self),
+        }
+    }
+
+    /// the type of both operands after the usual arithmetic conversions.  panics if either
+    /// operand is not of arithmetic type.
+    pub fn common(&self, other: &Type) -> Type {
+        match (self, other) {
+            (Type::Int(lhs), Type::Int(rhs)) => Type::Int(lhs.common(*rhs)),
+            (Type::Float(lhs), Type::Float(rhs)) => Type::Float(*lhs.max(rhs)),
+            (Type::Float(ty), Type::Int(_)) | (Type::Int(_), Type::Float(ty)) => Type::Float(*ty),
+            _ => panic!(
+                "invalid operands to binary expression ({} and {})",
+                self, other
+            ),
+        }
+    }
+
+    /// the same as `common`, but returns None if either operand is not of arithmetic type
+    pub fn common_checked(&self, other: &Type) -> Option<Type> {
+        if self.is_arithmetic() && other.is_arithmetic() {
+            Some(self.common(other))
+        } else {
+            None
+        }
+    }
+
+    /// integer promotions, or nothing for non-integer types
+    pub fn promote(&self) -> Type {
+        match self {
+            Type::Int(ty) => Type::Int(ty.promote()),
+            other => other.clone(),
+        }
+    }
+
+    /// default argument promotions (C11 6.5.2.2), applied to arguments of calls without a
+    /// prototype and to variadic arguments.
+    pub fn promote_argument(&self) -> Type {
+        match self {
+            Type::Float(FloatType::Float) => Type::DOUBLE,
+            other => other.promote(),
+        }
+    }
+}
+
+/// the type as it is written in C, such as `unsigned long`, `const char *` and `int [3]`
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Void => write!(f, "void"),
+            Type::Int(ty) => {
+                let name = match ty.rank {
+                    IntRank::Char => "char",
+                    IntRank::Short => "short",
+                    IntRank::Int => "int",
+                    IntRank::Long => "long",
+                    IntRank::LongLong => "long long",
+                };
+                if ty.signed {
+                    write!(f, "{}", name)
+                } else {
+                    write!(f, "unsigned {}", name)
+                }
+            }
+            Type::Float(FloatType::Float) => write!(f, "float"),
+            Type::Float(FloatType::Double) => write!(f, "double"),
+            Type::Pointer(pointee, qualifiers) => {
+                if qualifiers.is_const {
+                    write!(f, "const ")?;
+                }
+                if qualifiers.is_volatile {
+                    write!(f, "volatile ")?;
+                }
+                match **pointee {
+                    Type::Pointer(..) => write!(f, "{}*", pointee),
+                    _ => write!(f, "{} *", pointee),
+                }
+            }
+            Type::Array(element, Some(len)) => write!(f, "{} [{}]", element, len),
+            Type::Array(element, None) => write!(f, "{} []", element),
+            Type::Struct(name) => write!(f, "struct {}", name),
+            Type::Function(function) => {
+                let mut params: Vec<String> = function
+                    .params
+                    .iter()
+                    .flatten()
+                    .map(Type::to_string)
+                    .collect();
+                if function.variadic {
+                    params.push("...".to_owned());
+                }
+                if params.is_empty() && function.params.is_some() {
+                    params.push("void".to_owned());
+                }
+                write!(f, "{} ({})", function.ret, params.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_value() {
+        assert_eq!(IntType::INT.max_value(), i32::MAX as u64);
+        assert_eq!(IntType::UINT.max_value(), u32::MAX as u64);
+        assert_eq!(IntType::LONG.max_value(), i64::MAX as u64);
+        assert_eq!(IntType::ULLONG.max_value(), u64::MAX);
+    }
+
+    #[test]
+    fn usual_arithmetic_conversions() {
+        assert_eq!(IntType::INT.common(IntType::INT), IntType::INT);
+        assert_eq!(IntType::INT.common(IntType::UINT), IntType::UINT);
+        assert_eq!(IntType::UINT.common(IntType::LONG), IntType::LONG);
+        assert_eq!(IntType::LONG.common(IntType::ULONG), IntType::ULONG);
+        assert_eq!(IntType::LLONG.common(IntType::ULONG), IntType::ULLONG);
+        assert_eq!(IntType::LONG.common(IntType::LLONG), IntType::LLONG);
+        assert_eq!(
+            IntType::CHAR
+                .to_unsigned()
+                .common(IntType::SHORT.to_unsigned()),
+            IntType::INT
+        );
+    }
+
+    #[test]
+    fn floating_conversions() {
+        let float = Type::Float(FloatType::Float);
+        assert_eq!(float.common(&Type::Int(IntType::ULLONG)), float);
+        assert_eq!(float.common(&Type::DOUBLE), Type::DOUBLE);
+        assert_eq!(float.promote_argument(), Type::DOUBLE);
+        assert_eq!(Type::Int(IntType::CHAR).promote_argument(), Type::INT);
+    }
+
+    #[test]
+    fn aggregate_types() {
+        let matrix = Type::Array(Box::new(Type::Array(Box::new(Type::INT), Some(3))), Some(2));
+        assert_eq!(matrix.llvm_type(), "[2 x [3 x i32]]");
+        assert_eq!(
+            matrix.decay(Qualifiers::default()),
+            Type::Pointer(
+                Box::new(Type::Array(Box::new(Type::INT), Some(3))),
+                Qualifiers::default()
+            )
+        );
+        assert_eq!(
+            Type::Struct("point".to_owned()).llvm_type(),
+            "%struct.point"
+        );
+    }
+
+    #[test]
+    fn compatible_types() {
+        let function = |params| {
+            Type::Function(Box::new(FunctionType {
+                ret: Type::INT,
+                params,
+                variadic: false,
+            }))
+        };
+        let pointer = |ty, is_const| {
+            Type::Pointer(
+                Box::new(ty),
+                Qualifiers {
+                    is_const,
+                    is_volatile: false,
+                },
+            )
+        };
+        assert!(function(None).is_compatible(&function(Some(vec![Type::DOUBLE]))));
+        assert!(!function(Some(vec![Type::INT])).is_compatible(&function(Some(vec![]))));
+        assert!(pointer(Type::INT, true).is_compatible(&pointer(Type::INT, false)));
+        assert!(!pointer(Type::INT, false).is_compatible(&pointer(Type::DOUBLE, false)));
+        assert!(Type::Array(Box::new(Type::INT), None)
+            .is_compatible(&Type::Array(Box::new(Type::INT), Some(3))));
+    }
+
+    #[test]
+    fn display() {
+        let pointer = |ty, is_const| {
+            Type::Pointer(
+                Box::new(ty),
+                Qualifiers {
+                    is_const,
+                    is_volatile: false,
+                },
+            )
+        };
+        let string = pointer(Type::Int(IntType::CHAR), true);
+        assert_eq!(string.to_string(), "const char *");
+        assert_eq!(pointer(string, false).to_string(), "const char **");
+        assert_eq!(Type::Int(IntType::ULLONG).to_string(), "unsigned long long");
+        assert_eq!(
+            Type::Array(Box::new(Type::DOUBLE), Some(3)).to_string(),
+            "double [3]"
+        );
+        let function = FunctionType {
+            ret: Type::Void,
+            params: Some(vec![]),
+            variadic: false,
+        };
+        assert_eq!(
+            Type::Function(Box::new(function)).to_string(),
+            "void (void)"
+        );
+    }
+
+    #[test]
+    fn float_constants() {
+        assert_eq!(FloatType::Double.llvm_constant(1.5), "0x3FF8000000000000");
+        assert_eq!(FloatType::Float.llvm_constant(0.1), "0x3FB99999A0000000");
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+
+use diagnostic::{Diagnostic, Severity};
+
+/// the kinds of warnings.  each of them is enabled by `-Wname` and disabled by `-Wno-name`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Warning {
+    /// statements after `return` in the same block
+    UnreachableCode,
+
+    /// local variables that are never referred to
+    UnusedVariable,
+
+    /// integer division or remainder by a constant zero
+    DivisionByZero,
+
+    /// signed integer overflow in a constant expression
+    Overflow,
+
+    /// the end of a non-void function reachable without `return`
+    ReturnType,
+}
+
+impl Warning {
+    pub const ALL: [Warning; 5] = [
+        Warning::UnreachableCode,
+        Warning::UnusedVariable,
+        Warning::DivisionByZero,
+        Warning::Overflow,
+        Warning::ReturnType,
+    ];
+
+    /// the name in the command line options
+    pub fn name(self) -> &'static str {
+        match self {
+            Warning::UnreachableCode => "unreachable-code",
+            Warning::UnusedVariable => "unused-variable",
+            Warning::DivisionByZero => "division-by-zero",
+            Warning::Overflow => "overflow",
+            Warning::ReturnType => "return-type",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Warning> {
+        Warning::ALL
+            .iter()
+            .copied()
+            .find(|warning| warning.name() == name)
+    }
+}
+
+/// which warnings are reported, and whether they are errors.  all the warnings are enabled by
+/// default.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    disabled: HashSet<Warning>,
+
+    /// `-Werror`
+    pub is_error: bool,
+}
+
+impl Options {
+    /// applies a `-W` option.  returns false if it is not a known one.
+    pub fn parse_flag(&mut self, flag: &str) -> bool {
+        let name = match flag.strip_prefix("-W") {
+            Some(name) => name,
+            None => return false,
+        };
+        if name == "error" {
+            self.is_error = true;
+            return true;
+        }
+        if name == "no-error" {
+            self.is_error = false;
+            return true;
+        }
+        // as cc does, `-Wall` and `-Wextra` enable all the warnings
+        if name == "all" || name == "extra" {
+            self.disabled.clear();
+            return true;
+        }
+        let (name, enable) = match name.strip_prefix("no-") {
+            Some(name) => (name, false),
+            None => (name, true),
+        };
+        match Warning::from_name(name) {
+            Some(warning) if enable => {
+                self.disabled.remove(&warning);
+                true
+            }
+            Some(warning) => {
+                self.disabled.insert(warning);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// drops the disabled warnings from `diagnostics`, and makes the others errors with
+    /// `-Werror`
+    pub fn apply(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter(|diagnostic| match diagnostic.warning {
+                Some(warning) => !self.disabled.contains(&warning),
+                None => true,
+            })
+            .map(|diagnostic| match diagnostic.warning {
+                Some(_) if self.is_error => Diagnostic {
+                    severity: Severity::Error,
+                    ..diagnostic
+                },
+                _ => diagnostic,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use message::DiagnosticKind;
+    use source::Span;
+
+    #[test]
+    fn flags() {
+        let diagnostics = vec![
+            Diagnostic::new(
+                Span::new(0, 1),
+                DiagnosticKind::UnusedVariable("x".to_owned()),
+            ),
+            Diagnostic::new(Span::new(1, 2), DiagnosticKind::Overflow),
+            Diagnostic::new(Span::new(2, 3), DiagnosticKind::ZeroSizeArray),
+        ];
+
+        let mut options = Options::default();
+        assert!(options.parse_flag("-Wno-unused-variable"));
+        assert!(!options.parse_flag("-Wno-such-warning"));
+        let applied = options.apply(diagnostics.clone());
+        assert_eq!(applied.len(), 2);
+        assert_eq!(applied[0].severity, Severity::Warning);
+
+        assert!(options.parse_flag("-Wunused-variable"));
+        assert!(options.parse_flag("-Werror"));
+        let applied = options.apply(diagnostics.clone());
+        assert_eq!(applied.len(), 3);
+        assert!(applied.iter().all(Diagnostic::is_error));
+
+        assert!(options.parse_flag("-Wno-overflow"));
+        assert!(options.parse_flag("-Wall"));
+        assert_eq!(options.apply(diagnostics).len(), 3);
+    }
+}
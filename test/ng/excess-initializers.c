@@ -0,0 +1,4 @@
+int main(void) {
+    int values[2] = {1, 2, 3};
+    return values[0];
+}
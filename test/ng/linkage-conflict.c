@@ -0,0 +1,10 @@
+int z;
+static int z;
+
+static int w;
+extern int w;
+int w;
+
+int main(void) {
+    return z + w;
+}
@@ -0,0 +1,13 @@
+int f(int a) {
+    int x = a +;
+    x = (a * 2;
+    return x;
+}
+
+int g(void) {
+    return 1
+}
+
+int main(void) {
+    return f(1) + g();
+}
@@ -0,0 +1,18 @@
+void nothing(void) {
+    return 0;
+}
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int main(void) {
+    int x = 1;
+    int *p = &x;
+    x = add(1);
+    p = 1.5;
+    y = 2;
+    x + 1 = 3;
+    nothing(x);
+    return p;
+}
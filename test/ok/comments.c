@@ -0,0 +1,7 @@
+/* block comments, line comments and line splicing */
+int main(void) <%
+    // return 1;
+    return 4 /* * 10 */ * 10 + \
+        2; /* a /* does not nest
+              ... */
+%>
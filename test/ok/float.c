@@ -0,0 +1,8 @@
+int main(void) {
+    float f = 1.5f;
+    double d = 0x1.8p1, e = 2.5e-1;
+    int i = 3;
+    d = d * i + f / e - .5;
+    printf("%f %f\n", f, d);
+    return (d > 14.0) + (f == 1.5) * 2 + (i < f) * 4 + d;
+}
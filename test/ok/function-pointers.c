@@ -0,0 +1,43 @@
+int printf(const char *format, ...);
+
+static int add(int a, int b) {
+    return a + b;
+}
+
+static int mul(int a, int b) {
+    return a * b;
+}
+
+int apply(int (*op)(int, int), int a, int b) {
+    return op(a, b);
+}
+
+int twice(int f(int, int), int x) {
+    return (*f)(x, x);
+}
+
+int (*pick(void))(int, int) {
+    return mul;
+}
+
+struct binop {
+    int id;
+    int (*fn)(int, int);
+};
+
+struct binop table[] = {{1, add}, {2, &mul}};
+int (*current)(int, int) = add;
+
+int main(void) {
+    int (*fp)(int, int) = &mul;
+    int (*ops[2])(int, int) = {add, mul};
+    void *erased = (void *)fp;
+    int (*restored)(int, int) = (int (*)(int, int))erased;
+    int result = apply(fp, 6, 7);
+
+    printf("%d %d\n", apply(add, 1, 2), twice(mul, 5));
+    printf("%d %d\n", ops[0](3, 4), (*ops[1])(3, 4));
+    current = mul;
+    printf("%d %d %d\n", table[1].fn(2, 8), current(2, 9), pick()(table[0].id, 10));
+    return result + restored(1, 1) + (int)2.5;
+}
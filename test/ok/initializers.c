@@ -0,0 +1,30 @@
+int printf(const char *format, ...);
+
+struct point {
+    int x, y;
+};
+
+struct segment {
+    struct point from, to;
+    char label[8];
+};
+
+int primes[] = {2, 3, 5, 7, 11};
+struct point origin = {.y = 4};
+static int sparse[8] = {1, [5] = 6, 7};
+char greeting[] = "hello";
+
+int main(void) {
+    struct segment seg = {{1, 2}, .to.x = 30, 40, "seg"};
+    int grid[2][3] = {1, 2, 3, {4}};
+    double scales[4] = {[2] = 0.5};
+    char name[6] = "abc";
+    struct point points[] = {[1].y = 9, {3, 4}};
+
+    printf("%s %s %d\n", greeting, seg.label, seg.to.y);
+    printf("%d %d %d %d\n", grid[0][2], grid[1][0], grid[1][1], sparse[6]);
+    printf("%s %d\n", name, points[2].x);
+    seg.from.y = seg.from.y + primes[4];
+    return seg.from.x + seg.from.y + seg.to.x + origin.y + sparse[5] + scales[2] * 4 + points[1].y
+        + grid[1][0];
+}
@@ -0,0 +1,7 @@
+int main(void) {
+    return 0x10 + 010 + 0b11 - 1
+        + 3000000000 / 1000000000
+        + 3000000000u / 1000000000u
+        + 0xffffffffu % 10
+        + 10l * 2LL + 1ul;
+}
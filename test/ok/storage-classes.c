@@ -0,0 +1,27 @@
+int printf(const char *format, ...);
+
+extern int total;
+int total = 10;
+static const int step = 3;
+
+static int counter(void) {
+    static int count;
+    count = count + step;
+    return count;
+}
+
+void report(int value) {
+    printf("%d\n", value);
+    return;
+}
+
+int main(void) {
+    extern int total;
+    volatile int sum = 0;
+    const double half = 0.5;
+    counter();
+    counter();
+    sum = counter() + total;
+    report(sum);
+    return sum + half * 4;
+}